@@ -0,0 +1,46 @@
+//! Compares converting a large array element by element against
+//! NbtArray::to_vec, which converts it in bulk. Run with
+//! `cargo run --release --example array_bench`.
+
+use nobility::bin_decode::{Document, Tag};
+use nobility::bin_encode::NbtWriter;
+use std::time::{Duration, Instant};
+
+const ELEMENTS: i64 = 100_000;
+const ITERATIONS: u32 = 200;
+
+fn time<F: FnMut() -> usize>(mut func: F) -> Duration {
+    let start = Instant::now();
+    let mut total = 0;
+    for _ in 0..ITERATIONS {
+        total += std::hint::black_box(func());
+    }
+    assert_eq!(total, ELEMENTS as usize * ITERATIONS as usize);
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let ints = (0..ELEMENTS as i32).collect::<Vec<_>>();
+    let longs = (0..ELEMENTS).collect::<Vec<_>>();
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.field("ints").int_array(&ints);
+    root.field("longs").long_array(&longs);
+    root.finish();
+    let doc = Document::load(std::io::Cursor::new(writer.finish())).unwrap();
+    let (_name, root) = doc.parse().unwrap();
+
+    if let Some(Tag::IntArray(array)) = root.get("ints") {
+        assert_eq!(array.iter().collect::<Vec<_>>(), array.to_vec());
+        let slow = time(|| array.iter().collect::<Vec<_>>().len());
+        let fast = time(|| array.to_vec().len());
+        println!("i32 element-wise: {:?}, to_vec: {:?}", slow, fast);
+    }
+
+    if let Some(Tag::LongArray(array)) = root.get("longs") {
+        assert_eq!(array.iter().collect::<Vec<_>>(), array.to_vec());
+        let slow = time(|| array.iter().collect::<Vec<_>>().len());
+        let fast = time(|| array.to_vec().len());
+        println!("i64 element-wise: {:?}, to_vec: {:?}", slow, fast);
+    }
+}
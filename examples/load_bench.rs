@@ -0,0 +1,44 @@
+//! Compares loading many small uncompressed documents with
+//! Document::load, which checks for gzip first, against
+//! Document::load_uncompressed. Run with
+//! `cargo run --release --example load_bench`.
+
+use nobility::bin_decode::Document;
+use nobility::bin_encode::NbtWriter;
+use std::time::{Duration, Instant};
+
+const DOCUMENTS: usize = 10_000;
+const ITERATIONS: u32 = 20;
+
+fn time<F: FnMut(&[u8]) -> Document>(documents: &[Vec<u8>], mut func: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        for data in documents {
+            std::hint::black_box(func(data));
+        }
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let documents = (0..DOCUMENTS as i32)
+        .map(|i| {
+            let mut writer = NbtWriter::new();
+            let mut root = writer.root("");
+            root.field("xPos").int(i);
+            root.field("zPos").int(-i);
+            root.field("Status").string("full");
+            root.finish();
+            writer.finish()
+        })
+        .collect::<Vec<_>>();
+
+    let sniffed = time(&documents, |data| Document::load(data).unwrap());
+    let direct = time(&documents, |data| {
+        Document::load_uncompressed(data).unwrap()
+    });
+    println!(
+        "{} documents with load: {:?}, load_uncompressed: {:?}",
+        DOCUMENTS, sniffed, direct
+    );
+}
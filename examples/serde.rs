@@ -0,0 +1,22 @@
+use nobility::bin_decode::{from_document, Document};
+use serde::Deserialize;
+use std::fs::File;
+use std::io::Read;
+
+#[derive(Deserialize)]
+struct HelloWorld {
+    name: String,
+}
+
+fn main() {
+    let mut file = File::open("files/hello_world.nbt").expect("File to exist");
+    let mut data = vec![];
+    file.read_to_end(&mut data).expect("Read to succeed");
+    let doc = Document::load(std::io::Cursor::new(data)).unwrap();
+
+    // Deserializes the root compound into the struct. Fields that are
+    // missing or have the wrong type will cause an error.
+    let hello: HelloWorld = from_document(&doc).unwrap();
+
+    println!("name: {}", hello.name);
+}
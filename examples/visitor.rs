@@ -0,0 +1,28 @@
+use nobility::bin_decode::{Document, NbtString, Visitor};
+use std::fs::File;
+use std::io::Read;
+
+/// Adds up every TAG_Int in the document.
+#[derive(Default)]
+struct SumInts {
+    sum: i64,
+}
+
+impl<'a> Visitor<'a> for SumInts {
+    fn visit_int(&mut self, _name: Option<NbtString<'a>>, value: i32) {
+        self.sum += value as i64;
+    }
+}
+
+fn main() {
+    let mut file = File::open("files/bigtest.nbt").expect("File to exist");
+    let mut data = vec![];
+    file.read_to_end(&mut data).expect("Read to succeed");
+    let doc = Document::load(std::io::Cursor::new(data)).unwrap();
+
+    // Only the visitor's state is kept, no tree of tags is built.
+    let mut visitor = SumInts::default();
+    doc.visit(&mut visitor).unwrap();
+
+    println!("sum of ints: {}", visitor.sum);
+}
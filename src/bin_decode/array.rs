@@ -1,7 +1,8 @@
 use crate::bin_decode::{NbtParse, ParseError, Reader};
-use byteorder::{BigEndian, ByteOrder};
+use crate::TagType;
+use alloc::vec::Vec;
+use core::fmt;
 use core::marker::PhantomData;
-use std::fmt;
 
 /// Common representation for TAG_Int_Array, TAG_Long_Array, and
 /// TAG_List with elements of fixed size (Byte, Short, Int, Long, Float,
@@ -20,41 +21,72 @@ pub struct NbtArray<'a, T> {
 
 mod internal {
     use byteorder::{BigEndian, ByteOrder};
-    use std::fmt::Debug;
+    use core::fmt::Debug;
 
-    pub trait NbtPrimitive: Debug + Copy {
+    /// # Safety
+    ///
+    /// Only implemented for plain integers and floats, which have no
+    /// padding and are valid for any bit pattern. [NbtArray::to_vec]
+    /// relies on this to copy the document's bytes into a `Vec<Self>`.
+    ///
+    /// [NbtArray::to_vec]: super::NbtArray::to_vec
+    pub unsafe trait NbtPrimitive: Debug + Copy {
         const SIZE: usize;
 
         fn read(data: &[u8]) -> Self;
+
+        /// Converts a value whose bytes were copied straight out of the
+        /// document from big-endian to the host's byte order.
+        fn to_native(self) -> Self;
     }
 
     macro_rules! create_impl {
-        ($ty:ty, $size:expr, $func:ident) => {
-            impl NbtPrimitive for $ty {
+        ($ty:ty, $size:expr, $func:ident, $to_native:expr) => {
+            unsafe impl NbtPrimitive for $ty {
                 const SIZE: usize = $size;
                 fn read(data: &[u8]) -> Self {
                     BigEndian::$func(data)
                 }
+                fn to_native(self) -> Self {
+                    $to_native(self)
+                }
             }
         };
     }
 
-    create_impl!(i16, 2, read_i16);
-    create_impl!(i32, 4, read_i32);
-    create_impl!(i64, 8, read_i64);
-    create_impl!(f32, 4, read_f32);
-    create_impl!(f64, 8, read_f64);
+    unsafe impl NbtPrimitive for i8 {
+        const SIZE: usize = 1;
+        fn read(data: &[u8]) -> Self {
+            data[0] as i8
+        }
+        fn to_native(self) -> Self {
+            self
+        }
+    }
+
+    create_impl!(i16, 2, read_i16, i16::from_be);
+    create_impl!(i32, 4, read_i32, i32::from_be);
+    create_impl!(i64, 8, read_i64, i64::from_be);
+    create_impl!(f32, 4, read_f32, |value: f32| f32::from_bits(u32::from_be(
+        value.to_bits()
+    )));
+    create_impl!(f64, 8, read_f64, |value: f64| f64::from_bits(u64::from_be(
+        value.to_bits()
+    )));
 }
 
-use internal::NbtPrimitive;
+pub(crate) use internal::NbtPrimitive;
 
 impl<'a, T> NbtParse<'a> for NbtArray<'a, T>
 where
     T: NbtPrimitive,
 {
     fn read(reader: &mut Reader<'a>) -> Result<Self, ParseError> {
-        let length = BigEndian::read_u32(reader.advance(4)?);
-        let data = reader.advance(length as usize * T::SIZE)?;
+        let length = reader.read_array_length()?;
+        let size = length.checked_mul(T::SIZE).ok_or(ParseError::EOF {
+            offset: reader.position,
+        })?;
+        let data = reader.advance(size)?;
         Ok(NbtArray {
             data,
             _phantom: PhantomData,
@@ -66,6 +98,17 @@ impl<'a, T> NbtArray<'a, T>
 where
     T: NbtPrimitive,
 {
+    /// Reads a TAG_List of primitives, which has the same layout as the
+    /// array tags, but a more precise error for invalid lengths.
+    pub(crate) fn read_list(reader: &mut Reader<'a>, element: TagType) -> Result<Self, ParseError> {
+        let length = reader.read_list_length(element)?;
+        let data = reader.advance(length * T::SIZE)?;
+        Ok(NbtArray {
+            data,
+            _phantom: PhantomData,
+        })
+    }
+
     /// Returns the number of elements in the array.
     pub fn len(&self) -> usize {
         self.data.len() / T::SIZE
@@ -87,14 +130,77 @@ where
         }
     }
 
+    /// Returns the elements as they're stored in the document, in
+    /// big-endian byte order. This is useful for passing the data on
+    /// without converting each element.
+    pub fn as_raw_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
     /// Creates a Vec of the contents of this array.
+    ///
+    /// When the underlying bytes are suitably aligned for `T`, they're
+    /// copied into the Vec in one go, and then on little-endian hosts
+    /// each element is byte swapped in place. On big-endian hosts this
+    /// is just a copy. Otherwise, the elements are converted one at a
+    /// time like [NbtArray::get] does.
     pub fn to_vec(&self) -> Vec<T> {
-        let mut v = vec![];
-        v.reserve(self.len());
-        for i in 0..self.len() {
-            v.push(self.get(i).unwrap())
+        let ptr = self.data.as_ptr();
+        if ptr.align_offset(core::mem::align_of::<T>()) != 0 {
+            return self.data.chunks_exact(T::SIZE).map(T::read).collect();
+        }
+        // SAFETY: The pointer is aligned for T, the data is exactly
+        // len() elements long, and NbtPrimitive is only implemented for
+        // types that are valid for any bit pattern.
+        let elements = unsafe { core::slice::from_raw_parts(ptr as *const T, self.len()) };
+        let mut vec = elements.to_vec();
+        if cfg!(target_endian = "little") {
+            for value in &mut vec {
+                *value = value.to_native();
+            }
         }
-        v
+        vec
+    }
+
+    /// Copies as many elements as will fit into `out`, starting from
+    /// the beginning of the array, and returns how many were copied.
+    /// Unlike [NbtArray::to_vec], this allows reusing a buffer instead
+    /// of allocating a new one each time.
+    pub fn copy_into(&self, out: &mut [T]) -> usize {
+        let count = self.len().min(out.len());
+        let chunks = self.data.chunks_exact(T::SIZE);
+        for (slot, chunk) in out[..count].iter_mut().zip(chunks) {
+            *slot = T::read(chunk);
+        }
+        count
+    }
+
+    /// Returns the elements as a fixed-size array if there are exactly
+    /// `N` of them, otherwise returns None. Useful for arrays with a
+    /// known shape, such as packed coordinates or UUIDs.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nobility::bin_decode::IntArray;
+    /// # fn example(array: IntArray) {
+    /// if let Some([x, y, z]) = array.as_array::<3>() {
+    ///     println!("position {}, {}, {}", x, y, z);
+    /// }
+    /// # }
+    /// ```
+    pub fn as_array<const N: usize>(&self) -> Option<[T; N]> {
+        if self.len() != N {
+            return None;
+        }
+        let mut chunks = self.data.chunks_exact(T::SIZE);
+        Some(core::array::from_fn(|_| T::read(chunks.next().unwrap())))
+    }
+
+    /// Same as [NbtArray::to_vec], which now uses the same bulk
+    /// conversion.
+    pub fn to_vec_fast(&self) -> Vec<T> {
+        self.to_vec()
     }
 
     /// Returns an iterator over the elements of the array.
@@ -106,6 +212,16 @@ where
     }
 }
 
+impl<'a, T> Default for NbtArray<'a, T> {
+    /// Creates an empty array.
+    fn default() -> Self {
+        NbtArray {
+            data: &[],
+            _phantom: PhantomData,
+        }
+    }
+}
+
 impl<'a, T> fmt::Debug for NbtArray<'a, T>
 where
     T: NbtPrimitive,
@@ -117,6 +233,18 @@ where
     }
 }
 
+impl<'a, T> IntoIterator for NbtArray<'a, T>
+where
+    T: NbtPrimitive,
+{
+    type Item = T;
+    type IntoIter = NbtArrayIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 /// Iterator over the contents of [NbtArray], yielding the element type.
 pub struct NbtArrayIter<'a, T> {
     array: NbtArray<'a, T>,
@@ -146,12 +274,7 @@ where
     T: NbtPrimitive,
 {
     fn len(&self) -> usize {
-        let len = self.array.len();
-        if self.index < len {
-            len - self.index
-        } else {
-            0
-        }
+        self.array.len().saturating_sub(self.index)
     }
 }
 
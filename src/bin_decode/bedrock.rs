@@ -0,0 +1,103 @@
+use crate::bin_decode::string::encode_java;
+use crate::bin_decode::{read_root_type, read_type, ParseError, ParseOptions, Reader};
+use crate::TagType;
+use byteorder::{ByteOrder, LittleEndian};
+
+/// Converts a little-endian document, as used by Bedrock edition, to
+/// the big-endian format that the rest of the decoder understands.
+/// Strings are converted from UTF-8 to Java's CESU-8 along the way.
+pub(crate) fn to_big_endian(data: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let mut reader = Reader::with_options(data, ParseOptions::default());
+    let mut out = Vec::with_capacity(data.len());
+    read_root_type(&mut reader)?;
+    out.push(TagType::Compound as u8);
+    convert_string(&mut reader, &mut out)?;
+    convert_value(&mut reader, &mut out, TagType::Compound)?;
+    Ok(out)
+}
+
+fn convert_value(reader: &mut Reader, out: &mut Vec<u8>, tag: TagType) -> Result<(), ParseError> {
+    match tag {
+        TagType::End => {
+            return Err(ParseError::UnexpectedEndTag {
+                offset: reader.position,
+            })
+        }
+        TagType::Byte => out.extend_from_slice(reader.advance(1)?),
+        TagType::Short => swap(reader, out, 2)?,
+        TagType::Int | TagType::Float => swap(reader, out, 4)?,
+        TagType::Long | TagType::Double => swap(reader, out, 8)?,
+        TagType::ByteArray => {
+            let length = convert_length(reader, out)?;
+            out.extend_from_slice(reader.advance(length)?);
+        }
+        TagType::String => convert_string(reader, out)?,
+        TagType::List => {
+            let element = read_type(reader)?;
+            out.push(element as u8);
+            let length = convert_length(reader, out)?;
+            reader.enter()?;
+            for _ in 0..length {
+                convert_value(reader, out, element)?;
+            }
+            reader.exit();
+        }
+        TagType::Compound => {
+            reader.enter()?;
+            loop {
+                let tag = read_type(reader)?;
+                out.push(tag as u8);
+                if tag == TagType::End {
+                    break;
+                }
+                convert_string(reader, out)?;
+                convert_value(reader, out, tag)?;
+            }
+            reader.exit();
+        }
+        TagType::IntArray => {
+            for _ in 0..convert_length(reader, out)? {
+                swap(reader, out, 4)?;
+            }
+        }
+        TagType::LongArray => {
+            for _ in 0..convert_length(reader, out)? {
+                swap(reader, out, 8)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Copies a primitive of the given size, reversing its bytes.
+fn swap(reader: &mut Reader, out: &mut Vec<u8>, size: usize) -> Result<(), ParseError> {
+    out.extend(reader.advance(size)?.iter().rev());
+    Ok(())
+}
+
+fn convert_length(reader: &mut Reader, out: &mut Vec<u8>) -> Result<usize, ParseError> {
+    let bytes = reader.advance(4)?;
+    out.extend(bytes.iter().rev());
+    Ok(LittleEndian::read_u32(bytes) as usize)
+}
+
+fn convert_string(reader: &mut Reader, out: &mut Vec<u8>) -> Result<(), ParseError> {
+    let offset = reader.position;
+    let length = LittleEndian::read_u16(reader.advance(2)?) as usize;
+    let bytes = reader.advance(length)?;
+    // Invalid UTF-8 is passed through, so that it can still be decoded
+    // lossily.
+    let bytes = match std::str::from_utf8(bytes) {
+        Ok(string) => encode_java(string),
+        Err(_) => bytes.into(),
+    };
+    if bytes.len() > u16::MAX as usize {
+        return Err(ParseError::StringTooLong {
+            length: bytes.len(),
+            offset,
+        });
+    }
+    out.extend_from_slice(&(bytes.len() as u16).to_be_bytes());
+    out.extend_from_slice(&bytes);
+    Ok(())
+}
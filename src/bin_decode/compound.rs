@@ -1,9 +1,16 @@
-use crate::bin_decode::read_type;
-use crate::bin_decode::{NbtParse, NbtString, ParseError, Reader, Tag};
+use crate::bin_decode::string::encode_java;
+use crate::bin_decode::{outline, read_type};
+use crate::bin_decode::{Cesu8DecodingError, List, NbtParse, NbtString, ParseError, Reader, Tag};
 use crate::TagType;
+use alloc::borrow::{Cow, ToOwned};
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
 use core::ops::Index;
 use core::slice::Iter as SliceIter;
-use std::fmt;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
 
 /// Represents an entry into a [Compound], with a name and a value.
 #[derive(Clone, PartialEq)]
@@ -28,7 +35,8 @@ impl<'a> Entry<'a> {
 ///
 /// # Example
 ///
-/// ```rust
+#[cfg_attr(feature = "std", doc = "```rust")]
+#[cfg_attr(not(feature = "std"), doc = "```ignore")]
 /// # use std::error::Error;
 /// # use nobility::bin_decode::Document;
 /// #
@@ -37,10 +45,8 @@ impl<'a> Entry<'a> {
 /// # let doc = Document::load(input)?;
 /// # let (_name, compound) = doc.parse()?;
 /// #
-/// if let Some(entry) = compound.find_first_key("Health") {
-///     if let Some(health) = entry.value().to_i64() {
-///         println!("Player has {} health", health);
-///     }
+/// if let Some(health) = compound.get("Health").and_then(|tag| tag.to_i64()) {
+///     println!("Player has {} health", health);
 /// }
 /// #
 /// # Ok(())
@@ -53,21 +59,60 @@ pub struct Compound<'a> {
 
 impl<'a> NbtParse<'a> for Compound<'a> {
     fn read(reader: &mut Reader<'a>) -> Result<Self, ParseError> {
-        let mut entries = vec![];
-        loop {
-            let tag = read_type(reader)?;
-            if tag == TagType::End {
-                break;
-            }
-            let name = NbtString::read(reader)?;
-            let value = Tag::read(tag, reader)?;
-            entries.push(Entry { name, value });
-        }
+        let entries = Compound::read_prefix(reader, usize::MAX)?;
         Ok(Compound { entries })
     }
 }
 
 impl<'a> Compound<'a> {
+    /// Creates a compound with no entries.
+    pub fn new() -> Compound<'a> {
+        Compound { entries: vec![] }
+    }
+
+    pub(crate) fn from_entries(entries: Vec<Entry<'a>>) -> Compound<'a> {
+        Compound { entries }
+    }
+
+    /// Reads at most `max_entries` entries of a compound. If there are
+    /// more entries than that, the reader is left in the middle of the
+    /// compound.
+    pub(crate) fn read_prefix(
+        reader: &mut Reader<'a>,
+        max_entries: usize,
+    ) -> Result<Vec<Entry<'a>>, ParseError> {
+        reader.enter()?;
+        let result = Compound::read_entries(reader, max_entries);
+        reader.exit();
+        result
+    }
+
+    fn read_entries(
+        reader: &mut Reader<'a>,
+        max_entries: usize,
+    ) -> Result<Vec<Entry<'a>>, ParseError> {
+        let mut entries = vec![];
+        while entries.len() < max_entries {
+            match Compound::read_entry(reader)? {
+                Some(entry) => entries.push(entry),
+                None => break,
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Reads the next entry of a compound, or None once its TAG_End
+    /// has been reached.
+    pub(crate) fn read_entry(reader: &mut Reader<'a>) -> Result<Option<Entry<'a>>, ParseError> {
+        let tag = read_type(reader)?;
+        if tag == TagType::End {
+            return Ok(None);
+        }
+        let name = NbtString::read(reader)?;
+        let value = Tag::read(tag, reader)?;
+        Ok(Some(Entry { name, value }))
+    }
+
     /// Returns the number of entries.
     pub fn len(&self) -> usize {
         self.entries.len()
@@ -86,16 +131,238 @@ impl<'a> Compound<'a> {
     /// Searches for the first key that matches the input, and returns
     /// it if it exists.
     pub fn find_first_key(&self, key: &str) -> Option<&Entry<'a>> {
-        for entry in &self.entries {
-            if entry.name == key {
-                return Some(entry);
+        self.entries.iter().find(|entry| entry.name == key)
+    }
+
+    /// Same as [Compound::find_first_key], but uses a binary search
+    /// instead of checking every entry. This only works if the entries
+    /// are sorted by the raw bytes of their names, as some producers
+    /// write them, and gives wrong results otherwise. Sorting is only
+    /// checked when debug assertions are enabled.
+    ///
+    /// If the key appears more than once, any one of the matching
+    /// entries may be returned.
+    pub fn find_sorted(&self, key: &str) -> Option<&Entry<'a>> {
+        debug_assert!(
+            self.entries
+                .windows(2)
+                .all(|pair| pair[0].name.as_bytes() <= pair[1].name.as_bytes()),
+            "Compound::find_sorted called on a compound with unsorted keys"
+        );
+        let key = encode_java(key);
+        self.entries
+            .binary_search_by(|entry| entry.name.as_bytes().cmp(&key))
+            .ok()
+            .map(|index| &self.entries[index])
+    }
+
+    /// Returns every entry whose name matches the input. Compounds are
+    /// allowed to contain duplicate keys, so this can be used to find
+    /// all of them.
+    pub fn find_all<'s>(&'s self, key: &'s str) -> impl Iterator<Item = &'s Entry<'a>> + 's {
+        self.entries.iter().filter(move |entry| entry.name == key)
+    }
+
+    /// Searches for the first key that matches the input, and returns
+    /// its value if it exists. Use [Compound::find_first_key] if the
+    /// name is needed as well.
+    pub fn get(&self, key: &str) -> Option<&Tag<'a>> {
+        self.find_first_key(key).map(Entry::value)
+    }
+
+    /// Looks up a key and coerces it to an integer using [Tag::to_i64].
+    pub fn get_i64(&self, key: &str) -> Option<i64> {
+        self.get(key).and_then(Tag::to_i64)
+    }
+
+    /// Looks up a key and coerces it to a f64 using [Tag::to_f64].
+    pub fn get_f64(&self, key: &str) -> Option<f64> {
+        self.get(key).and_then(Tag::to_f64)
+    }
+
+    /// Looks up a key and returns it if it's a string.
+    pub fn get_string(&self, key: &str) -> Option<NbtString<'a>> {
+        self.get(key).and_then(Tag::as_string)
+    }
+
+    /// Looks up a key and returns it if it's a [Compound].
+    pub fn get_compound(&self, key: &str) -> Option<&Compound<'a>> {
+        self.get(key).and_then(Tag::as_compound)
+    }
+
+    /// Looks up a key and returns a copy of it if it's a [Compound], or
+    /// an empty compound if it's missing or has another type. Useful for
+    /// optional nested structures, where a missing compound means the
+    /// same thing as an empty one.
+    pub fn get_compound_or_empty(&self, key: &str) -> Compound<'a> {
+        self.get_compound(key).cloned().unwrap_or_default()
+    }
+
+    /// Looks up a key and returns it if it's a [List].
+    pub fn get_list(&self, key: &str) -> Option<&List<'a>> {
+        self.get(key).and_then(Tag::as_list)
+    }
+
+    /// Describes the compound as an indented outline, with one line per
+    /// tag showing its name, type and a short version of its value. Long
+    /// strings and arrays are cut off. This tends to be easier to scan
+    /// than the [Debug][fmt::Debug] output for deeply nested data.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(feature = "std", doc = "```rust")]
+    #[cfg_attr(not(feature = "std"), doc = "```ignore")]
+    /// # use std::error::Error;
+    /// # use nobility::bin_decode::Document;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let input = Document::doctest_demo();
+    /// # let doc = Document::load(input)?;
+    /// # let (_name, compound) = doc.parse()?;
+    /// print!("{}", compound.outline());
+    /// // name: String "Bananrama"
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn outline(&self) -> String {
+        let mut out = String::new();
+        outline::outline_compound(&mut out, self, 0);
+        out
+    }
+
+    /// Looks up several keys at once, checking that each exists and has
+    /// the expected type. On success the values are returned in the
+    /// same order as `specs`. Unlike looking up the keys one at a time,
+    /// every failure is reported rather than just the first.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(feature = "std", doc = "```rust")]
+    #[cfg_attr(not(feature = "std"), doc = "```ignore")]
+    /// # use std::error::Error;
+    /// # use nobility::bin_decode::Document;
+    /// use nobility::TagType;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let input = Document::doctest_demo();
+    /// # let doc = Document::load(input)?;
+    /// # let (_name, compound) = doc.parse()?;
+    ///
+    /// match compound.require(&[("name", TagType::String), ("level", TagType::Int)]) {
+    ///     Ok(values) => println!("name = {:?}, level = {:?}", values[0], values[1]),
+    ///     Err(errors) => {
+    ///         for error in errors {
+    ///             println!("{}", error);
+    ///         }
+    ///     }
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn require(&self, specs: &[(&str, TagType)]) -> Result<Vec<&Tag<'a>>, Vec<RequireError>> {
+        let mut values = Vec::with_capacity(specs.len());
+        let mut errors = vec![];
+        for &(key, expected) in specs {
+            match self.get(key) {
+                Some(value) if value.tag_type() == expected => values.push(value),
+                Some(value) => errors.push(RequireError::WrongType {
+                    key: key.to_owned(),
+                    expected,
+                    found: value.tag_type(),
+                }),
+                None => errors.push(RequireError::Missing {
+                    key: key.to_owned(),
+                }),
             }
         }
-        None
+        if errors.is_empty() {
+            Ok(values)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Looks up a nested value using a path of keys separated by `/`,
+    /// for example `"Level/Sections/0/Y"`. When the current value is a
+    /// [List], the segment is parsed as an index into it instead of a
+    /// key. Returns None if any key is missing, an index is out of
+    /// range, or a value in the middle of the path is not a Compound or
+    /// List.
+    ///
+    /// # Notes
+    ///
+    /// Values found in a Compound are borrowed, but values found by
+    /// indexing into a List are returned by value, as a List doesn't
+    /// store its elements as [Tag]s. Like [List::get], this results in
+    /// a clone if the final value is a Compound or List inside of a
+    /// List.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(feature = "std", doc = "```rust")]
+    #[cfg_attr(not(feature = "std"), doc = "```ignore")]
+    /// # use std::error::Error;
+    /// # use nobility::bin_decode::Document;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let input = Document::doctest_demo();
+    /// # let doc = Document::load(input)?;
+    /// # let (_name, compound) = doc.parse()?;
+    /// #
+    /// if let Some(id) = compound.path("Inventory/0/id") {
+    ///     println!("First item is {:?}", id);
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn path(&self, path: &str) -> Option<Cow<'_, Tag<'a>>> {
+        let mut current = PathStep::Compound(self);
+        for segment in path.split('/') {
+            current = current.step(segment)?;
+        }
+        Some(match current {
+            PathStep::Tag(tag) => Cow::Borrowed(tag),
+            PathStep::Compound(compound) => Cow::Owned(Tag::Compound(compound.clone())),
+            PathStep::List(list) => Cow::Owned(Tag::List(list.clone())),
+            PathStep::Owned(tag) => Cow::Owned(tag),
+        })
+    }
+
+    /// Decodes every key and collects the entries into a HashMap, for
+    /// when many lookups need to be done on a large compound. If a key
+    /// appears more than once, the first entry wins, the same as
+    /// [Compound::find_first_key].
+    ///
+    /// # Errors
+    ///
+    /// Fails if any of the keys are not valid CESU-8.
+    #[cfg(feature = "std")]
+    pub fn to_hashmap(&self) -> Result<HashMap<Cow<'_, str>, &Tag<'a>>, Cesu8DecodingError> {
+        let mut map = HashMap::with_capacity(self.len());
+        for entry in &self.entries {
+            map.entry(entry.name.decode()?).or_insert(&entry.value);
+        }
+        Ok(map)
+    }
+
+    /// Decodes every key and returns the entries as pairs, in the same
+    /// order as the document. Unlike [Compound::to_hashmap], duplicate
+    /// keys are all kept.
+    ///
+    /// # Errors
+    ///
+    /// Fails on the first key that isn't valid CESU-8.
+    pub fn to_pairs(&self) -> Result<Vec<(String, &Tag<'a>)>, Cesu8DecodingError> {
+        self.entries
+            .iter()
+            .map(|entry| Ok((entry.name.decode()?.into_owned(), &entry.value)))
+            .collect()
     }
 
     /// Returns an iterator over the entries.
-    pub fn iter(&self) -> SliceIter<Entry<'a>> {
+    pub fn iter(&self) -> SliceIter<'_, Entry<'a>> {
         self.entries.iter()
     }
 
@@ -104,6 +371,105 @@ impl<'a> Compound<'a> {
     }
 }
 
+/// A failure to find one of the fields passed to [Compound::require].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RequireError {
+    /// There is no entry with this name.
+    Missing { key: String },
+    /// The entry exists, but has a different type than expected.
+    WrongType {
+        key: String,
+        expected: TagType,
+        found: TagType,
+    },
+}
+
+impl fmt::Display for RequireError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RequireError::Missing { key } => write!(fmt, "Missing required field {:?}", key),
+            RequireError::WrongType {
+                key,
+                expected,
+                found,
+            } => write!(
+                fmt,
+                "Field {:?} should be {:?}, but is {:?}",
+                key, expected, found
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RequireError {}
+
+/// Allows iterating over the entries using a `for` loop.
+///
+/// # Example
+///
+#[cfg_attr(feature = "std", doc = "```rust")]
+#[cfg_attr(not(feature = "std"), doc = "```ignore")]
+/// # use std::error::Error;
+/// # use nobility::bin_decode::Document;
+/// #
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// # let input = Document::doctest_demo();
+/// # let doc = Document::load(input)?;
+/// # let (_name, compound) = doc.parse()?;
+/// #
+/// for entry in &compound {
+///     println!("{:?}: {:?}", entry.name(), entry.value());
+/// }
+/// #
+/// # Ok(())
+/// # }
+/// ```
+impl<'s, 'a> IntoIterator for &'s Compound<'a> {
+    type Item = &'s Entry<'a>;
+    type IntoIter = SliceIter<'s, Entry<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// The value reached after each segment of [Compound::path]. Compounds
+/// and Lists inside of a List aren't stored as a [Tag], so they are
+/// tracked separately to avoid cloning them.
+enum PathStep<'s, 'a> {
+    Tag(&'s Tag<'a>),
+    Compound(&'s Compound<'a>),
+    List(&'s List<'a>),
+    Owned(Tag<'a>),
+}
+
+impl<'s, 'a> PathStep<'s, 'a> {
+    fn step(self, segment: &str) -> Option<PathStep<'s, 'a>> {
+        match self {
+            PathStep::Tag(Tag::Compound(compound)) | PathStep::Compound(compound) => {
+                compound.get(segment).map(PathStep::Tag)
+            }
+            PathStep::Tag(Tag::List(list)) | PathStep::List(list) => {
+                let index = segment.parse().ok()?;
+                match list {
+                    List::Compound(list) => list.get(index).map(PathStep::Compound),
+                    List::List(list) => list.get(index).map(PathStep::List),
+                    list => list.get(index).map(PathStep::Owned),
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Default for Compound<'a> {
+    fn default() -> Compound<'a> {
+        Compound::new()
+    }
+}
+
 impl<'a> Index<usize> for Compound<'a> {
     type Output = Entry<'a>;
 
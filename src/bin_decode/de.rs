@@ -0,0 +1,342 @@
+use crate::bin_decode::{Compound, Document, Entry, List, NbtString, ParseError, Tag};
+use core::slice::Iter as SliceIter;
+use serde::de::value::{SeqDeserializer as ValueSeqDeserializer, StringDeserializer};
+use serde::de::{
+    self, DeserializeSeed, Deserializer, IntoDeserializer, MapAccess, SeqAccess, Visitor,
+};
+use serde::{forward_to_deserialize_any, Deserialize};
+use std::fmt;
+
+/// Failures which can occur while deserializing a document using serde.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DeserializeError {
+    /// The document could not be parsed.
+    Parse(ParseError),
+    /// A string or key in the document is not valid CESU-8.
+    InvalidString,
+    /// The document didn't match the type being deserialized into.
+    Message(String),
+}
+
+impl fmt::Display for DeserializeError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeserializeError::Parse(err) => write!(fmt, "Failed to parse document: {}", err),
+            DeserializeError::InvalidString => write!(fmt, "String is not valid CESU-8"),
+            DeserializeError::Message(msg) => write!(fmt, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for DeserializeError {}
+
+impl de::Error for DeserializeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        DeserializeError::Message(msg.to_string())
+    }
+}
+
+impl From<ParseError> for DeserializeError {
+    fn from(err: ParseError) -> Self {
+        DeserializeError::Parse(err)
+    }
+}
+
+/// Parses a document and deserializes its root compound into `T`.
+/// Requires the `serde` feature.
+///
+/// Compounds are deserialized as maps (so they can be read into
+/// structs), lists and arrays as sequences, and strings are decoded from
+/// CESU-8. Strings which are valid UTF-8 can be borrowed from the
+/// document. As NBT has no bool type, bools are read from bytes which
+/// are 0 or 1.
+///
+/// # Example
+///
+/// ```rust
+/// # use std::error::Error;
+/// #
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use nobility::bin_decode::{from_document, Document};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct HelloWorld {
+///     name: String,
+/// }
+///
+/// # let input = Document::doctest_demo();
+/// let doc = Document::load(input)?;
+/// let hello: HelloWorld = from_document(&doc)?;
+/// assert_eq!(hello.name, "Bananrama");
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_document<'de, T>(document: &'de Document) -> Result<T, DeserializeError>
+where
+    T: Deserialize<'de>,
+{
+    let (_name, root) = document.parse()?;
+    from_compound(&root)
+}
+
+/// Deserializes an already parsed [Compound] into `T`. See
+/// [from_document] for how tags are mapped. Requires the `serde`
+/// feature.
+pub fn from_compound<'de, T>(compound: &Compound<'de>) -> Result<T, DeserializeError>
+where
+    T: Deserialize<'de>,
+{
+    T::deserialize(ValueDeserializer {
+        value: Value::Compound(compound),
+    })
+}
+
+/// Values that can be deserialized. Compounds and Lists inside of a
+/// List aren't stored as a [Tag], so they are handled separately to
+/// avoid cloning them.
+enum Value<'s, 'de> {
+    Tag(Tag<'de>),
+    Compound(&'s Compound<'de>),
+    List(&'s List<'de>),
+}
+
+impl<'s, 'de> Value<'s, 'de> {
+    fn from_tag(tag: &'s Tag<'de>) -> Value<'s, 'de> {
+        match tag {
+            Tag::Compound(compound) => Value::Compound(compound),
+            Tag::List(list) => Value::List(list),
+            tag => Value::Tag(tag.clone()),
+        }
+    }
+}
+
+type SeqDeserializer<I> = ValueSeqDeserializer<I, DeserializeError>;
+
+struct ValueDeserializer<'s, 'de> {
+    value: Value<'s, 'de>,
+}
+
+fn visit_string<'de, V>(string: NbtString<'de>, visitor: V) -> Result<V::Value, DeserializeError>
+where
+    V: Visitor<'de>,
+{
+    if let Some(string) = string.as_str() {
+        visitor.visit_borrowed_str(string)
+    } else {
+        let string = string
+            .decode()
+            .map_err(|_| DeserializeError::InvalidString)?;
+        visitor.visit_string(string.into_owned())
+    }
+}
+
+impl<'s, 'de> Deserializer<'de> for ValueDeserializer<'s, 'de> {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::Tag(Tag::Byte(value)) => visitor.visit_i8(value),
+            Value::Tag(Tag::Short(value)) => visitor.visit_i16(value),
+            Value::Tag(Tag::Int(value)) => visitor.visit_i32(value),
+            Value::Tag(Tag::Long(value)) => visitor.visit_i64(value),
+            Value::Tag(Tag::Float(value)) => visitor.visit_f32(value),
+            Value::Tag(Tag::Double(value)) => visitor.visit_f64(value),
+            Value::Tag(Tag::String(value)) => visit_string(value, visitor),
+            Value::Tag(Tag::ByteArray(value)) => {
+                visitor.visit_seq(SeqDeserializer::new(value.iter().map(|b| *b as i8)))
+            }
+            Value::Tag(Tag::IntArray(value)) => {
+                visitor.visit_seq(SeqDeserializer::new(value.iter()))
+            }
+            Value::Tag(Tag::LongArray(value)) => {
+                visitor.visit_seq(SeqDeserializer::new(value.iter()))
+            }
+            Value::Tag(Tag::Compound(compound)) => visitor.visit_map(CompoundAccess {
+                iter: compound.iter(),
+                value: None,
+            }),
+            Value::Tag(Tag::List(list)) => visitor.visit_seq(ListAccess {
+                list: &list,
+                index: 0,
+            }),
+            Value::Compound(compound) => visitor.visit_map(CompoundAccess {
+                iter: compound.iter(),
+                value: None,
+            }),
+            Value::List(list) => visitor.visit_seq(ListAccess { list, index: 0 }),
+        }
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::Tag(Tag::Byte(0)) => visitor.visit_bool(false),
+            Value::Tag(Tag::Byte(1)) => visitor.visit_bool(true),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::Tag(Tag::ByteArray(value)) => visitor.visit_borrowed_bytes(value),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        // Absent fields are handled by serde, so any value that exists
+        // is Some.
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        // Only unit variants stored as strings are supported.
+        match self.value {
+            Value::Tag(Tag::String(value)) => {
+                let value = value
+                    .decode()
+                    .map_err(|_| DeserializeError::InvalidString)?;
+                let access: StringDeserializer<DeserializeError> =
+                    value.into_owned().into_deserializer();
+                visitor.visit_enum(access)
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        unit unit_struct seq tuple tuple_struct map struct identifier
+        ignored_any
+    }
+}
+
+struct CompoundAccess<'s, 'de> {
+    iter: SliceIter<'s, Entry<'de>>,
+    value: Option<&'s Tag<'de>>,
+}
+
+impl<'s, 'de> MapAccess<'de> for CompoundAccess<'s, 'de> {
+    type Error = DeserializeError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some(entry) => {
+                self.value = Some(entry.value());
+                seed.deserialize(KeyDeserializer { key: *entry.name() })
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let value = self
+            .value
+            .take()
+            .expect("next_value_seed called before next_key_seed");
+        seed.deserialize(ValueDeserializer {
+            value: Value::from_tag(value),
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+struct ListAccess<'s, 'de> {
+    list: &'s List<'de>,
+    index: usize,
+}
+
+impl<'s, 'de> SeqAccess<'de> for ListAccess<'s, 'de> {
+    type Error = DeserializeError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let index = self.index;
+        self.index += 1;
+        let value = match self.list {
+            List::Compound(list) => list.get(index).map(Value::Compound),
+            List::List(list) => list.get(index).map(Value::List),
+            list => list.get(index).map(Value::Tag),
+        };
+        match value {
+            Some(value) => seed.deserialize(ValueDeserializer { value }).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.list.len().saturating_sub(self.index))
+    }
+}
+
+struct KeyDeserializer<'de> {
+    key: NbtString<'de>,
+}
+
+impl<'de> Deserializer<'de> for KeyDeserializer<'de> {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visit_string(self.key, visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
@@ -0,0 +1,224 @@
+use crate::bin_decode::{
+    read_root_header, read_type, NbtParse, NbtString, ParseError, Reader, Tag,
+};
+use crate::TagType;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// A single step of walking through a document with [EventReader].
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Event<'a> {
+    /// A TAG_Compound was started. Its entries follow, and then an
+    /// [Event::End].
+    StartCompound { name: Option<NbtString<'a>> },
+    /// A TAG_List was started. `len` elements follow, each without a
+    /// name, and then an [Event::End].
+    StartList {
+        name: Option<NbtString<'a>>,
+        element_type: TagType,
+        len: usize,
+    },
+    /// Any tag other than a compound or list. Arrays and strings borrow
+    /// from the document, like they do in [Tag].
+    Primitive {
+        name: Option<NbtString<'a>>,
+        tag: Tag<'a>,
+    },
+    /// The most recently started compound or list has ended.
+    End,
+}
+
+enum Frame {
+    Compound,
+    List {
+        element_type: TagType,
+        remaining: usize,
+    },
+}
+
+/// A pull parser which walks through a document one tag at a time,
+/// instead of building a tree of [Compound][crate::bin_decode::Compound]
+/// and [List][crate::bin_decode::List]. This avoids allocating for
+/// documents that only need to be scanned. Created using
+/// [Document::events][crate::bin_decode::Document::events].
+///
+/// Names are `None` for the elements of a list, and `Some` everywhere
+/// else, including the root compound.
+///
+/// After an error has been returned, the iterator ends.
+///
+/// # Example
+///
+#[cfg_attr(feature = "std", doc = "```rust")]
+#[cfg_attr(not(feature = "std"), doc = "```ignore")]
+/// # use std::error::Error;
+/// # use nobility::bin_decode::Document;
+/// #
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use nobility::bin_decode::Event;
+///
+/// # let input = Document::doctest_demo();
+/// let doc = Document::load(input)?;
+/// let mut strings = 0;
+/// for event in doc.events() {
+///     if let Event::Primitive { tag, .. } = event? {
+///         if tag.as_string().is_some() {
+///             strings += 1;
+///         }
+///     }
+/// }
+/// assert_eq!(strings, 1);
+/// # Ok(())
+/// # }
+/// ```
+pub struct EventReader<'a> {
+    reader: Reader<'a>,
+    stack: Vec<Frame>,
+    started: bool,
+    done: bool,
+}
+
+impl<'a> EventReader<'a> {
+    pub(crate) fn new(reader: Reader<'a>) -> EventReader<'a> {
+        EventReader {
+            reader,
+            stack: vec![],
+            started: false,
+            done: false,
+        }
+    }
+
+    /// Skips the rest of the innermost compound or list that is
+    /// currently open, including its [Event::End]. Calling this right
+    /// after receiving [Event::StartCompound] or [Event::StartList]
+    /// skips the whole subtree, without creating any events for it.
+    /// Does nothing once the root compound has ended.
+    ///
+    /// # Errors
+    ///
+    /// The skipped data is still checked to be well formed, so this
+    /// can fail the same way that reading it would.
+    pub fn skip_subtree(&mut self) -> Result<(), ParseError> {
+        let frame = match self.stack.pop() {
+            Some(frame) => frame,
+            None => return Ok(()),
+        };
+        let result = match frame {
+            Frame::Compound => self.reader.skip_compound_entries(),
+            Frame::List {
+                element_type,
+                remaining,
+            } => self.reader.skip_elements(element_type, remaining),
+        };
+        self.reader.exit();
+        if self.stack.is_empty() || result.is_err() {
+            self.done = true;
+        }
+        result
+    }
+
+    /// Returns how deeply nested the reader currently is. This is 1
+    /// inside of the root compound, and 0 before it starts or after it
+    /// has ended.
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    fn read_event(&mut self) -> Result<Option<Event<'a>>, ParseError> {
+        if !self.started {
+            self.started = true;
+            let name = read_root_header(&mut self.reader)?;
+            return self.start(Some(name), TagType::Compound).map(Some);
+        }
+
+        match self.stack.last_mut() {
+            None => Ok(None),
+            Some(Frame::Compound) => {
+                let tag = read_type(&mut self.reader)?;
+                if tag == TagType::End {
+                    self.end();
+                    return Ok(Some(Event::End));
+                }
+                let name = NbtString::read(&mut self.reader)?;
+                self.start(Some(name), tag).map(Some)
+            }
+            Some(Frame::List {
+                element_type,
+                remaining,
+            }) => {
+                if *remaining == 0 {
+                    self.end();
+                    return Ok(Some(Event::End));
+                }
+                *remaining -= 1;
+                let tag = *element_type;
+                self.start(None, tag).map(Some)
+            }
+        }
+    }
+
+    fn start(
+        &mut self,
+        name: Option<NbtString<'a>>,
+        tag: TagType,
+    ) -> Result<Event<'a>, ParseError> {
+        match tag {
+            TagType::Compound => {
+                self.reader.enter()?;
+                self.stack.push(Frame::Compound);
+                Ok(Event::StartCompound { name })
+            }
+            TagType::List => {
+                self.reader.enter()?;
+                let element_type = read_type(&mut self.reader)?;
+                let len = self.reader.read_list_length(element_type)?;
+                if element_type == TagType::End && len > 0 {
+                    return Err(ParseError::UnexpectedEndTag {
+                        offset: self.reader.position,
+                    });
+                }
+                self.stack.push(Frame::List {
+                    element_type,
+                    remaining: len,
+                });
+                Ok(Event::StartList {
+                    name,
+                    element_type,
+                    len,
+                })
+            }
+            tag => Ok(Event::Primitive {
+                name,
+                tag: Tag::read(tag, &mut self.reader)?,
+            }),
+        }
+    }
+
+    fn end(&mut self) {
+        self.stack.pop();
+        self.reader.exit();
+    }
+}
+
+impl<'a> Iterator for EventReader<'a> {
+    type Item = Result<Event<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = self.read_event();
+        match result {
+            Ok(Some(event)) => Some(Ok(event)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::bin_decode::{read_type, NbtParse, NbtString, ParseError, Reader};
+use crate::TagType;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// The keys of a TAG_Compound along with the type and location of each
+/// value, created by [Document::parse_index][crate::bin_decode::Document::parse_index].
+/// None of the values are parsed, they're only skipped over, which
+/// makes this a cheap way to catalog what large documents contain.
+///
+/// Unlike [LazyCompound][crate::bin_decode::LazyCompound], the index
+/// doesn't borrow from the document, so it can be stored after the
+/// document is dropped. Keys are decoded up front, with invalid CESU-8
+/// replaced like [NbtString::to_string_lossy].
+///
+/// # Example
+///
+#[cfg_attr(feature = "std", doc = "```rust")]
+#[cfg_attr(not(feature = "std"), doc = "```ignore")]
+/// # use std::error::Error;
+/// # use nobility::bin_decode::Document;
+/// # use nobility::TagType;
+/// #
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// # let input = Document::doctest_demo();
+/// let doc = Document::load(input)?;
+/// let (_name, index) = doc.parse_index()?;
+/// if let Some((offset, tag)) = index.get("name") {
+///     assert_eq!(tag, TagType::String);
+///     println!("name is stored at byte {}", offset);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompoundIndex {
+    entries: Vec<(String, usize, TagType)>,
+}
+
+impl<'a> NbtParse<'a> for CompoundIndex {
+    fn read(reader: &mut Reader<'a>) -> Result<Self, ParseError> {
+        reader.enter()?;
+        let result = CompoundIndex::read_entries(reader);
+        reader.exit();
+        Ok(CompoundIndex { entries: result? })
+    }
+}
+
+impl CompoundIndex {
+    fn read_entries(reader: &mut Reader) -> Result<Vec<(String, usize, TagType)>, ParseError> {
+        let mut entries = vec![];
+        loop {
+            let tag = read_type(reader)?;
+            if tag == TagType::End {
+                return Ok(entries);
+            }
+            let name = NbtString::read(reader)?;
+            let offset = reader.position;
+            reader.skip_value(tag)?;
+            entries.push((name.to_string_lossy().into_owned(), offset, tag));
+        }
+    }
+
+    /// Returns the number of entries in the compound.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the compound has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Finds the first entry with the given key, and returns the offset
+    /// of its value within the document along with its type.
+    pub fn get(&self, key: &str) -> Option<(usize, TagType)> {
+        self.entries
+            .iter()
+            .find(|(name, _, _)| name == key)
+            .map(|&(_, offset, tag)| (offset, tag))
+    }
+
+    /// Iterates over the key, offset and type of every entry, in the
+    /// order they appear in the document.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, usize, TagType)> + '_ {
+        self.entries
+            .iter()
+            .map(|(name, offset, tag)| (name.as_str(), *offset, *tag))
+    }
+}
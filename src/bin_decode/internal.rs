@@ -1,4 +1,6 @@
-use crate::bin_decode::ParseError;
+use crate::bin_decode::{read_type, LengthReader};
+use crate::bin_decode::{Limits, NbtString, ParseError, ParseOptions};
+use crate::TagType;
 use byteorder::{BigEndian, ByteOrder};
 
 pub trait NbtParse<'a>: Sized {
@@ -23,31 +25,191 @@ primitive_impl!(f64, 8, read_f64);
 
 impl<'a> NbtParse<'a> for &'a [u8] {
     fn read(reader: &mut Reader<'a>) -> Result<Self, ParseError> {
-        let length = BigEndian::read_u32(reader.advance(4)?);
-        Ok(reader.advance(length as usize)?)
+        let length = reader.read_array_length()?;
+        reader.advance(length)
     }
 }
 
 pub struct Reader<'a> {
     buffer: &'a [u8],
     pub position: usize,
+    depth: usize,
+    options: ParseOptions,
+    max_array_length: usize,
 }
 
 impl<'a> Reader<'a> {
-    pub(crate) fn new(buffer: &'a [u8]) -> Reader<'a> {
+    pub(crate) fn with_options(buffer: &'a [u8], options: ParseOptions) -> Reader<'a> {
         Reader {
             buffer,
             position: 0,
+            depth: 0,
+            options,
+            max_array_length: 0,
         }
     }
 
+    /// Called when starting to read a nested List or Compound. Every
+    /// successful call must be paired with a call to [Reader::exit].
+    pub(crate) fn enter(&mut self) -> Result<(), ParseError> {
+        if self.depth >= self.options.limits.max_depth {
+            return Err(ParseError::DepthExceeded {
+                offset: self.position,
+            });
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    pub(crate) fn buffer(&self) -> &'a [u8] {
+        self.buffer
+    }
+
+    pub(crate) fn options(&self) -> ParseOptions {
+        self.options
+    }
+
+    pub(crate) fn limits(&self) -> &Limits {
+        &self.options.limits
+    }
+
+    pub(crate) fn exit(&mut self) {
+        self.depth -= 1;
+    }
+
+    /// Returns the number of bytes left to be read.
+    pub(crate) fn remaining(&self) -> usize {
+        self.buffer.len() - self.position
+    }
+
+    /// Reads the length prefix of a string using
+    /// [ParseOptions::string_length].
+    pub(crate) fn read_string_length(&mut self) -> Result<usize, ParseError> {
+        self.read_length(self.options.string_length)
+    }
+
+    /// Reads the length prefix of a list or array using
+    /// [ParseOptions::array_length].
+    pub(crate) fn read_array_length(&mut self) -> Result<usize, ParseError> {
+        let length = self.read_length(self.options.array_length)?;
+        self.max_array_length = self.max_array_length.max(length);
+        Ok(length)
+    }
+
+    /// Reads the length prefix of a TAG_List. If its elements are a
+    /// fixed size, the length is checked against the rest of the
+    /// document up front, so that a corrupt length is reported as
+    /// [ParseError::ListLengthMismatch] instead of running into
+    /// unrelated data.
+    pub(crate) fn read_list_length(&mut self, element: TagType) -> Result<usize, ParseError> {
+        let offset = self.position;
+        let length = self.read_array_length()?;
+        if let Some(size) = element.element_size() {
+            match length.checked_mul(size) {
+                Some(bytes) if bytes <= self.remaining() => (),
+                _ => return Err(ParseError::ListLengthMismatch { length, offset }),
+            }
+        }
+        Ok(length)
+    }
+
+    /// Returns the largest length returned by [Reader::read_array_length]
+    /// so far.
+    pub(crate) fn max_array_length(&self) -> usize {
+        self.max_array_length
+    }
+
+    fn read_length(&mut self, func: LengthReader) -> Result<usize, ParseError> {
+        let (length, size) = func(&self.buffer[self.position..]).ok_or(ParseError::EOF {
+            offset: self.position,
+        })?;
+        self.advance(size)?;
+        Ok(length)
+    }
+
     pub(crate) fn advance(&mut self, n: usize) -> Result<&'a [u8], ParseError> {
-        if self.buffer.len() < self.position + n {
-            Err(ParseError::EOF)
+        if self.remaining() < n {
+            Err(ParseError::EOF {
+                offset: self.position,
+            })
         } else {
             let slice = &self.buffer[self.position..self.position + n];
             self.position += n;
             Ok(slice)
         }
     }
+
+    /// Skips over the payload of a tag without building anything, while
+    /// still checking that it's well formed.
+    pub(crate) fn skip_value(&mut self, tag: TagType) -> Result<(), ParseError> {
+        match tag {
+            TagType::End => Err(ParseError::UnexpectedEndTag {
+                offset: self.position,
+            }),
+            TagType::Byte => self.advance(1).map(drop),
+            TagType::Short => self.advance(2).map(drop),
+            TagType::Int | TagType::Float => self.advance(4).map(drop),
+            TagType::Long | TagType::Double => self.advance(8).map(drop),
+            TagType::ByteArray => self.skip_array(1),
+            TagType::String => NbtString::read(self).map(drop),
+            TagType::IntArray => self.skip_array(4),
+            TagType::LongArray => self.skip_array(8),
+            TagType::List => {
+                self.enter()?;
+                let result = self.skip_list_contents();
+                self.exit();
+                result
+            }
+            TagType::Compound => {
+                self.enter()?;
+                let result = self.skip_compound_entries();
+                self.exit();
+                result
+            }
+        }
+    }
+
+    fn skip_array(&mut self, size: usize) -> Result<(), ParseError> {
+        let length = self.read_array_length()?;
+        let bytes = length.checked_mul(size).ok_or(ParseError::EOF {
+            offset: self.position,
+        })?;
+        self.advance(bytes).map(drop)
+    }
+
+    fn skip_list_contents(&mut self) -> Result<(), ParseError> {
+        let tag = read_type(self)?;
+        let length = self.read_list_length(tag)?;
+        if tag == TagType::End {
+            return if length == 0 {
+                Ok(())
+            } else {
+                Err(ParseError::UnexpectedEndTag {
+                    offset: self.position,
+                })
+            };
+        }
+        self.skip_elements(tag, length)
+    }
+
+    /// Skips `count` list elements of the given type.
+    pub(crate) fn skip_elements(&mut self, tag: TagType, count: usize) -> Result<(), ParseError> {
+        for _ in 0..count {
+            self.skip_value(tag)?;
+        }
+        Ok(())
+    }
+
+    /// Skips the remaining entries of a compound, including its
+    /// TAG_End.
+    pub(crate) fn skip_compound_entries(&mut self) -> Result<(), ParseError> {
+        loop {
+            let tag = read_type(self)?;
+            if tag == TagType::End {
+                return Ok(());
+            }
+            NbtString::read(self)?;
+            self.skip_value(tag)?;
+        }
+    }
 }
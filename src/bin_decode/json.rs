@@ -0,0 +1,80 @@
+use crate::bin_decode::{Compound, List, Tag};
+use serde_json::{Map, Number, Value};
+
+/// The largest integer that can be represented exactly by a double,
+/// which is what most JSON parsers use for numbers.
+const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+fn float_to_json(value: f64) -> Value {
+    Number::from_f64(value).map_or(Value::Null, Value::Number)
+}
+
+impl<'a> Tag<'a> {
+    /// Converts the tag into a JSON value, for debugging or for passing
+    /// to tools that don't understand NBT. Requires the `json` feature.
+    ///
+    /// - Compounds become objects. If a key is repeated, the last value
+    ///   is kept.
+    /// - Lists and arrays become arrays.
+    /// - Strings are decoded from CESU-8, replacing invalid sequences
+    ///   like [NbtString::to_string_lossy][crate::bin_decode::NbtString::to_string_lossy].
+    /// - Numbers become JSON numbers, except for NaN and infinities,
+    ///   which become null.
+    /// - TAG_Long values outside of ±(2<sup>53</sup> - 1) become
+    ///   strings, as JavaScript and many other JSON parsers would
+    ///   otherwise silently lose precision.
+    ///
+    /// Type information is lost, so this conversion can't be reversed
+    /// exactly.
+    pub fn to_json(&self) -> Value {
+        match self {
+            Tag::Byte(value) => Value::from(*value),
+            Tag::Short(value) => Value::from(*value),
+            Tag::Int(value) => Value::from(*value),
+            Tag::Long(value) => long_to_json(*value),
+            Tag::Float(value) => float_to_json(*value as f64),
+            Tag::Double(value) => float_to_json(*value),
+            Tag::ByteArray(value) => Value::from(value.to_vec()),
+            Tag::String(value) => Value::from(value.to_string_lossy()),
+            Tag::IntArray(value) => value.iter().map(Value::from).collect(),
+            Tag::LongArray(value) => value.iter().map(long_to_json).collect(),
+            Tag::List(value) => value.to_json(),
+            Tag::Compound(value) => value.to_json(),
+        }
+    }
+}
+
+fn long_to_json(value: i64) -> Value {
+    if (-MAX_SAFE_INTEGER..=MAX_SAFE_INTEGER).contains(&value) {
+        Value::from(value)
+    } else {
+        Value::from(value.to_string())
+    }
+}
+
+impl<'a> List<'a> {
+    /// Converts the list into a JSON array. See [Tag::to_json]. Requires
+    /// the `json` feature.
+    pub fn to_json(&self) -> Value {
+        match self {
+            List::Compound(list) => list.iter().map(|value| value.to_json()).collect(),
+            List::List(list) => list.iter().map(|value| value.to_json()).collect(),
+            list => list.iter().map(|value| value.to_json()).collect(),
+        }
+    }
+}
+
+impl<'a> Compound<'a> {
+    /// Converts the compound into a JSON object. See [Tag::to_json].
+    /// Requires the `json` feature.
+    pub fn to_json(&self) -> Value {
+        let mut map = Map::with_capacity(self.len());
+        for entry in self.iter() {
+            map.insert(
+                entry.name().to_string_lossy().into_owned(),
+                entry.value().to_json(),
+            );
+        }
+        Value::Object(map)
+    }
+}
@@ -0,0 +1,123 @@
+use crate::bin_decode::{read_type, NbtParse, NbtString, ParseError, ParseOptions, Reader, Tag};
+use crate::TagType;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A TAG_Compound where only the names of the entries are decoded up
+/// front. Each value is parsed when it's looked up with
+/// [LazyCompound::get], which is much cheaper than [Compound][crate::bin_decode::Compound]
+/// when only a few keys of a large compound are needed.
+///
+/// The whole compound is still checked to be well formed when it's
+/// read, so looking up a value won't normally fail.
+///
+/// # Example
+///
+#[cfg_attr(feature = "std", doc = "```rust")]
+#[cfg_attr(not(feature = "std"), doc = "```ignore")]
+/// # use std::error::Error;
+/// # use nobility::bin_decode::Document;
+/// #
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// # let input = Document::doctest_demo();
+/// let doc = Document::load(input)?;
+/// let (_name, root) = doc.parse_lazy()?;
+/// if let Some(name) = root.get("name") {
+///     println!("{:?}", name?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct LazyCompound<'a> {
+    buffer: &'a [u8],
+    options: ParseOptions,
+    entries: Vec<LazyEntry<'a>>,
+}
+
+#[derive(Clone)]
+struct LazyEntry<'a> {
+    name: NbtString<'a>,
+    tag: TagType,
+    /// Offset of the value within the buffer.
+    offset: usize,
+}
+
+impl<'a> NbtParse<'a> for LazyCompound<'a> {
+    fn read(reader: &mut Reader<'a>) -> Result<Self, ParseError> {
+        reader.enter()?;
+        let result = LazyCompound::read_entries(reader);
+        reader.exit();
+        Ok(LazyCompound {
+            buffer: reader.buffer(),
+            options: reader.options(),
+            entries: result?,
+        })
+    }
+}
+
+impl<'a> LazyCompound<'a> {
+    fn read_entries(reader: &mut Reader<'a>) -> Result<Vec<LazyEntry<'a>>, ParseError> {
+        let mut entries = vec![];
+        loop {
+            let tag = read_type(reader)?;
+            if tag == TagType::End {
+                return Ok(entries);
+            }
+            let name = NbtString::read(reader)?;
+            let offset = reader.position;
+            reader.skip_value(tag)?;
+            entries.push(LazyEntry { name, tag, offset });
+        }
+    }
+
+    /// Returns the number of entries in the compound.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the compound has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the names of the entries, in the order they appear in
+    /// the document.
+    pub fn keys(&self) -> impl Iterator<Item = NbtString<'a>> + '_ {
+        self.entries.iter().map(|entry| entry.name)
+    }
+
+    /// Returns the type of the value with the given key, without
+    /// parsing it.
+    pub fn tag_type(&self, key: &str) -> Option<TagType> {
+        self.find(key).map(|entry| entry.tag)
+    }
+
+    /// Finds the first entry with the given key and parses its value.
+    /// Returns None if there is no entry with that key.
+    ///
+    /// # Errors
+    ///
+    /// Parsing the value can only fail if the same check failed when
+    /// the compound was read, so this should not return an error in
+    /// practice.
+    pub fn get(&self, key: &str) -> Option<Result<Tag<'a>, ParseError>> {
+        let entry = self.find(key)?;
+        let mut reader = Reader::with_options(self.buffer, self.options);
+        reader.position = entry.offset;
+        Some(Tag::read(entry.tag, &mut reader))
+    }
+
+    fn find(&self, key: &str) -> Option<&LazyEntry<'a>> {
+        self.entries.iter().find(|entry| entry.name == key)
+    }
+}
+
+impl<'a> fmt::Debug for LazyCompound<'a> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_map()
+            .entries(self.entries.iter().map(|entry| (entry.name, entry.tag)))
+            .finish()
+    }
+}
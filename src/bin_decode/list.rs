@@ -1,13 +1,12 @@
 use crate::bin_decode::array::{IntArray, LongArray, NbtArray};
 use crate::bin_decode::Tag;
-use crate::bin_decode::{
-    read_byte_array, read_type, Compound, NbtParse, NbtString, ParseError, Reader,
-};
+use crate::bin_decode::{read_type, Compound, NbtParse, NbtString, ParseError, Reader};
 use crate::TagType;
-use byteorder::{BigEndian, ByteOrder};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
 use core::ops::Index;
 use core::slice::Iter as SliceIter;
-use std::fmt;
 
 /// Implementation for lists whose elements do not have a fixed size.
 #[derive(Clone, PartialEq)]
@@ -20,9 +19,16 @@ where
     T: NbtParse<'a>,
 {
     fn read(reader: &mut Reader<'a>) -> Result<Self, ParseError> {
-        let length = BigEndian::read_u32(reader.advance(4)?);
-        let mut entries = vec![];
-        entries.reserve(length as usize);
+        let length = reader.read_array_length()?;
+        // Every element takes up at least one byte, so a length longer
+        // than the rest of the document can be rejected before
+        // allocating space for it.
+        if length > reader.remaining() {
+            return Err(ParseError::EOF {
+                offset: reader.position,
+            });
+        }
+        let mut entries = Vec::with_capacity(length);
         for _index in 0..length {
             entries.push(T::read(reader)?);
         }
@@ -52,7 +58,7 @@ impl<T> NbtList<T> {
     }
 
     /// Returns an iterator over the elements of the list.
-    pub fn iter(&self) -> SliceIter<T> {
+    pub fn iter(&self) -> SliceIter<'_, T> {
         self.entries.iter()
     }
 
@@ -61,6 +67,13 @@ impl<T> NbtList<T> {
     }
 }
 
+impl<T> Default for NbtList<T> {
+    /// Creates an empty list.
+    fn default() -> Self {
+        NbtList { entries: vec![] }
+    }
+}
+
 impl<T> Index<usize> for NbtList<T> {
     type Output = T;
 
@@ -96,6 +109,8 @@ pub type LongArrayList<'a> = NbtList<LongArray<'a>>;
 pub type ByteArrayList<'a> = NbtList<&'a [u8]>;
 
 // Primitive lists
+/// A TAG_List of TAG_Byte.
+pub type ByteList<'a> = NbtArray<'a, i8>;
 /// A TAG_List of TAG_Short.
 pub type ShortList<'a> = NbtArray<'a, i16>;
 /// A TAG_List of TAG_Int.
@@ -111,7 +126,12 @@ pub type DoubleList<'a> = NbtArray<'a, f64>;
 #[derive(Clone, Debug, PartialEq)]
 #[non_exhaustive]
 pub enum List<'a> {
-    Byte(&'a [u8]),
+    /// An empty list with an element type of TAG_End. Some
+    /// implementations write empty lists this way, and it's kept
+    /// separate from an empty list of TAG_Byte so that the document can
+    /// be re-encoded exactly.
+    End,
+    Byte(ByteList<'a>),
     Short(ShortList<'a>),
     Int(IntList<'a>),
     Long(LongList<'a>),
@@ -127,26 +147,35 @@ pub enum List<'a> {
 
 impl<'a> NbtParse<'a> for List<'a> {
     fn read(reader: &mut Reader<'a>) -> Result<Self, ParseError> {
+        reader.enter()?;
+        let result = List::read_contents(reader);
+        reader.exit();
+        result
+    }
+}
+
+impl<'a> List<'a> {
+    fn read_contents(reader: &mut Reader<'a>) -> Result<Self, ParseError> {
         let tag = read_type(reader)?;
         match tag {
             TagType::End => {
-                let length = BigEndian::read_u32(reader.advance(4)?);
+                let length = reader.read_array_length()?;
                 // Some implementations will generate an End tag when
-                // serializing an empty list. In this case,
-                // implementations should treat it as an empty byte
-                // array.
+                // serializing an empty list.
                 if length == 0 {
-                    Ok(List::Byte(&[]))
+                    Ok(List::End)
                 } else {
-                    Err(ParseError::UnexpectedEndTag)
+                    Err(ParseError::UnexpectedEndTag {
+                        offset: reader.position,
+                    })
                 }
             }
-            TagType::Byte => read_byte_array(reader).map(List::Byte),
-            TagType::Short => Ok(List::Short(ShortList::read(reader)?)),
-            TagType::Int => Ok(List::Int(IntList::read(reader)?)),
-            TagType::Long => Ok(List::Long(LongList::read(reader)?)),
-            TagType::Float => Ok(List::Float(FloatList::read(reader)?)),
-            TagType::Double => Ok(List::Double(DoubleList::read(reader)?)),
+            TagType::Byte => Ok(List::Byte(ByteList::read_list(reader, tag)?)),
+            TagType::Short => Ok(List::Short(ShortList::read_list(reader, tag)?)),
+            TagType::Int => Ok(List::Int(IntList::read_list(reader, tag)?)),
+            TagType::Long => Ok(List::Long(LongList::read_list(reader, tag)?)),
+            TagType::Float => Ok(List::Float(FloatList::read_list(reader, tag)?)),
+            TagType::Double => Ok(List::Double(DoubleList::read_list(reader, tag)?)),
             TagType::ByteArray => Ok(List::ByteArray(ByteArrayList::read(reader)?)),
             TagType::String => Ok(List::String(StringList::read(reader)?)),
             TagType::List => Ok(List::List(ListList::read(reader)?)),
@@ -155,12 +184,11 @@ impl<'a> NbtParse<'a> for List<'a> {
             TagType::LongArray => Ok(List::LongArray(LongArrayList::read(reader)?)),
         }
     }
-}
 
-impl<'a> List<'a> {
     /// Returns the number of elements in the list.
     pub fn len(&self) -> usize {
         match self {
+            List::End => 0,
             List::Byte(list) => list.len(),
             List::Short(list) => list.len(),
             List::Int(list) => list.len(),
@@ -181,6 +209,197 @@ impl<'a> List<'a> {
         self.len() == 0
     }
 
+    /// Creates an empty list with the given element type. This is the
+    /// inverse of [List::element_type], including for [TagType::End].
+    pub fn from_empty(element: TagType) -> List<'a> {
+        match element {
+            TagType::End => List::End,
+            TagType::Byte => List::Byte(ByteList::default()),
+            TagType::Short => List::Short(ShortList::default()),
+            TagType::Int => List::Int(IntList::default()),
+            TagType::Long => List::Long(LongList::default()),
+            TagType::Float => List::Float(FloatList::default()),
+            TagType::Double => List::Double(DoubleList::default()),
+            TagType::ByteArray => List::ByteArray(ByteArrayList::default()),
+            TagType::String => List::String(StringList::default()),
+            TagType::List => List::List(ListList::default()),
+            TagType::Compound => List::Compound(CompoundList::default()),
+            TagType::IntArray => List::IntArray(IntArrayList::default()),
+            TagType::LongArray => List::LongArray(LongArrayList::default()),
+        }
+    }
+
+    /// Returns the type of the elements in the list, exactly as it was
+    /// in the document. This is [TagType::End] for [List::End].
+    pub fn element_type(&self) -> TagType {
+        match self {
+            List::End => TagType::End,
+            List::Byte(_) => TagType::Byte,
+            List::Short(_) => TagType::Short,
+            List::Int(_) => TagType::Int,
+            List::Long(_) => TagType::Long,
+            List::Float(_) => TagType::Float,
+            List::Double(_) => TagType::Double,
+            List::ByteArray(_) => TagType::ByteArray,
+            List::String(_) => TagType::String,
+            List::Compound(_) => TagType::Compound,
+            List::List(_) => TagType::List,
+            List::IntArray(_) => TagType::IntArray,
+            List::LongArray(_) => TagType::LongArray,
+        }
+    }
+
+    /// Counts the elements of the list by type, indexed by `TagType as
+    /// usize`. Every element of a list has the same type, so most lists
+    /// have a single non-zero bucket. Lists of lists are the exception:
+    /// each inner list can have a different element type, so they're
+    /// counted by their element type instead, with empty inner lists
+    /// usually counted as [TagType::End].
+    pub fn element_type_histogram(&self) -> [usize; 13] {
+        let mut histogram = [0; 13];
+        match self {
+            List::List(lists) => {
+                for list in lists.iter() {
+                    histogram[list.element_type() as usize] += 1;
+                }
+            }
+            _ => histogram[self.element_type() as usize] = self.len(),
+        }
+        histogram
+    }
+
+    /// If this is a list of TAG_Byte, returns it. Otherwise, returns None.
+    pub fn as_byte_list(&self) -> Option<ByteList<'a>> {
+        if let List::Byte(list) = self {
+            Some(*list)
+        } else {
+            None
+        }
+    }
+
+    /// If this is a list of TAG_Short, returns it. Otherwise, returns None.
+    pub fn as_short_list(&self) -> Option<ShortList<'a>> {
+        if let List::Short(list) = self {
+            Some(*list)
+        } else {
+            None
+        }
+    }
+
+    /// If this is a list of TAG_Int, returns it. Otherwise, returns None.
+    pub fn as_int_list(&self) -> Option<IntList<'a>> {
+        if let List::Int(list) = self {
+            Some(*list)
+        } else {
+            None
+        }
+    }
+
+    /// If this is a list of TAG_Long, returns it. Otherwise, returns None.
+    pub fn as_long_list(&self) -> Option<LongList<'a>> {
+        if let List::Long(list) = self {
+            Some(*list)
+        } else {
+            None
+        }
+    }
+
+    /// If this is a list of TAG_Float, returns it. Otherwise, returns None.
+    pub fn as_float_list(&self) -> Option<FloatList<'a>> {
+        if let List::Float(list) = self {
+            Some(*list)
+        } else {
+            None
+        }
+    }
+
+    /// If this is a list of TAG_Double, returns it. Otherwise, returns None.
+    pub fn as_double_list(&self) -> Option<DoubleList<'a>> {
+        if let List::Double(list) = self {
+            Some(*list)
+        } else {
+            None
+        }
+    }
+
+    /// If this is a list of TAG_Double with exactly `N` elements,
+    /// returns them as an array. Otherwise, returns None. Useful for
+    /// things like `Pos` (3 elements) and `Rotation` (2 elements).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nobility::bin_decode::List;
+    /// # fn example(motion: List) {
+    /// if let Some([x, y, z]) = motion.to_f64_array::<3>() {
+    ///     println!("moving at {}, {}, {}", x, y, z);
+    /// }
+    /// # }
+    /// ```
+    pub fn to_f64_array<const N: usize>(&self) -> Option<[f64; N]> {
+        let list = self.as_double_list()?;
+        if list.len() != N {
+            return None;
+        }
+        let mut out = [0.0; N];
+        list.copy_into(&mut out);
+        Some(out)
+    }
+
+    /// If this is a list of TAG_Byte_Array, returns it. Otherwise, returns None.
+    pub fn as_byte_array_list(&self) -> Option<&ByteArrayList<'a>> {
+        if let List::ByteArray(list) = self {
+            Some(list)
+        } else {
+            None
+        }
+    }
+
+    /// If this is a list of TAG_String, returns it. Otherwise, returns None.
+    pub fn as_string_list(&self) -> Option<&StringList<'a>> {
+        if let List::String(list) = self {
+            Some(list)
+        } else {
+            None
+        }
+    }
+
+    /// If this is a list of TAG_Compound, returns it. Otherwise, returns None.
+    pub fn as_compound_list(&self) -> Option<&CompoundList<'a>> {
+        if let List::Compound(list) = self {
+            Some(list)
+        } else {
+            None
+        }
+    }
+
+    /// If this is a list of TAG_List, returns it. Otherwise, returns None.
+    pub fn as_list_list(&self) -> Option<&ListList<'a>> {
+        if let List::List(list) = self {
+            Some(list)
+        } else {
+            None
+        }
+    }
+
+    /// If this is a list of TAG_Int_Array, returns it. Otherwise, returns None.
+    pub fn as_int_array_list(&self) -> Option<&IntArrayList<'a>> {
+        if let List::IntArray(list) = self {
+            Some(list)
+        } else {
+            None
+        }
+    }
+
+    /// If this is a list of TAG_Long_Array, returns it. Otherwise, returns None.
+    pub fn as_long_array_list(&self) -> Option<&LongArrayList<'a>> {
+        if let List::LongArray(list) = self {
+            Some(list)
+        } else {
+            None
+        }
+    }
+
     /// Allows the list to be indexed without creating a case for each
     /// possible list type. The return value is wrapped in a Tag.
     ///
@@ -190,7 +409,8 @@ impl<'a> List<'a> {
     /// performed.
     pub fn get(&self, index: usize) -> Option<Tag<'a>> {
         match self {
-            List::Byte(list) => list.get(index).map(|&v| Tag::Byte(v as i8)),
+            List::End => None,
+            List::Byte(list) => list.get(index).map(Tag::Byte),
             List::Short(list) => list.get(index).map(Tag::Short),
             List::Int(list) => list.get(index).map(Tag::Int),
             List::Long(list) => list.get(index).map(Tag::Long),
@@ -214,6 +434,15 @@ impl<'a> List<'a> {
     }
 }
 
+impl<'s, 'a> IntoIterator for &'s List<'a> {
+    type Item = Tag<'s>;
+    type IntoIter = ListIter<'s>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 /// Iterator over the contents of [List], wrapped as a [Tag].
 pub struct ListIter<'a> {
     list: &'a List<'a>,
@@ -237,11 +466,6 @@ impl<'a> Iterator for ListIter<'a> {
 
 impl<'a> ExactSizeIterator for ListIter<'a> {
     fn len(&self) -> usize {
-        let len = self.list.len();
-        if self.index < len {
-            len - self.index
-        } else {
-            0
-        }
+        self.list.len().saturating_sub(self.index)
     }
 }
@@ -0,0 +1,85 @@
+//! Mapping byte offsets back to the tags that contain them.
+
+use crate::bin_decode::{
+    read_root_header, read_type, NbtParse, NbtString, ParseError, ParseOptions, Reader,
+};
+use crate::TagType;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Returns the path to the innermost tag whose bytes contain `offset`,
+/// or None if the offset is past the end of the root tag. See
+/// [Document::entry_at_offset][crate::bin_decode::Document::entry_at_offset].
+pub(crate) fn path_at_offset(
+    data: &[u8],
+    offset: usize,
+) -> Result<Option<Vec<String>>, ParseError> {
+    let mut reader = Reader::with_options(data, ParseOptions::default());
+    read_root_header(&mut reader)?;
+    let contents = reader.position;
+    // Checks the whole document first, so that the walk below doesn't
+    // have to deal with errors part way through.
+    reader.skip_value(TagType::Compound)?;
+    if offset >= reader.position {
+        return Ok(None);
+    }
+    reader.position = contents;
+    let mut path = vec![];
+    locate_compound(&mut reader, offset, &mut path)?;
+    Ok(Some(path))
+}
+
+fn locate_value(
+    reader: &mut Reader<'_>,
+    tag: TagType,
+    offset: usize,
+    path: &mut Vec<String>,
+) -> Result<(), ParseError> {
+    match tag {
+        TagType::Compound => locate_compound(reader, offset, path),
+        TagType::List => locate_list(reader, offset, path),
+        _ => Ok(()),
+    }
+}
+
+fn locate_compound(
+    reader: &mut Reader<'_>,
+    offset: usize,
+    path: &mut Vec<String>,
+) -> Result<(), ParseError> {
+    loop {
+        let start = reader.position;
+        let tag = read_type(reader)?;
+        if tag == TagType::End {
+            return Ok(());
+        }
+        let name = NbtString::read(reader)?;
+        let value = reader.position;
+        reader.skip_value(tag)?;
+        if (start..reader.position).contains(&offset) {
+            path.push(name.to_string_lossy().into_owned());
+            reader.position = value;
+            return locate_value(reader, tag, offset, path);
+        }
+    }
+}
+
+fn locate_list(
+    reader: &mut Reader<'_>,
+    offset: usize,
+    path: &mut Vec<String>,
+) -> Result<(), ParseError> {
+    let tag = read_type(reader)?;
+    let len = reader.read_array_length()?;
+    for index in 0..len {
+        let start = reader.position;
+        reader.skip_value(tag)?;
+        if (start..reader.position).contains(&offset) {
+            path.push(index.to_string());
+            reader.position = start;
+            return locate_value(reader, tag, offset, path);
+        }
+    }
+    Ok(())
+}
@@ -9,21 +9,15 @@
 //!
 //! # Example
 //!
-//! ```rust
+#![cfg_attr(feature = "std", doc = "```rust")]
+#![cfg_attr(not(feature = "std"), doc = "```ignore")]
 //! # use std::error::Error;
 //! #
 //! # fn main() -> Result<(), Box<dyn Error>> {
 //! use nobility::bin_decode::Document;
-//! use std::fs::File;
-//! use std::io::Read;
-//!
-//! let mut file = File::open("files/hello_world.nbt")?;
-//! let mut data = vec![];
-//! file.read_to_end(&mut data)?;
-//! let cursor = std::io::Cursor::new(data);
 //!
 //! // Either copies the data (plaintext) or decompresses it (gzip).
-//! let doc = Document::load(cursor)?;
+//! let doc = Document::open("files/hello_world.nbt")?;
 //!
 //! // Returns the root tag's name, and the root tag (always a Compound tag).
 //! // Both of these are borrowing the data inside the Document.
@@ -36,29 +30,68 @@
 //! ```
 
 use crate::TagType;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use byteorder::LittleEndian;
 use byteorder::{BigEndian, ByteOrder};
+use core::convert::TryFrom;
+use core::fmt;
+#[cfg(feature = "std")]
 use flate2::read::GzDecoder;
-use std::fmt;
+#[cfg(feature = "std")]
 use std::io::Error as IoError;
+#[cfg(feature = "std")]
 use std::io::Read;
+#[cfg(feature = "std")]
+use std::path::Path;
 
 mod array;
+#[cfg(feature = "std")]
+mod bedrock;
 mod compound;
+#[cfg(feature = "serde")]
+mod de;
+mod event;
+mod index;
 mod internal;
+#[cfg(feature = "json")]
+mod json;
+mod lazy;
 mod list;
-mod string;
+mod locate;
+mod outline;
+#[cfg(feature = "std")]
+mod owned;
+#[cfg(feature = "std")]
+mod shared;
+pub(crate) mod string;
+mod visitor;
 
+pub(crate) use array::NbtPrimitive;
 pub use array::{IntArray, LongArray, NbtArray, NbtArrayIter};
-pub use compound::{Compound, Entry};
+pub use compound::{Compound, Entry, RequireError};
+#[cfg(feature = "serde")]
+pub use de::{from_compound, from_document, DeserializeError};
+pub use event::{Event, EventReader};
+pub use index::CompoundIndex;
 pub(crate) use internal::{NbtParse, Reader};
+pub use lazy::LazyCompound;
 pub use list::{
-    ByteArrayList, CompoundList, DoubleList, FloatList, IntArrayList, IntList, List, ListIter,
-    ListList, LongArrayList, LongList, NbtList, ShortList, StringList,
+    ByteArrayList, ByteList, CompoundList, DoubleList, FloatList, IntArrayList, IntList, List,
+    ListIter, ListList, LongArrayList, LongList, NbtList, ShortList, StringList,
 };
-pub use string::NbtString;
+#[cfg(feature = "std")]
+pub use owned::{OwnedCompound, OwnedList, OwnedTag};
+#[cfg(feature = "std")]
+pub use shared::{SharedCompound, SharedList, SharedTag};
+pub use string::{Cesu8DecodingError, NbtString, OwnedNbtString};
+pub use visitor::{visit, Visit, Visitor};
 
 /// Failures which can occur while parsing an NBT document.
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum ParseError {
     /// End of file happens when the document is truncated, i.e. we were
@@ -66,18 +99,20 @@ pub enum ParseError {
     /// ended instead. In particular, this can happen when:
     ///
     /// - Any primitive type is not followed by enough bytes to
-    /// construct the primitive type (TAG_Byte, TAG_Short, TAG_Int,
-    /// TAG_Long, TAG_Float, TAG_Double).
+    ///   construct the primitive type (TAG_Byte, TAG_Short, TAG_Int,
+    ///   TAG_Long, TAG_Float, TAG_Double).
     ///
     /// - A TAG_Byte_Array, TAG_String, or TAG_Int_Array is not followed
-    /// by as many elements as it says it is.
+    ///   by as many elements as it says it is.
     ///
     /// - A TAG_List does not have as many elements as it says it does, or
-    /// we get an EOF while attempting to parse an element.
+    ///   we get an EOF while attempting to parse an element.
     ///
     /// - A TAG_Compound does not have a TAG_End to terminate it, or we
-    /// get an EOF while attempting to parse a tag.
-    EOF,
+    ///   get an EOF while attempting to parse a tag.
+    ///
+    /// The offset is where the data that's missing should have started.
+    EOF { offset: usize },
     /// This happens when there is an unknown tag type in the
     /// stream. This can happen if Mojang adds new tag types, if a
     /// document has third party tag types, if the file is corrupted, or
@@ -86,35 +121,333 @@ pub enum ParseError {
     /// This happens when we found a TAG_End where we shouldn't
     /// have. TAG_End is only supposed to be found after having a
     /// TAG_Compound, to terminate it. Places we can find this include
-    /// as the root tag of a document and inside of a List.
-    UnexpectedEndTag,
+    /// as the root tag of a document and inside of a List. The offset is
+    /// where parsing stopped after reading it.
+    UnexpectedEndTag { offset: usize },
     /// This library assumes that NBT documents always have a root
     /// TAG_Compound, and if this invariant fails this error will be
-    /// generated.
-    IncorrectStartTag { tag: TagType },
+    /// generated. The offset is that of the root tag.
+    IncorrectStartTag { tag: TagType, offset: usize },
+    /// Lists and Compounds were nested deeper than allowed by
+    /// [Limits::max_depth]. This is used to prevent maliciously crafted
+    /// documents from overflowing the stack.
+    DepthExceeded { offset: usize },
+    /// A string was longer than allowed by [Limits::max_string_len].
+    /// The offset is that of the string's length prefix.
+    StringTooLong { length: usize, offset: usize },
+    /// There was more data after the end of the root tag. Only returned
+    /// by [Document::parse_strict].
+    TrailingData { offset: usize },
+    /// The root tag's name wasn't the one that was expected. Only
+    /// returned by [Document::parse_expecting_root]. Names that aren't
+    /// valid CESU-8 are decoded lossily. The root name always starts at
+    /// offset 1, so unlike the other variants, no offset is included.
+    UnexpectedRootName { expected: Box<str>, found: Box<str> },
+    /// A TAG_List of a fixed-size type declared more elements than there
+    /// are bytes left in the document. The offset is that of the
+    /// list's length prefix.
+    ListLengthMismatch { length: usize, offset: usize },
+    /// A chunk in a region file uses a compression scheme that isn't
+    /// supported, or is stored in a separate `.mcc` file. Only returned
+    /// by [RegionFile::chunk][crate::region::RegionFile::chunk].
+    UnknownCompression { scheme: u8, offset: usize },
+    /// A chunk in a region file points outside of the file, or its
+    /// data couldn't be decompressed. Only returned by
+    /// [RegionFile::chunk][crate::region::RegionFile::chunk].
+    CorruptChunk { offset: usize },
 }
 
 impl fmt::Display for ParseError {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            ParseError::EOF => write!(fmt, "Unexpected end of file"),
+            ParseError::EOF { offset } => {
+                write!(fmt, "Unexpected end of file at offset {:#x}", offset)
+            }
             ParseError::UnknownTag { tag, offset } => {
                 write!(fmt, "Unknown tag {} at offset {:#x}", tag, offset)
             }
-            ParseError::UnexpectedEndTag => write!(fmt, "Unexpected end tag in document"),
-            ParseError::IncorrectStartTag { tag } => {
+            ParseError::UnexpectedEndTag { offset } => {
+                write!(fmt, "Unexpected end tag at offset {:#x}", offset)
+            }
+            ParseError::IncorrectStartTag { tag, offset } => {
+                write!(
+                    fmt,
+                    "Document starts with tag {:?} at offset {:#x}, it should only start with Compound.",
+                    tag, offset
+                )
+            }
+            ParseError::DepthExceeded { offset } => {
+                write!(
+                    fmt,
+                    "Maximum nesting depth exceeded at offset {:#x}",
+                    offset
+                )
+            }
+            ParseError::StringTooLong { length, offset } => {
+                write!(
+                    fmt,
+                    "String of length {} exceeds limit at offset {:#x}",
+                    length, offset
+                )
+            }
+            ParseError::TrailingData { offset } => {
+                write!(
+                    fmt,
+                    "Unexpected data after root tag at offset {:#x}",
+                    offset
+                )
+            }
+            ParseError::UnexpectedRootName { expected, found } => {
                 write!(
                     fmt,
-                    "Document starts with tag {:?}, it should only start with Compound.",
-                    tag
+                    "Expected root tag to be named {:?}, found {:?}",
+                    expected, found
                 )
             }
+            ParseError::ListLengthMismatch { length, offset } => {
+                write!(
+                    fmt,
+                    "List of {} elements at offset {:#x} is longer than the rest of the document",
+                    length, offset
+                )
+            }
+            ParseError::UnknownCompression { scheme, offset } => {
+                write!(
+                    fmt,
+                    "Unknown chunk compression scheme {} at offset {:#x}",
+                    scheme, offset
+                )
+            }
+            ParseError::CorruptChunk { offset } => {
+                write!(fmt, "Corrupt chunk at offset {:#x}", offset)
+            }
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for ParseError {}
 
+#[cfg(feature = "std")]
+/// Covers every failure that can happen between reading the raw bytes
+/// of a document and having fully decoded it, for use by convenience
+/// functions like [parse_owned].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum NbtError {
+    /// Reading or decompressing the input failed.
+    Io(IoError),
+    /// The document is malformed, see [ParseError].
+    Parse(ParseError),
+    /// A string in the document is not valid CESU-8.
+    Decode(Cesu8DecodingError),
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for NbtError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NbtError::Io(err) => write!(fmt, "Failed to read document: {}", err),
+            NbtError::Parse(err) => write!(fmt, "Failed to parse document: {}", err),
+            NbtError::Decode(err) => write!(fmt, "Failed to decode string: {}", err),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NbtError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NbtError::Io(err) => Some(err),
+            NbtError::Parse(err) => Some(err),
+            NbtError::Decode(err) => Some(err),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<IoError> for NbtError {
+    fn from(err: IoError) -> NbtError {
+        NbtError::Io(err)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<ParseError> for NbtError {
+    fn from(err: ParseError) -> NbtError {
+        NbtError::Parse(err)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<Cesu8DecodingError> for NbtError {
+    fn from(err: Cesu8DecodingError) -> NbtError {
+        NbtError::Decode(err)
+    }
+}
+
+/// Failures from the [TryFrom] conversions of [Tag] into Rust types.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum TagConversionError {
+    /// The tag doesn't have the type that corresponds to the Rust type.
+    /// Numbers are never converted between types.
+    Mismatch { expected: TagType, found: TagType },
+    /// The tag is a string, but isn't valid CESU-8.
+    Decode(Cesu8DecodingError),
+}
+
+impl fmt::Display for TagConversionError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TagConversionError::Mismatch { expected, found } => {
+                write!(fmt, "Expected tag {:?}, found {:?}", expected, found)
+            }
+            TagConversionError::Decode(err) => write!(fmt, "Failed to decode string: {}", err),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TagConversionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TagConversionError::Mismatch { .. } => None,
+            TagConversionError::Decode(err) => Some(err),
+        }
+    }
+}
+
+/// Restrictions placed on a document while parsing it, used to protect
+/// against documents crafted to exhaust resources.
+///
+/// # Example
+///
+/// ```rust
+/// use nobility::bin_decode::Limits;
+///
+/// let limits = Limits {
+///     max_depth: 64,
+///     ..Limits::default()
+/// };
+/// # let _ = limits;
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Limits {
+    /// The maximum number of Lists and Compounds that can be nested
+    /// inside of each other, including the root Compound. Defaults to
+    /// 512.
+    pub max_depth: usize,
+    /// The maximum length of a string in bytes, including compound
+    /// keys and the root name. Defaults to no limit, other than what
+    /// the length prefix can represent.
+    pub max_string_len: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Limits {
+        Limits {
+            max_depth: 512,
+            max_string_len: usize::MAX,
+        }
+    }
+}
+
+/// A function which decodes a length prefix from the start of `data`.
+/// It returns the decoded length, followed by the number of bytes that
+/// the prefix took up. If `data` is too short to contain the prefix,
+/// None should be returned.
+///
+/// See [ParseOptions] for how these are used.
+pub type LengthReader = fn(data: &[u8]) -> Option<(usize, usize)>;
+
+/// The [LengthReader] used for string lengths by default, a big endian
+/// u16.
+pub fn read_u16_length(data: &[u8]) -> Option<(usize, usize)> {
+    if data.len() < 2 {
+        None
+    } else {
+        Some((BigEndian::read_u16(data) as usize, 2))
+    }
+}
+
+/// The [LengthReader] used for list and array lengths by default, a big
+/// endian u32.
+pub fn read_u32_length(data: &[u8]) -> Option<(usize, usize)> {
+    if data.len() < 4 {
+        None
+    } else {
+        Some((BigEndian::read_u32(data) as usize, 4))
+    }
+}
+
+/// Returns true if `data` starts with a gzip header. This checks for
+/// the gzip magic number followed by the deflate compression method,
+/// which is the only one that gzip defines. NBT documents always start
+/// with TAG_Compound, so they can't be mistaken for this.
+///
+/// This is the check that [Document::load] uses to decide whether to
+/// decompress its input.
+pub fn is_gzip(data: &[u8]) -> bool {
+    data.starts_with(&[0x1f, 0x8b, 8])
+}
+
+/// Returns true if `data` starts with a zlib header, as used for chunks
+/// in Anvil region files. This checks that the compression method is
+/// deflate with a valid window size, and that the header checksum is
+/// correct. NBT documents always start with TAG_Compound, which isn't a
+/// valid compression method, so they can't be mistaken for this.
+pub fn is_zlib(data: &[u8]) -> bool {
+    match data {
+        [cmf, flg, ..] => {
+            cmf & 0x0f == 8 && cmf >> 4 <= 7 && (*cmf as u16 * 256 + *flg as u16) % 31 == 0
+        }
+        _ => false,
+    }
+}
+
+/// Settings which control how a document is parsed. The defaults are
+/// correct for Java Edition NBT, the other settings are for parsing
+/// variants of the format.
+///
+/// # Example
+///
+/// ```rust
+/// use nobility::bin_decode::ParseOptions;
+///
+/// fn read_u8_length(data: &[u8]) -> Option<(usize, usize)> {
+///     data.first().map(|&len| (len as usize, 1))
+/// }
+///
+/// let options = ParseOptions {
+///     array_length: read_u8_length,
+///     ..ParseOptions::default()
+/// };
+/// # let _ = options;
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct ParseOptions {
+    /// Limits applied to the document.
+    pub limits: Limits,
+    /// Reads the length prefix of TAG_String, and of the names of
+    /// tags. Defaults to [read_u16_length].
+    pub string_length: LengthReader,
+    /// Reads the length prefix of TAG_List, TAG_Byte_Array,
+    /// TAG_Int_Array, and TAG_Long_Array. Defaults to
+    /// [read_u32_length].
+    pub array_length: LengthReader,
+}
+
+impl Default for ParseOptions {
+    fn default() -> ParseOptions {
+        ParseOptions {
+            limits: Limits::default(),
+            string_length: read_u16_length,
+            array_length: read_u32_length,
+        }
+    }
+}
+
 /// Representation for all values that a tag can be.
 #[derive(Clone, Debug, PartialEq)]
 #[non_exhaustive]
@@ -150,7 +483,9 @@ pub enum Tag<'a> {
 impl<'a> Tag<'a> {
     pub(crate) fn read(tag: TagType, reader: &mut Reader<'a>) -> Result<Tag<'a>, ParseError> {
         match tag {
-            TagType::End => Err(ParseError::UnexpectedEndTag),
+            TagType::End => Err(ParseError::UnexpectedEndTag {
+                offset: reader.position,
+            }),
             TagType::Byte => Ok(Tag::Byte(reader.advance(1)?[0] as i8)),
             TagType::Short => Ok(Tag::Short(BigEndian::read_i16(reader.advance(2)?))),
             TagType::Int => Ok(Tag::Int(BigEndian::read_i32(reader.advance(4)?))),
@@ -193,6 +528,17 @@ impl<'a> Tag<'a> {
         }
     }
 
+    /// NBT has no boolean type, so TAG_Byte is used instead. Returns
+    /// false for a byte of 0 and true for a byte of 1. Other values and
+    /// other tags return None.
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            Tag::Byte(0) => Some(false),
+            Tag::Byte(1) => Some(true),
+            _ => None,
+        }
+    }
+
     /// If this tag is a byte array, returns it. Otherwise, returns None.
     pub fn as_byte_array(&self) -> Option<&[u8]> {
         if let Tag::ByteArray(value) = self {
@@ -202,6 +548,16 @@ impl<'a> Tag<'a> {
         }
     }
 
+    /// Treats a byte array as a bitset and returns the bit at `index`.
+    /// Bits are numbered LSB-first, so `index` refers to bit
+    /// `index % 8` of byte `index / 8`, where bit 0 is the least
+    /// significant bit. Returns None if this isn't a byte array or the
+    /// index is out of range.
+    pub fn byte_array_bit(&self, index: usize) -> Option<bool> {
+        let byte = self.as_byte_array()?.get(index / 8)?;
+        Some(byte & (1 << (index % 8)) != 0)
+    }
+
     /// If this tag is a [Compound], returns it. Otherwise, returns None.
     pub fn as_compound(&self) -> Option<&Compound<'a>> {
         if let Tag::Compound(value) = self {
@@ -283,31 +639,163 @@ impl<'a> Tag<'a> {
     pub fn to_uuid(&self) -> Option<uuid::Uuid> {
         self.to_uuid_bytes().map(uuid::Uuid::from_bytes)
     }
+
+    /// Describes the tag in a single short line, such as
+    /// `Compound(11 fields)`, `List<Long>(5)`, or `String("Bananrama")`.
+    /// Only the size of containers and arrays is shown, and long strings
+    /// are cut off, which makes this suitable for log messages where the
+    /// [Debug][fmt::Debug] output would be too verbose.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(feature = "std", doc = "```rust")]
+    #[cfg_attr(not(feature = "std"), doc = "```ignore")]
+    /// # use std::error::Error;
+    /// # use nobility::bin_decode::Document;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let doc = Document::load(Document::doctest_demo())?;
+    /// let (_name, root) = doc.parse()?;
+    /// let name = root.get("name").unwrap();
+    /// assert_eq!(name.summary(), r#"String("Bananrama")"#);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn summary(&self) -> String {
+        outline::summarize_tag(self)
+    }
+}
+
+// Allows conversions like `i32::try_from(&tag)`. Like the PartialEq
+// impls below, the variant has to match exactly.
+macro_rules! tag_try_from_impl {
+    ($ty:ty, $variant:ident) => {
+        impl<'a, 'b> TryFrom<&'b Tag<'a>> for $ty {
+            type Error = TagConversionError;
+
+            fn try_from(tag: &'b Tag<'a>) -> Result<$ty, TagConversionError> {
+                match tag {
+                    Tag::$variant(value) => Ok(*value),
+                    _ => Err(TagConversionError::Mismatch {
+                        expected: TagType::$variant,
+                        found: tag.tag_type(),
+                    }),
+                }
+            }
+        }
+    };
+}
+
+tag_try_from_impl!(i8, Byte);
+tag_try_from_impl!(i16, Short);
+tag_try_from_impl!(i32, Int);
+tag_try_from_impl!(i64, Long);
+tag_try_from_impl!(f32, Float);
+tag_try_from_impl!(f64, Double);
+tag_try_from_impl!(NbtString<'a>, String);
+
+impl<'a, 'b> TryFrom<&'b Tag<'a>> for String {
+    type Error = TagConversionError;
+
+    fn try_from(tag: &'b Tag<'a>) -> Result<String, TagConversionError> {
+        let string = NbtString::try_from(tag)?;
+        let decoded = string.decode().map_err(TagConversionError::Decode)?;
+        Ok(decoded.into_owned())
+    }
+}
+
+// Allows comparisons like `tag == 20i32`. These only match when the
+// variant is the one for that type, numbers are never coerced.
+macro_rules! tag_eq_impl {
+    ($ty:ty, $variant:ident) => {
+        impl<'a> PartialEq<$ty> for Tag<'a> {
+            fn eq(&self, other: &$ty) -> bool {
+                matches!(self, Tag::$variant(value) if value == other)
+            }
+        }
+    };
+}
+
+tag_eq_impl!(i8, Byte);
+tag_eq_impl!(i16, Short);
+tag_eq_impl!(i32, Int);
+tag_eq_impl!(i64, Long);
+tag_eq_impl!(f32, Float);
+tag_eq_impl!(f64, Double);
+
+impl<'a> PartialEq<str> for Tag<'a> {
+    fn eq(&self, other: &str) -> bool {
+        matches!(self, Tag::String(value) if *value == other)
+    }
+}
+
+impl<'a, 'b> PartialEq<&'b str> for Tag<'a> {
+    fn eq(&self, other: &&'b str) -> bool {
+        self == *other
+    }
 }
 
 pub(crate) fn read_type(reader: &mut Reader<'_>) -> Result<TagType, ParseError> {
     let offset = reader.position;
-    match reader.advance(1)?[0] {
-        0 => Ok(TagType::End),
-        1 => Ok(TagType::Byte),
-        2 => Ok(TagType::Short),
-        3 => Ok(TagType::Int),
-        4 => Ok(TagType::Long),
-        5 => Ok(TagType::Float),
-        6 => Ok(TagType::Double),
-        7 => Ok(TagType::ByteArray),
-        8 => Ok(TagType::String),
-        9 => Ok(TagType::List),
-        10 => Ok(TagType::Compound),
-        11 => Ok(TagType::IntArray),
-        12 => Ok(TagType::LongArray),
-        tag => Err(ParseError::UnknownTag { tag, offset }),
-    }
+    let tag = reader.advance(1)?[0];
+    TagType::from_u8(tag).ok_or(ParseError::UnknownTag { tag, offset })
 }
 
 fn read_byte_array<'a>(reader: &mut Reader<'a>) -> Result<&'a [u8], ParseError> {
-    let len = BigEndian::read_u32(reader.advance(4)?);
-    Ok(reader.advance(len as usize)?)
+    let len = reader.read_array_length()?;
+    reader.advance(len)
+}
+
+#[cfg(feature = "std")]
+/// Loads, parses, and converts a document into an [OwnedCompound] in
+/// one step. Like [Document::load], gzip compression is detected
+/// automatically. This is the simplest way of reading a document when
+/// zero-copy parsing isn't needed.
+///
+/// # Example
+///
+/// ```rust
+/// # use std::error::Error;
+/// #
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use nobility::bin_decode::parse_owned;
+///
+/// let data = std::fs::read("files/hello_world.nbt")?;
+/// let (name, root) = parse_owned(&data)?;
+/// assert_eq!(name, "hello world");
+/// # let _ = root;
+/// # Ok(())
+/// # }
+/// ```
+pub fn parse_owned(data: &[u8]) -> Result<(String, OwnedCompound), NbtError> {
+    let doc = Document::load(std::io::Cursor::new(data))?;
+    let (name, root) = doc.parse()?;
+    Ok((name.decode()?.into_owned(), root.to_owned_compound()?))
+}
+
+/// Parses an uncompressed document that's already in memory, borrowing
+/// from `data` instead of copying it into a [Document] first. This is
+/// the same as [Document::parse], and is available without the `std`
+/// feature.
+///
+/// # Errors
+///
+/// Same as [Document::parse].
+///
+/// # Example
+///
+/// ```rust
+/// use nobility::bin_decode::parse_borrowed;
+///
+/// // An empty compound named "hi".
+/// let data = [10, 0, 2, b'h', b'i', 0];
+/// let (name, root) = parse_borrowed(&data).unwrap();
+/// assert_eq!(name.as_bytes(), b"hi");
+/// assert!(root.is_empty());
+/// ```
+pub fn parse_borrowed(data: &[u8]) -> Result<(NbtString<'_>, Compound<'_>), ParseError> {
+    let mut reader = Reader::with_options(data, ParseOptions::default());
+    read_root(&mut reader)
 }
 
 /// Represents an NBT document and is the owner of the data contained in
@@ -315,7 +803,8 @@ fn read_byte_array<'a>(reader: &mut Reader<'a>) -> Result<&'a [u8], ParseError>
 ///
 /// # Example
 ///
-/// ```rust
+#[cfg_attr(feature = "std", doc = "```rust")]
+#[cfg_attr(not(feature = "std"), doc = "```ignore")]
 /// # use std::error::Error;
 /// #
 /// # fn main() -> Result<(), Box<dyn Error>> {
@@ -341,6 +830,7 @@ pub struct Document {
 
 impl Document {
     #[doc(hidden)]
+    #[cfg(feature = "std")]
     pub fn doctest_demo() -> impl Read + Clone {
         use std::fs::File;
 
@@ -354,23 +844,180 @@ impl Document {
     /// are compressed with gzip will be automatically decompressed,
     /// otherwise the data will just be copied.
     ///
+    /// Gzip is detected using the magic number at the start of the
+    /// stream, so any combination of optional header fields (file
+    /// name, comment, extra data, and header checksum) is accepted.
+    ///
     /// # Errors
     ///
     /// Errors from this function are either from the input [Read]
     /// object or from [GzDecoder].
+    #[cfg(feature = "std")]
     pub fn load<R: Read + Clone>(mut input: R) -> Result<Document, IoError> {
-        let mut decoder = GzDecoder::new(input.clone());
         let mut data = vec![];
-        if decoder.header().is_some() {
-            // Valid gzip stream
-            decoder.read_to_end(&mut data)?;
+        if read_is_gzip(input.clone())? {
+            GzDecoder::new(input).read_to_end(&mut data)?;
         } else {
-            // Not a gzip stream
             input.read_to_end(&mut data)?;
         }
         Ok(Document { data })
     }
 
+    /// Loads a document which is known not to be compressed, such as a
+    /// chunk that has already been decompressed. Unlike
+    /// [Document::load], the input isn't checked for gzip first, which
+    /// avoids reading the start of it twice, and doesn't need to
+    /// implement [Clone].
+    ///
+    /// # Errors
+    ///
+    /// Errors from this function are from the input [Read] object.
+    #[cfg(feature = "std")]
+    pub fn load_uncompressed<R: Read>(mut input: R) -> Result<Document, IoError> {
+        let mut data = vec![];
+        input.read_to_end(&mut data)?;
+        Ok(Document { data })
+    }
+
+    /// Reads a document from a file. Like [Document::load], gzip
+    /// compression is detected automatically.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be read, or if it can't be
+    /// decompressed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::error::Error;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use nobility::bin_decode::Document;
+    ///
+    /// let doc = Document::open("files/hello_world.nbt")?;
+    /// let (name, _root) = doc.parse()?;
+    /// assert_eq!(name, "hello world");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Document, IoError> {
+        let data = std::fs::read(path)?;
+        if read_is_gzip(&data[..])? {
+            Document::load(&data[..])
+        } else {
+            // Avoids the copy that load() would make.
+            Ok(Document { data })
+        }
+    }
+
+    /// Same as [Document::load], but reads from a tokio
+    /// [AsyncRead][tokio::io::AsyncRead] without blocking. Only reading
+    /// the input is asynchronous. Once all of it has been read, gzip is
+    /// detected and decompressed synchronously like [Document::load]
+    /// does. Requires the `tokio` feature.
+    ///
+    /// # Errors
+    ///
+    /// Errors from this function are either from the input or from
+    /// [GzDecoder].
+    #[cfg(feature = "tokio")]
+    pub async fn load_async<R>(mut input: R) -> Result<Document, IoError>
+    where
+        R: tokio::io::AsyncRead + Unpin,
+    {
+        use tokio::io::AsyncReadExt;
+
+        let mut data = vec![];
+        input.read_to_end(&mut data).await?;
+        if is_gzip(&data) {
+            Document::load(&data[..])
+        } else {
+            Ok(Document { data })
+        }
+    }
+
+    /// Loads a `level.dat` file from Bedrock edition. These start with
+    /// an 8 byte header, containing a storage version and the length of
+    /// the rest of the file, followed by a little-endian NBT document.
+    ///
+    /// The document is converted to the same format Java edition uses,
+    /// so it can be parsed normally afterwards. The storage version is
+    /// returned alongside it.
+    ///
+    /// # Errors
+    ///
+    /// Fails if reading from `input` fails. Returns [ParseError::EOF] if
+    /// the file is shorter than the header says, and
+    /// [ParseError::TrailingData] if it's longer. Otherwise, returns any
+    /// error found while converting the document, which are the same as
+    /// for [Document::parse]. Offsets in those errors are relative to
+    /// the end of the header.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use std::error::Error;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use nobility::bin_decode::Document;
+    ///
+    /// let file = std::fs::File::open("level.dat")?;
+    /// let (version, doc) = Document::load_bedrock_leveldat(file)?;
+    /// let (_name, root) = doc.parse()?;
+    /// println!("Storage version {}: {:#?}", version, root);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn load_bedrock_leveldat<R: Read>(mut input: R) -> Result<(u32, Document), NbtError> {
+        let mut data = vec![];
+        input.read_to_end(&mut data)?;
+        if data.len() < 8 {
+            let offset = data.len();
+            return Err(ParseError::EOF { offset }.into());
+        }
+        let version = LittleEndian::read_u32(&data[0..4]);
+        let length = LittleEndian::read_u32(&data[4..8]) as usize;
+        let body = &data[8..];
+        if body.len() < length {
+            let offset = data.len();
+            return Err(ParseError::EOF { offset }.into());
+        } else if body.len() > length {
+            return Err(ParseError::TrailingData { offset: 8 + length }.into());
+        }
+        let data = bedrock::to_big_endian(body)?;
+        Ok((version, Document { data }))
+    }
+
+    /// Loads a little-endian document without a header, such as one
+    /// written by
+    /// [NbtWriter::new_little_endian][crate::bin_encode::NbtWriter::new_little_endian]
+    /// or stored in Bedrock edition's world database. Like
+    /// [Document::load_bedrock_leveldat], the document is converted to
+    /// the format Java edition uses so it can be parsed normally.
+    ///
+    /// # Errors
+    ///
+    /// Fails if reading from `input` fails, or if the document is
+    /// malformed. Conversion errors are the same as for
+    /// [Document::parse].
+    #[cfg(feature = "std")]
+    pub fn load_little_endian<R: Read>(mut input: R) -> Result<Document, NbtError> {
+        let mut data = vec![];
+        input.read_to_end(&mut data)?;
+        let data = bedrock::to_big_endian(&data)?;
+        Ok(Document { data })
+    }
+
+    /// Returns the decompressed contents of the document. Offsets such
+    /// as the ones in [CompoundIndex] and [ParseError] refer to
+    /// positions within this.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
     /// Parses the document and returns the name and contents of the
     /// root tag.
     ///
@@ -388,15 +1035,463 @@ impl Document {
     /// 3. The specification has changed due to a new Minecraft version.
     ///    This will likely generate [ParseError::UnknownTag].
     /// 4. There's a bug in the parser.
-    pub fn parse(&self) -> Result<(NbtString, Compound), ParseError> {
-        let mut reader = Reader::new(&self.data);
+    pub fn parse(&self) -> Result<(NbtString<'_>, Compound<'_>), ParseError> {
+        self.parse_with_limits(Limits::default())
+    }
+
+    /// Same as [Document::parse], but returns a [LazyCompound], which
+    /// only parses values when they're looked up.
+    ///
+    /// # Errors
+    ///
+    /// Same as [Document::parse]. The whole document is still checked.
+    pub fn parse_lazy(&self) -> Result<(NbtString<'_>, LazyCompound<'_>), ParseError> {
+        let mut reader = Reader::with_options(&self.data, ParseOptions::default());
+        read_root(&mut reader)
+    }
+
+    /// Reads the names of the root tag's entries along with the type and
+    /// offset of each value, without parsing any of the values. See
+    /// [CompoundIndex] for details.
+    ///
+    /// # Errors
+    ///
+    /// Same as [Document::parse]. The whole document is still checked.
+    pub fn parse_index(&self) -> Result<(NbtString<'_>, CompoundIndex), ParseError> {
+        let mut reader = Reader::with_options(&self.data, ParseOptions::default());
+        read_root(&mut reader)
+    }
+
+    /// Looks up a key in the root compound without building any of the
+    /// other entries. Values before the key are skipped over rather
+    /// than parsed, and parsing stops once it's found. A [Compound] has
+    /// already parsed all of its values, which is why this is on
+    /// Document instead.
+    ///
+    /// # Errors
+    ///
+    /// Same as [Document::parse], but only for the part of the document
+    /// before the end of the value that was found. Anything after it is
+    /// not checked.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(feature = "std", doc = "```rust")]
+    #[cfg_attr(not(feature = "std"), doc = "```ignore")]
+    /// # use std::error::Error;
+    /// # use nobility::bin_decode::Document;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let input = Document::doctest_demo();
+    /// let doc = Document::load(input)?;
+    /// if let Some(name) = doc.get_skipping("name")? {
+    ///     println!("{:?}", name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_skipping(&self, key: &str) -> Result<Option<Tag<'_>>, ParseError> {
+        let mut reader = Reader::with_options(&self.data, ParseOptions::default());
+        read_root_header(&mut reader)?;
+        reader.enter()?;
+        loop {
+            let tag = read_type(&mut reader)?;
+            if tag == TagType::End {
+                return Ok(None);
+            }
+            let name = NbtString::read(&mut reader)?;
+            if name == key {
+                return Tag::read(tag, &mut reader).map(Some);
+            }
+            reader.skip_value(tag)?;
+        }
+    }
+
+    /// Same as [Document::parse], but accepts any type of root tag
+    /// instead of only TAG_Compound. Some tools and protocols use other
+    /// root types, such as a TAG_List.
+    ///
+    /// # Errors
+    ///
+    /// Same as [Document::parse], except that
+    /// [ParseError::IncorrectStartTag] is never returned. A root of
+    /// TAG_End results in [ParseError::UnexpectedEndTag].
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(feature = "std", doc = "```rust")]
+    #[cfg_attr(not(feature = "std"), doc = "```ignore")]
+    /// # use std::error::Error;
+    /// # use nobility::bin_decode::Document;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let input = Document::doctest_demo();
+    /// let doc = Document::load(input)?;
+    /// let (name, root) = doc.parse_any()?;
+    /// println!("{}: {:?}", name, root.tag_type());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn parse_any(&self) -> Result<(NbtString<'_>, Tag<'_>), ParseError> {
+        let mut reader = Reader::with_options(&self.data, ParseOptions::default());
         let tag = read_type(&mut reader)?;
-        if tag != TagType::Compound {
-            return Err(ParseError::IncorrectStartTag { tag });
+        if tag == TagType::End {
+            return Err(ParseError::UnexpectedEndTag {
+                offset: reader.position,
+            });
         }
         let name = NbtString::read(&mut reader)?;
-        let root = Compound::read(&mut reader)?;
-        Ok((name, root))
+        let value = Tag::read(tag, &mut reader)?;
+        Ok((name, value))
+    }
+
+    /// Same as [Document::parse], but allows specifying the [Limits]
+    /// used instead of the defaults.
+    pub fn parse_with_limits(
+        &self,
+        limits: Limits,
+    ) -> Result<(NbtString<'_>, Compound<'_>), ParseError> {
+        self.parse_with_options(ParseOptions {
+            limits,
+            ..ParseOptions::default()
+        })
+    }
+
+    /// Same as [Document::parse], but allows specifying all of the
+    /// [ParseOptions] used.
+    pub fn parse_with_options(
+        &self,
+        options: ParseOptions,
+    ) -> Result<(NbtString<'_>, Compound<'_>), ParseError> {
+        let mut reader = Reader::with_options(&self.data, options);
+        read_root(&mut reader)
+    }
+
+    /// Returns an [EventReader] for walking through the document one tag
+    /// at a time, without building a tree.
+    pub fn events(&self) -> EventReader<'_> {
+        EventReader::new(Reader::with_options(&self.data, ParseOptions::default()))
+    }
+
+    /// Walks through the document, calling the methods of `visitor` for
+    /// each tag. See [visit] for details.
+    pub fn visit<'a, V>(&'a self, visitor: &mut V) -> Result<(), ParseError>
+    where
+        V: Visitor<'a>,
+    {
+        visit(&self.data, visitor)
+    }
+
+    /// Scans the document without decoding it, and returns the largest
+    /// number of elements declared by any list or array. Loaders can use
+    /// this to reject documents that would need too much memory before
+    /// parsing them. Documents without any lists or arrays return 0.
+    ///
+    /// # Errors
+    ///
+    /// Same as [Document::parse]. A declared length which is longer
+    /// than the rest of the document results in [ParseError::EOF], so
+    /// such a length is never returned.
+    pub fn max_declared_length(&self) -> Result<usize, ParseError> {
+        let mut reader = Reader::with_options(&self.data, ParseOptions::default());
+        read_root_header(&mut reader)?;
+        reader.skip_value(TagType::Compound)?;
+        Ok(reader.max_array_length())
+    }
+
+    /// Same as [Document::parse], but checks that the root tag has the
+    /// expected name and only returns its contents. For example, chunks
+    /// use `"Level"` and `level.dat` uses `"Data"`.
+    ///
+    /// # Errors
+    ///
+    /// In addition to the errors returned by [Document::parse], this
+    /// returns [ParseError::UnexpectedRootName] if the name is
+    /// different.
+    pub fn parse_expecting_root(&self, expected_name: &str) -> Result<Compound<'_>, ParseError> {
+        let (name, root) = self.parse()?;
+        if name == expected_name {
+            Ok(root)
+        } else {
+            Err(ParseError::UnexpectedRootName {
+                expected: expected_name.into(),
+                found: name.to_string_lossy().into(),
+            })
+        }
+    }
+
+    /// Parses only the first `max_entries` entries of the root
+    /// compound, stopping early instead of reading the rest of the
+    /// document. Nothing is skipped, so this is cheap for showing a
+    /// preview of a large file. If the root has fewer entries, all of
+    /// them are returned.
+    ///
+    /// # Errors
+    ///
+    /// Same as [Document::parse], except that errors after the
+    /// returned entries are not detected.
+    pub fn preview_root(&self, max_entries: usize) -> Result<Vec<Entry<'_>>, ParseError> {
+        let mut reader = Reader::with_options(&self.data, ParseOptions::default());
+        read_root_header(&mut reader)?;
+        Compound::read_prefix(&mut reader, max_entries)
+    }
+
+    /// Finds the tag whose bytes contain `offset`, which is a position
+    /// in the decompressed document, and returns its path from the
+    /// root. Names in the path are decoded lossily, and elements of
+    /// lists are given by their index. The path is empty for the
+    /// root's own header and its TAG_End.
+    ///
+    /// Returns None if `offset` is past the end of the root tag.
+    ///
+    /// # Errors
+    ///
+    /// Same as [Document::parse]. The whole document is checked, even
+    /// if the offset is near the start.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(feature = "std", doc = "```rust")]
+    #[cfg_attr(not(feature = "std"), doc = "```ignore")]
+    /// # use std::error::Error;
+    /// # use nobility::bin_decode::Document;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let input = Document::doctest_demo();
+    /// let doc = Document::load(input)?;
+    /// // The root is named "hello world", so its entries start at 14.
+    /// assert_eq!(doc.entry_at_offset(20)?, Some(vec!["name".to_owned()]));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn entry_at_offset(&self, offset: usize) -> Result<Option<Vec<String>>, ParseError> {
+        locate::path_at_offset(&self.data, offset)
+    }
+
+    /// Parses the entries of the root compound one at a time, as the
+    /// iterator advances. Only one entry is kept in memory at a time,
+    /// which helps with documents that have a very large root compound.
+    ///
+    /// After an error has been returned, the iterator ends.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(feature = "std", doc = "```rust")]
+    #[cfg_attr(not(feature = "std"), doc = "```ignore")]
+    /// # use std::error::Error;
+    /// # use nobility::bin_decode::Document;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let input = Document::doctest_demo();
+    /// let doc = Document::load(input)?;
+    /// for entry in doc.root_entries() {
+    ///     let entry = entry?;
+    ///     println!("{}: {:?}", entry.name(), entry.value());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn root_entries(&self) -> impl Iterator<Item = Result<Entry<'_>, ParseError>> + '_ {
+        let mut reader = Reader::with_options(&self.data, ParseOptions::default());
+        let mut started = false;
+        let mut done = false;
+        core::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+            match next_root_entry(&mut reader, &mut started) {
+                Ok(Some(entry)) => Some(Ok(entry)),
+                Ok(None) => {
+                    done = true;
+                    None
+                }
+                Err(err) => {
+                    done = true;
+                    Some(Err(err))
+                }
+            }
+        })
+    }
+
+    /// Parses as much of the root compound as possible, for recovering
+    /// data from documents that are truncated or partially corrupt.
+    /// Entries are read until one fails to parse, and everything before
+    /// it is returned along with the error and the offset at which the
+    /// failed entry starts. Nothing after that point is recovered.
+    ///
+    /// If the document parses successfully, this returns the same
+    /// compound as [Document::parse] and no error.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(feature = "std", doc = "```rust")]
+    #[cfg_attr(not(feature = "std"), doc = "```ignore")]
+    /// # use nobility::bin_decode::Document;
+    /// # let input = Document::doctest_demo();
+    /// let doc = Document::load(input).unwrap();
+    /// let (root, error) = doc.parse_recovering();
+    /// if let Some((error, offset)) = error {
+    ///     eprintln!("Recovered {} entries, then {} at {:#x}", root.len(), error, offset);
+    /// }
+    /// ```
+    pub fn parse_recovering(&self) -> (Compound<'_>, Option<(ParseError, usize)>) {
+        let mut reader = Reader::with_options(&self.data, ParseOptions::default());
+        let mut started = false;
+        let mut entries = vec![];
+        loop {
+            let offset = reader.position;
+            match next_root_entry(&mut reader, &mut started) {
+                Ok(Some(entry)) => entries.push(entry),
+                Ok(None) => return (Compound::from_entries(entries), None),
+                Err(err) => return (Compound::from_entries(entries), Some((err, offset))),
+            }
+        }
+    }
+
+    /// Parses several documents which have been concatenated together,
+    /// one after another until all of `data` has been consumed. The
+    /// data must already be decompressed. Offsets in errors are
+    /// relative to the start of `data`.
+    ///
+    /// # Errors
+    ///
+    /// Same as [Document::parse]. Trailing bytes which don't form a
+    /// complete document result in an error, usually
+    /// [ParseError::EOF].
+    pub fn split_all(data: &[u8]) -> Result<Vec<(NbtString<'_>, Compound<'_>)>, ParseError> {
+        let mut reader = Reader::with_options(data, ParseOptions::default());
+        let mut documents = vec![];
+        while reader.remaining() > 0 {
+            documents.push(read_root(&mut reader)?);
+        }
+        Ok(documents)
+    }
+
+    /// Same as [Document::parse], but also checks that the root tag is
+    /// the last thing in the document. What is allowed to come after it
+    /// is decided by the [TrailingPolicy].
+    ///
+    /// # Errors
+    ///
+    /// In addition to the errors returned by [Document::parse], this
+    /// returns [ParseError::TrailingData] if there are bytes after the
+    /// root tag that the policy doesn't allow.
+    pub fn parse_strict(
+        &self,
+        trailing: TrailingPolicy,
+    ) -> Result<(NbtString<'_>, Compound<'_>), ParseError> {
+        let mut reader = Reader::with_options(&self.data, ParseOptions::default());
+        let result = read_root(&mut reader)?;
+        let offset = reader.position;
+        let rest = &self.data[offset..];
+        let allowed = match trailing {
+            TrailingPolicy::Forbid => rest.is_empty(),
+            TrailingPolicy::AllowZeroPadding => rest.iter().all(|&byte| byte == 0),
+        };
+        if allowed {
+            Ok(result)
+        } else {
+            Err(ParseError::TrailingData { offset })
+        }
+    }
+
+    /// Gives a rough estimate of how well the document would compress,
+    /// without actually compressing it. This is the Shannon entropy of
+    /// the byte frequencies, scaled to be between 0 (every byte is the
+    /// same, very compressible) and 1 (looks like random data, not
+    /// compressible). An empty document returns 0.
+    ///
+    /// Repeated sequences aren't taken into account, so NBT with many
+    /// repeated names usually compresses better than this suggests.
+    #[cfg(feature = "std")]
+    pub fn estimate_compressibility(&self) -> f32 {
+        if self.data.is_empty() {
+            return 0.0;
+        }
+        let mut counts = [0usize; 256];
+        for &byte in &self.data {
+            counts[byte as usize] += 1;
+        }
+        let total = self.data.len() as f64;
+        let mut entropy = 0.0;
+        for &count in counts.iter().filter(|&&count| count > 0) {
+            let probability = count as f64 / total;
+            entropy -= probability * probability.log2();
+        }
+        // 8 bits per byte is the most possible.
+        (entropy / 8.0) as f32
+    }
+}
+
+/// Decides which bytes are allowed to follow the root tag when using
+/// [Document::parse_strict].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TrailingPolicy {
+    /// Nothing may come after the root tag.
+    Forbid,
+    /// Only zero bytes may come after the root tag. Chunks extracted
+    /// from Anvil region files are padded to the next 4KiB sector this
+    /// way.
+    AllowZeroPadding,
+}
+
+/// Same as [is_gzip], but reads the start of the stream from `input`.
+#[cfg(feature = "std")]
+fn read_is_gzip<R: Read>(input: R) -> Result<bool, IoError> {
+    let mut magic = vec![];
+    input.take(3).read_to_end(&mut magic)?;
+    Ok(is_gzip(&magic))
+}
+
+fn next_root_entry<'a>(
+    reader: &mut Reader<'a>,
+    started: &mut bool,
+) -> Result<Option<Entry<'a>>, ParseError> {
+    if !*started {
+        *started = true;
+        read_root_header(reader)?;
+        reader.enter()?;
+    }
+    Compound::read_entry(reader)
+}
+
+/// Reads a whole document, with the root compound parsed as `T`.
+pub(crate) fn read_root<'a, T>(reader: &mut Reader<'a>) -> Result<(NbtString<'a>, T), ParseError>
+where
+    T: NbtParse<'a>,
+{
+    let name = read_root_header(reader)?;
+    let root = T::read(reader)?;
+    Ok((name, root))
+}
+
+/// Reads the tag type and name at the start of a document, leaving the
+/// reader at the start of the root compound's contents.
+pub(crate) fn read_root_header<'a>(reader: &mut Reader<'a>) -> Result<NbtString<'a>, ParseError> {
+    read_root_type(reader)?;
+    NbtString::read(reader)
+}
+
+/// Reads the tag type at the start of a document, and checks that it's
+/// a TAG_Compound.
+pub(crate) fn read_root_type(reader: &mut Reader<'_>) -> Result<(), ParseError> {
+    let tag = read_type(reader)?;
+    if tag != TagType::Compound {
+        return Err(ParseError::IncorrectStartTag {
+            tag,
+            offset: reader.position - 1,
+        });
+    }
+    Ok(())
+}
+
+/// Wraps a document that's already in memory and not compressed,
+/// without copying it. This is also available without the `std`
+/// feature.
+impl From<Vec<u8>> for Document {
+    fn from(data: Vec<u8>) -> Document {
+        Document { data }
     }
 }
 
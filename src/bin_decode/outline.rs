@@ -0,0 +1,122 @@
+use crate::bin_decode::{Compound, List, NbtArray, NbtPrimitive, Tag};
+use alloc::format;
+use alloc::string::String;
+use core::fmt::{Display, Write};
+
+/// Strings longer than this many characters are cut off.
+const MAX_STRING_CHARS: usize = 40;
+/// Arrays and lists of primitives show at most this many elements.
+const MAX_ELEMENTS: usize = 8;
+/// Strings in a [Tag::summary] are cut off after this many characters.
+const MAX_SUMMARY_CHARS: usize = 16;
+
+pub(crate) fn summarize_tag(tag: &Tag) -> String {
+    match tag {
+        Tag::Byte(value) => format!("Byte({})", value),
+        Tag::Short(value) => format!("Short({})", value),
+        Tag::Int(value) => format!("Int({})", value),
+        Tag::Long(value) => format!("Long({})", value),
+        Tag::Float(value) => format!("Float({})", value),
+        Tag::Double(value) => format!("Double({})", value),
+        Tag::String(value) => {
+            let (shown, ellipsis) = truncate(&value.to_string_lossy(), MAX_SUMMARY_CHARS);
+            format!("String({:?}{})", shown, ellipsis)
+        }
+        Tag::ByteArray(value) => format!("ByteArray({})", value.len()),
+        Tag::IntArray(value) => format!("IntArray({})", value.len()),
+        Tag::LongArray(value) => format!("LongArray({})", value.len()),
+        Tag::List(list) => format!("List<{:?}>({})", list.element_type(), list.len()),
+        Tag::Compound(compound) => format!("Compound({} fields)", compound.len()),
+    }
+}
+
+/// Returns the first `max_chars` characters of `value`, along with an
+/// ellipsis if anything was cut off.
+fn truncate(value: &str, max_chars: usize) -> (String, &'static str) {
+    let mut chars = value.chars();
+    let shown = chars.by_ref().take(max_chars).collect();
+    let ellipsis = if chars.next().is_some() { "..." } else { "" };
+    (shown, ellipsis)
+}
+
+pub(crate) fn outline_compound(out: &mut String, compound: &Compound, depth: usize) {
+    for entry in compound {
+        outline_tag(out, &entry.name().to_string_lossy(), entry.value(), depth);
+    }
+}
+
+fn outline_tag(out: &mut String, key: &str, tag: &Tag, depth: usize) {
+    let _ = write!(out, "{:indent$}{}: ", "", key, indent = depth * 2);
+    match tag {
+        Tag::Byte(value) => line(out, format_args!("Byte {}", value)),
+        Tag::Short(value) => line(out, format_args!("Short {}", value)),
+        Tag::Int(value) => line(out, format_args!("Int {}", value)),
+        Tag::Long(value) => line(out, format_args!("Long {}", value)),
+        Tag::Float(value) => line(out, format_args!("Float {}", value)),
+        Tag::Double(value) => line(out, format_args!("Double {}", value)),
+        Tag::String(value) => {
+            let (shown, ellipsis) = truncate(&value.to_string_lossy(), MAX_STRING_CHARS);
+            line(out, format_args!("String {:?}{}", shown, ellipsis));
+        }
+        Tag::ByteArray(value) => {
+            out.push_str("ByteArray ");
+            elements(out, value.iter(), value.len());
+        }
+        Tag::IntArray(value) => {
+            out.push_str("IntArray ");
+            array(out, value);
+        }
+        Tag::LongArray(value) => {
+            out.push_str("LongArray ");
+            array(out, value);
+        }
+        Tag::List(list) => outline_list(out, list, depth),
+        Tag::Compound(compound) => {
+            line(out, format_args!("Compound ({} entries)", compound.len()));
+            outline_compound(out, compound, depth + 1);
+        }
+    }
+}
+
+fn outline_list(out: &mut String, list: &List, depth: usize) {
+    let _ = write!(out, "List<{:?}> ", list.element_type());
+    match list {
+        List::End => elements(out, core::iter::empty::<u8>(), 0),
+        List::Byte(list) => array(out, list),
+        List::Short(list) => array(out, list),
+        List::Int(list) => array(out, list),
+        List::Long(list) => array(out, list),
+        List::Float(list) => array(out, list),
+        List::Double(list) => array(out, list),
+        _ => {
+            line(out, format_args!("({} elements)", list.len()));
+            for (index, element) in list.iter().enumerate() {
+                outline_tag(out, &format!("[{}]", index), &element, depth + 1);
+            }
+        }
+    }
+}
+
+fn array<T: NbtPrimitive + Display>(out: &mut String, array: &NbtArray<T>) {
+    elements(out, array.iter(), array.len());
+}
+
+/// Writes up to [MAX_ELEMENTS] elements, followed by the total count.
+fn elements<T: Display>(out: &mut String, iter: impl Iterator<Item = T>, len: usize) {
+    out.push('[');
+    for (index, element) in iter.take(MAX_ELEMENTS).enumerate() {
+        if index > 0 {
+            out.push_str(", ");
+        }
+        let _ = write!(out, "{}", element);
+    }
+    if len > MAX_ELEMENTS {
+        out.push_str(", ...");
+    }
+    line(out, format_args!("] ({} elements)", len));
+}
+
+fn line(out: &mut String, args: core::fmt::Arguments) {
+    let _ = out.write_fmt(args);
+    out.push('\n');
+}
@@ -0,0 +1,214 @@
+use crate::bin_decode::{Cesu8DecodingError, Compound, List, Tag};
+use core::slice::Iter as SliceIter;
+
+/// Owned counterpart to [Tag]. Unlike the other decoder types, this
+/// does not borrow from the [Document][crate::bin_decode::Document], so
+/// it can outlive it. Strings are decoded to UTF-8 as part of the
+/// conversion.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum OwnedTag {
+    /// A small i8 integer.
+    Byte(i8),
+    /// An i16 integer.
+    Short(i16),
+    /// An i32 integer.
+    Int(i32),
+    /// An i64 integer.
+    Long(i64),
+    /// An f32 number.
+    Float(f32),
+    /// An f64 number.
+    Double(f64),
+    /// An array of raw bytes.
+    ByteArray(Vec<u8>),
+    /// A decoded string.
+    String(String),
+    /// An array of i32.
+    IntArray(Vec<i32>),
+    /// An array of i64.
+    LongArray(Vec<i64>),
+    /// A list of elements which all have the same type.
+    List(OwnedList),
+    /// A list of key/value pairs, creating a dictionary.
+    Compound(OwnedCompound),
+}
+
+/// Owned counterpart to [List], with the same variants.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum OwnedList {
+    End,
+    Byte(Vec<i8>),
+    Short(Vec<i16>),
+    Int(Vec<i32>),
+    Long(Vec<i64>),
+    Float(Vec<f32>),
+    Double(Vec<f64>),
+    ByteArray(Vec<Vec<u8>>),
+    String(Vec<String>),
+    Compound(Vec<OwnedCompound>),
+    List(Vec<OwnedList>),
+    IntArray(Vec<Vec<i32>>),
+    LongArray(Vec<Vec<i64>>),
+}
+
+impl OwnedList {
+    /// Returns the number of elements in the list.
+    pub fn len(&self) -> usize {
+        match self {
+            OwnedList::End => 0,
+            OwnedList::Byte(list) => list.len(),
+            OwnedList::Short(list) => list.len(),
+            OwnedList::Int(list) => list.len(),
+            OwnedList::Long(list) => list.len(),
+            OwnedList::Float(list) => list.len(),
+            OwnedList::Double(list) => list.len(),
+            OwnedList::ByteArray(list) => list.len(),
+            OwnedList::String(list) => list.len(),
+            OwnedList::Compound(list) => list.len(),
+            OwnedList::List(list) => list.len(),
+            OwnedList::IntArray(list) => list.len(),
+            OwnedList::LongArray(list) => list.len(),
+        }
+    }
+
+    /// Returns true if there are no elements in the list.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Owned counterpart to [Compound]. Entries are kept in the same order
+/// as they appeared in the document.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OwnedCompound {
+    entries: Vec<(String, OwnedTag)>,
+}
+
+impl OwnedCompound {
+    /// Creates an empty compound.
+    pub fn new() -> OwnedCompound {
+        OwnedCompound { entries: vec![] }
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends an entry to the end of the compound. Duplicate keys are
+    /// not checked for.
+    pub fn push(&mut self, name: String, value: OwnedTag) {
+        self.entries.push((name, value));
+    }
+
+    /// Returns the value of the first entry with the given name, if it
+    /// exists.
+    pub fn get(&self, key: &str) -> Option<&OwnedTag> {
+        self.entries
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns an iterator over the entries.
+    pub fn iter(&self) -> SliceIter<'_, (String, OwnedTag)> {
+        self.entries.iter()
+    }
+
+    /// Converts into a Vec of key/value pairs.
+    pub fn into_vec(self) -> Vec<(String, OwnedTag)> {
+        self.entries
+    }
+}
+
+impl<'a> Tag<'a> {
+    /// Creates an [OwnedTag] which no longer borrows from the document.
+    ///
+    /// # Errors
+    ///
+    /// Fails if any string or compound key contained in the tag is
+    /// invalid CESU-8.
+    pub fn to_owned_tag(&self) -> Result<OwnedTag, Cesu8DecodingError> {
+        Ok(match self {
+            Tag::Byte(value) => OwnedTag::Byte(*value),
+            Tag::Short(value) => OwnedTag::Short(*value),
+            Tag::Int(value) => OwnedTag::Int(*value),
+            Tag::Long(value) => OwnedTag::Long(*value),
+            Tag::Float(value) => OwnedTag::Float(*value),
+            Tag::Double(value) => OwnedTag::Double(*value),
+            Tag::ByteArray(value) => OwnedTag::ByteArray(value.to_vec()),
+            Tag::String(value) => OwnedTag::String(value.decode()?.into_owned()),
+            Tag::IntArray(value) => OwnedTag::IntArray(value.to_vec()),
+            Tag::LongArray(value) => OwnedTag::LongArray(value.to_vec()),
+            Tag::List(value) => OwnedTag::List(value.to_owned_list()?),
+            Tag::Compound(value) => OwnedTag::Compound(value.to_owned_compound()?),
+        })
+    }
+}
+
+impl<'a> List<'a> {
+    /// Creates an [OwnedList] which no longer borrows from the document.
+    ///
+    /// # Errors
+    ///
+    /// Fails if any string or compound key contained in the list is
+    /// invalid CESU-8.
+    pub fn to_owned_list(&self) -> Result<OwnedList, Cesu8DecodingError> {
+        Ok(match self {
+            List::End => OwnedList::End,
+            List::Byte(list) => OwnedList::Byte(list.to_vec()),
+            List::Short(list) => OwnedList::Short(list.to_vec()),
+            List::Int(list) => OwnedList::Int(list.to_vec()),
+            List::Long(list) => OwnedList::Long(list.to_vec()),
+            List::Float(list) => OwnedList::Float(list.to_vec()),
+            List::Double(list) => OwnedList::Double(list.to_vec()),
+            List::ByteArray(list) => {
+                OwnedList::ByteArray(list.iter().map(|v| v.to_vec()).collect())
+            }
+            List::String(list) => OwnedList::String(
+                list.iter()
+                    .map(|v| v.decode().map(|v| v.into_owned()))
+                    .collect::<Result<_, _>>()?,
+            ),
+            List::Compound(list) => OwnedList::Compound(
+                list.iter()
+                    .map(Compound::to_owned_compound)
+                    .collect::<Result<_, _>>()?,
+            ),
+            List::List(list) => OwnedList::List(
+                list.iter()
+                    .map(List::to_owned_list)
+                    .collect::<Result<_, _>>()?,
+            ),
+            List::IntArray(list) => OwnedList::IntArray(list.iter().map(|v| v.to_vec()).collect()),
+            List::LongArray(list) => {
+                OwnedList::LongArray(list.iter().map(|v| v.to_vec()).collect())
+            }
+        })
+    }
+}
+
+impl<'a> Compound<'a> {
+    /// Creates an [OwnedCompound] which no longer borrows from the
+    /// document.
+    ///
+    /// # Errors
+    ///
+    /// Fails if any string or key contained in the compound is invalid
+    /// CESU-8.
+    pub fn to_owned_compound(&self) -> Result<OwnedCompound, Cesu8DecodingError> {
+        let mut entries = Vec::with_capacity(self.len());
+        for entry in self.iter() {
+            let name = entry.name().decode()?.into_owned();
+            entries.push((name, entry.value().to_owned_tag()?));
+        }
+        Ok(OwnedCompound { entries })
+    }
+}
@@ -0,0 +1,395 @@
+use crate::bin_decode::{
+    Cesu8DecodingError, Compound, List, OwnedCompound, OwnedList, OwnedTag, Tag,
+};
+use core::slice::Iter as SliceIter;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// Same as [OwnedTag], except that compounds are stored behind an
+/// [Arc], so that identical compounds can share memory. Created by
+/// [Compound::to_owned_compound_deduplicated].
+///
+/// Only compounds are shared. Lists, arrays, and strings are copied
+/// each time they appear, even when they're identical. [Arc] is used
+/// rather than [Rc][std::rc::Rc] so that the result is [Send] and
+/// [Sync] like [OwnedTag], and can be handed to other threads.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum SharedTag {
+    /// A small i8 integer.
+    Byte(i8),
+    /// An i16 integer.
+    Short(i16),
+    /// An i32 integer.
+    Int(i32),
+    /// An i64 integer.
+    Long(i64),
+    /// An f32 number.
+    Float(f32),
+    /// An f64 number.
+    Double(f64),
+    /// An array of raw bytes.
+    ByteArray(Vec<u8>),
+    /// A decoded string.
+    String(String),
+    /// An array of i32.
+    IntArray(Vec<i32>),
+    /// An array of i64.
+    LongArray(Vec<i64>),
+    /// A list of elements which all have the same type.
+    List(SharedList),
+    /// A list of key/value pairs, creating a dictionary.
+    Compound(Arc<SharedCompound>),
+}
+
+impl SharedTag {
+    /// Creates an [OwnedTag] with the same contents, copying any
+    /// compounds that are shared.
+    pub fn to_owned_tag(&self) -> OwnedTag {
+        match self {
+            SharedTag::Byte(value) => OwnedTag::Byte(*value),
+            SharedTag::Short(value) => OwnedTag::Short(*value),
+            SharedTag::Int(value) => OwnedTag::Int(*value),
+            SharedTag::Long(value) => OwnedTag::Long(*value),
+            SharedTag::Float(value) => OwnedTag::Float(*value),
+            SharedTag::Double(value) => OwnedTag::Double(*value),
+            SharedTag::ByteArray(value) => OwnedTag::ByteArray(value.clone()),
+            SharedTag::String(value) => OwnedTag::String(value.clone()),
+            SharedTag::IntArray(value) => OwnedTag::IntArray(value.clone()),
+            SharedTag::LongArray(value) => OwnedTag::LongArray(value.clone()),
+            SharedTag::List(value) => OwnedTag::List(value.to_owned_list()),
+            SharedTag::Compound(value) => OwnedTag::Compound(value.to_owned_compound()),
+        }
+    }
+}
+
+/// Same as [OwnedList], except that compounds are stored behind an
+/// [Arc]. See [SharedTag].
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum SharedList {
+    End,
+    Byte(Vec<i8>),
+    Short(Vec<i16>),
+    Int(Vec<i32>),
+    Long(Vec<i64>),
+    Float(Vec<f32>),
+    Double(Vec<f64>),
+    ByteArray(Vec<Vec<u8>>),
+    String(Vec<String>),
+    Compound(Vec<Arc<SharedCompound>>),
+    List(Vec<SharedList>),
+    IntArray(Vec<Vec<i32>>),
+    LongArray(Vec<Vec<i64>>),
+}
+
+impl SharedList {
+    /// Returns the number of elements in the list.
+    pub fn len(&self) -> usize {
+        match self {
+            SharedList::End => 0,
+            SharedList::Byte(list) => list.len(),
+            SharedList::Short(list) => list.len(),
+            SharedList::Int(list) => list.len(),
+            SharedList::Long(list) => list.len(),
+            SharedList::Float(list) => list.len(),
+            SharedList::Double(list) => list.len(),
+            SharedList::ByteArray(list) => list.len(),
+            SharedList::String(list) => list.len(),
+            SharedList::Compound(list) => list.len(),
+            SharedList::List(list) => list.len(),
+            SharedList::IntArray(list) => list.len(),
+            SharedList::LongArray(list) => list.len(),
+        }
+    }
+
+    /// Returns true if there are no elements in the list.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Creates an [OwnedList] with the same contents, copying any
+    /// compounds that are shared.
+    pub fn to_owned_list(&self) -> OwnedList {
+        match self {
+            SharedList::End => OwnedList::End,
+            SharedList::Byte(list) => OwnedList::Byte(list.clone()),
+            SharedList::Short(list) => OwnedList::Short(list.clone()),
+            SharedList::Int(list) => OwnedList::Int(list.clone()),
+            SharedList::Long(list) => OwnedList::Long(list.clone()),
+            SharedList::Float(list) => OwnedList::Float(list.clone()),
+            SharedList::Double(list) => OwnedList::Double(list.clone()),
+            SharedList::ByteArray(list) => OwnedList::ByteArray(list.clone()),
+            SharedList::String(list) => OwnedList::String(list.clone()),
+            SharedList::Compound(list) => {
+                OwnedList::Compound(list.iter().map(|v| v.to_owned_compound()).collect())
+            }
+            SharedList::List(list) => {
+                OwnedList::List(list.iter().map(SharedList::to_owned_list).collect())
+            }
+            SharedList::IntArray(list) => OwnedList::IntArray(list.clone()),
+            SharedList::LongArray(list) => OwnedList::LongArray(list.clone()),
+        }
+    }
+}
+
+/// Same as [OwnedCompound], except that nested compounds are stored
+/// behind an [Arc]. See [SharedTag].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SharedCompound {
+    entries: Vec<(String, SharedTag)>,
+}
+
+impl SharedCompound {
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the value of the first entry with the given name, if it
+    /// exists.
+    pub fn get(&self, key: &str) -> Option<&SharedTag> {
+        self.entries
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns an iterator over the entries.
+    pub fn iter(&self) -> SliceIter<'_, (String, SharedTag)> {
+        self.entries.iter()
+    }
+
+    /// Creates an [OwnedCompound] with the same contents, copying any
+    /// compounds that are shared.
+    pub fn to_owned_compound(&self) -> OwnedCompound {
+        let mut compound = OwnedCompound::new();
+        for (name, value) in &self.entries {
+            compound.push(name.clone(), value.to_owned_tag());
+        }
+        compound
+    }
+}
+
+impl<'a> Compound<'a> {
+    /// Same as [Compound::to_owned_compound], but nested compounds with
+    /// identical contents are only stored once, and share the same
+    /// [Arc]. This uses less memory for repetitive documents, such as
+    /// chunks with many identical block entities, at the cost of hashing
+    /// every compound during the conversion. Only compounds are shared,
+    /// see [SharedTag].
+    ///
+    /// Compounds containing NaN are never considered identical, since
+    /// NaN isn't equal to itself.
+    ///
+    /// # Errors
+    ///
+    /// Same as [Compound::to_owned_compound].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::error::Error;
+    /// # use nobility::bin_decode::Document;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let input = Document::doctest_demo();
+    /// let doc = Document::load(input)?;
+    /// let (_name, root) = doc.parse()?;
+    /// let shared = root.to_owned_compound_deduplicated()?;
+    /// assert_eq!(shared.to_owned_compound(), root.to_owned_compound()?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_owned_compound_deduplicated(&self) -> Result<SharedCompound, Cesu8DecodingError> {
+        convert_compound(self, &mut Interner::default())
+    }
+}
+
+/// Keeps track of the compounds that have been converted so far, so
+/// that identical ones can be shared.
+#[derive(Default)]
+struct Interner {
+    compounds: HashMap<u64, Vec<Arc<SharedCompound>>>,
+}
+
+impl Interner {
+    fn share(&mut self, compound: SharedCompound) -> Arc<SharedCompound> {
+        let mut hasher = DefaultHasher::new();
+        hash_compound(&compound, &mut hasher);
+        let candidates = self.compounds.entry(hasher.finish()).or_default();
+        if let Some(existing) = candidates
+            .iter()
+            .find(|existing| same_compound(existing, &compound))
+        {
+            return existing.clone();
+        }
+        let compound = Arc::new(compound);
+        candidates.push(compound.clone());
+        compound
+    }
+}
+
+/// Hashes the contents of a compound. Nested compounds have already
+/// been shared by the time their parent is hashed, so they're hashed by
+/// address instead of being walked again.
+fn hash_compound(compound: &SharedCompound, hasher: &mut DefaultHasher) {
+    compound.len().hash(hasher);
+    for (name, value) in compound.iter() {
+        name.hash(hasher);
+        hash_tag(value, hasher);
+    }
+}
+
+fn hash_tag(tag: &SharedTag, hasher: &mut DefaultHasher) {
+    std::mem::discriminant(tag).hash(hasher);
+    match tag {
+        SharedTag::Byte(value) => value.hash(hasher),
+        SharedTag::Short(value) => value.hash(hasher),
+        SharedTag::Int(value) => value.hash(hasher),
+        SharedTag::Long(value) => value.hash(hasher),
+        SharedTag::Float(value) => value.to_bits().hash(hasher),
+        SharedTag::Double(value) => value.to_bits().hash(hasher),
+        SharedTag::ByteArray(value) => value.hash(hasher),
+        SharedTag::String(value) => value.hash(hasher),
+        SharedTag::IntArray(value) => value.hash(hasher),
+        SharedTag::LongArray(value) => value.hash(hasher),
+        SharedTag::List(list) => hash_list(list, hasher),
+        SharedTag::Compound(compound) => Arc::as_ptr(compound).hash(hasher),
+    }
+}
+
+fn hash_list(list: &SharedList, hasher: &mut DefaultHasher) {
+    std::mem::discriminant(list).hash(hasher);
+    match list {
+        SharedList::End => {}
+        SharedList::Byte(list) => list.hash(hasher),
+        SharedList::Short(list) => list.hash(hasher),
+        SharedList::Int(list) => list.hash(hasher),
+        SharedList::Long(list) => list.hash(hasher),
+        SharedList::Float(list) => list.iter().for_each(|v| v.to_bits().hash(hasher)),
+        SharedList::Double(list) => list.iter().for_each(|v| v.to_bits().hash(hasher)),
+        SharedList::ByteArray(list) => list.hash(hasher),
+        SharedList::String(list) => list.hash(hasher),
+        SharedList::Compound(list) => list.iter().for_each(|v| Arc::as_ptr(v).hash(hasher)),
+        SharedList::List(list) => list.iter().for_each(|v| hash_list(v, hasher)),
+        SharedList::IntArray(list) => list.hash(hasher),
+        SharedList::LongArray(list) => list.hash(hasher),
+    }
+}
+
+/// Compares two compounds for [Interner::share]. Like
+/// [hash_compound], nested compounds are compared by address, as
+/// identical ones have already been shared, so checking a candidate
+/// doesn't walk the whole subtree again.
+fn same_compound(left: &SharedCompound, right: &SharedCompound) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right.iter())
+            .all(|((left_name, left), (right_name, right))| {
+                left_name == right_name && same_tag(left, right)
+            })
+}
+
+fn same_tag(left: &SharedTag, right: &SharedTag) -> bool {
+    match (left, right) {
+        (SharedTag::List(left), SharedTag::List(right)) => same_list(left, right),
+        (SharedTag::Compound(left), SharedTag::Compound(right)) => Arc::ptr_eq(left, right),
+        _ => left == right,
+    }
+}
+
+fn same_list(left: &SharedList, right: &SharedList) -> bool {
+    match (left, right) {
+        (SharedList::Compound(left), SharedList::Compound(right)) => {
+            left.len() == right.len()
+                && left
+                    .iter()
+                    .zip(right.iter())
+                    .all(|(left, right)| Arc::ptr_eq(left, right))
+        }
+        (SharedList::List(left), SharedList::List(right)) => {
+            left.len() == right.len()
+                && left
+                    .iter()
+                    .zip(right.iter())
+                    .all(|(left, right)| same_list(left, right))
+        }
+        _ => left == right,
+    }
+}
+
+fn convert_tag(tag: &Tag, interner: &mut Interner) -> Result<SharedTag, Cesu8DecodingError> {
+    Ok(match tag {
+        Tag::Byte(value) => SharedTag::Byte(*value),
+        Tag::Short(value) => SharedTag::Short(*value),
+        Tag::Int(value) => SharedTag::Int(*value),
+        Tag::Long(value) => SharedTag::Long(*value),
+        Tag::Float(value) => SharedTag::Float(*value),
+        Tag::Double(value) => SharedTag::Double(*value),
+        Tag::ByteArray(value) => SharedTag::ByteArray(value.to_vec()),
+        Tag::String(value) => SharedTag::String(value.decode()?.into_owned()),
+        Tag::IntArray(value) => SharedTag::IntArray(value.to_vec()),
+        Tag::LongArray(value) => SharedTag::LongArray(value.to_vec()),
+        Tag::List(value) => SharedTag::List(convert_list(value, interner)?),
+        Tag::Compound(value) => SharedTag::Compound(convert_shared(value, interner)?),
+    })
+}
+
+fn convert_list(list: &List, interner: &mut Interner) -> Result<SharedList, Cesu8DecodingError> {
+    Ok(match list {
+        List::End => SharedList::End,
+        List::Byte(list) => SharedList::Byte(list.to_vec()),
+        List::Short(list) => SharedList::Short(list.to_vec()),
+        List::Int(list) => SharedList::Int(list.to_vec()),
+        List::Long(list) => SharedList::Long(list.to_vec()),
+        List::Float(list) => SharedList::Float(list.to_vec()),
+        List::Double(list) => SharedList::Double(list.to_vec()),
+        List::ByteArray(list) => SharedList::ByteArray(list.iter().map(|v| v.to_vec()).collect()),
+        List::String(list) => SharedList::String(
+            list.iter()
+                .map(|v| v.decode().map(|v| v.into_owned()))
+                .collect::<Result<_, _>>()?,
+        ),
+        List::Compound(list) => SharedList::Compound(
+            list.iter()
+                .map(|v| convert_shared(v, interner))
+                .collect::<Result<_, _>>()?,
+        ),
+        List::List(list) => SharedList::List(
+            list.iter()
+                .map(|v| convert_list(v, interner))
+                .collect::<Result<_, _>>()?,
+        ),
+        List::IntArray(list) => SharedList::IntArray(list.iter().map(|v| v.to_vec()).collect()),
+        List::LongArray(list) => SharedList::LongArray(list.iter().map(|v| v.to_vec()).collect()),
+    })
+}
+
+fn convert_compound(
+    compound: &Compound,
+    interner: &mut Interner,
+) -> Result<SharedCompound, Cesu8DecodingError> {
+    let mut entries = Vec::with_capacity(compound.len());
+    for entry in compound.iter() {
+        let name = entry.name().decode()?.into_owned();
+        entries.push((name, convert_tag(entry.value(), interner)?));
+    }
+    Ok(SharedCompound { entries })
+}
+
+fn convert_shared(
+    compound: &Compound,
+    interner: &mut Interner,
+) -> Result<Arc<SharedCompound>, Cesu8DecodingError> {
+    let compound = convert_compound(compound, interner)?;
+    Ok(interner.share(compound))
+}
@@ -1,9 +1,9 @@
 use crate::bin_decode::{NbtParse, ParseError, Reader};
-use byteorder::{BigEndian, ByteOrder};
-use cesu8::{from_java_cesu8, Cesu8DecodingError};
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
 use core::ops::Deref;
-use std::borrow::Cow;
-use std::fmt;
 
 /// NBT stores strings in Java's modified version of [CESU-8][2] called
 /// ["Modified UTF-8"][1]. This type stores a reference to the raw data
@@ -20,8 +20,12 @@ pub struct NbtString<'a> {
 
 impl<'a> NbtParse<'a> for NbtString<'a> {
     fn read(reader: &mut Reader<'a>) -> Result<Self, ParseError> {
-        let length = BigEndian::read_u16(reader.advance(2)?);
-        let data = reader.advance(length as usize)?;
+        let offset = reader.position;
+        let length = reader.read_string_length()?;
+        if length > reader.limits().max_string_len {
+            return Err(ParseError::StringTooLong { length, offset });
+        }
+        let data = reader.advance(length)?;
         Ok(NbtString { data })
     }
 }
@@ -33,15 +37,218 @@ impl<'a> NbtString<'a> {
         NbtString { data }
     }
 
-    /// Attempts to parse the string into UTF-8 using the [cesu8] crate.
-    /// An error will be returned if this fails, which should only
-    /// happen if the data contained is invalid CESU-8.
-    pub fn decode(&self) -> Result<Cow<str>, Cesu8DecodingError> {
-        from_java_cesu8(self.data)
+    /// Attempts to parse the string into UTF-8. An error will be
+    /// returned if this fails, which should only happen if the data
+    /// contained is invalid CESU-8.
+    pub fn decode(&self) -> Result<Cow<'_, str>, Cesu8DecodingError> {
+        decode_valid(self.data).ok_or(Cesu8DecodingError)
+    }
+
+    /// Similar to [NbtString::decode], but never fails. Any invalid
+    /// sequences are replaced with U+FFFD (�), like
+    /// [String::from_utf8_lossy]. This is useful for displaying names
+    /// when an error isn't actionable, such as in logging.
+    pub fn to_string_lossy(&self) -> Cow<'a, str> {
+        if let Some(result) = decode_valid(self.data) {
+            return result;
+        }
+        let mut output = String::with_capacity(self.data.len());
+        let mut rest = self.data;
+        while !rest.is_empty() {
+            let (ch, len) = decode_char(rest);
+            output.push(ch.unwrap_or(char::REPLACEMENT_CHARACTER));
+            rest = &rest[len..];
+        }
+        Cow::Owned(output)
+    }
+
+    /// Returns the number of Unicode scalar values (`char`s) in the
+    /// string, without decoding it into a new allocation.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the string is invalid CESU-8, like [NbtString::decode].
+    pub fn char_count(&self) -> Result<usize, Cesu8DecodingError> {
+        self.chars().try_fold(0, |count, ch| ch.map(|_| count + 1))
+    }
+
+    /// Returns true if every byte of the string is below 0x80. Pure
+    /// ASCII is encoded identically in CESU-8 and UTF-8, so such strings
+    /// can be compared and borrowed without decoding them.
+    pub fn is_ascii(&self) -> bool {
+        self.data.is_ascii()
+    }
+
+    /// Borrows the string as a `&str` without any copying, if it's
+    /// already valid UTF-8. This is the case for most strings, including
+    /// all ASCII strings. Returns None if the string uses CESU-8 specific
+    /// encodings (surrogate pairs or the two-byte NUL), or is invalid.
+    ///
+    /// When this returns Some, [NbtString::decode] returns the same
+    /// string.
+    pub fn as_str(&self) -> Option<&'a str> {
+        core::str::from_utf8(self.data).ok()
     }
 
     pub fn as_bytes(&self) -> &[u8] {
-        &self.data
+        self.data
+    }
+
+    /// Copies the raw bytes of the string into an [OwnedNbtString],
+    /// which doesn't borrow from the document. Unlike
+    /// `decode()?.into_owned()`, this can't fail and preserves invalid
+    /// CESU-8 exactly.
+    pub fn to_owned(&self) -> OwnedNbtString {
+        OwnedNbtString {
+            data: self.data.to_vec(),
+        }
+    }
+
+    /// Returns the length of the string in bytes, as stored in the
+    /// file. This is the CESU-8 length, which can differ from the length
+    /// of the decoded string.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns true if the string contains no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Iterates over the characters of the string, decoding them one at
+    /// a time instead of allocating the whole string like
+    /// [NbtString::decode] might.
+    ///
+    /// Each invalid sequence produces an error and skips a single byte,
+    /// after which decoding carries on with the rest of the string.
+    pub fn chars(&self) -> impl Iterator<Item = Result<char, Cesu8DecodingError>> + 'a {
+        let mut rest = self.data;
+        core::iter::from_fn(move || {
+            if rest.is_empty() {
+                return None;
+            }
+            let (ch, len) = decode_char(rest);
+            rest = &rest[len..];
+            Some(ch.ok_or(Cesu8DecodingError))
+        })
+    }
+}
+
+/// The error returned when an [NbtString] can't be decoded because it
+/// isn't valid CESU-8.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Cesu8DecodingError;
+
+impl fmt::Display for Cesu8DecodingError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "could not convert CESU-8 data to UTF-8")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Cesu8DecodingError {}
+
+/// Encodes a string to Java's CESU-8. Strings without NULs or
+/// characters outside of the Basic Multilingual Plane are the same in
+/// UTF-8, and are borrowed as-is.
+pub(crate) fn encode_java(value: &str) -> Cow<'_, [u8]> {
+    // NULs and 4-byte sequences are the only things encoded differently
+    // from UTF-8.
+    if !value.bytes().any(|byte| byte == 0 || byte >= 0xF0) {
+        return Cow::Borrowed(value.as_bytes());
+    }
+    let mut output = Vec::with_capacity(value.len() + value.len() / 2);
+    let push_unit = |output: &mut Vec<u8>, unit: u32| {
+        output.extend_from_slice(&[
+            0xE0 | (unit >> 12) as u8,
+            0x80 | (unit >> 6 & 0x3F) as u8,
+            0x80 | (unit & 0x3F) as u8,
+        ])
+    };
+    for ch in value.chars() {
+        match ch as u32 {
+            0 => output.extend_from_slice(&[0xC0, 0x80]),
+            0x10000.. => {
+                let value = ch as u32 - 0x10000;
+                push_unit(&mut output, 0xD800 | value >> 10);
+                push_unit(&mut output, 0xDC00 | (value & 0x3FF));
+            }
+            _ => output.extend_from_slice(ch.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+    Cow::Owned(output)
+}
+
+/// Decodes the whole string, returning None if it's invalid.
+fn decode_valid(data: &[u8]) -> Option<Cow<'_, str>> {
+    if let Ok(result) = core::str::from_utf8(data) {
+        return Some(Cow::Borrowed(result));
+    }
+    let mut output = String::with_capacity(data.len());
+    let mut rest = data;
+    while !rest.is_empty() {
+        let (ch, len) = decode_char(rest);
+        output.push(ch?);
+        rest = &rest[len..];
+    }
+    Some(Cow::Owned(output))
+}
+
+/// Decodes the first character of a CESU-8 string, returning it along
+/// with the number of bytes it took up. Invalid sequences return None
+/// and consume one byte. Plain 4-byte UTF-8 sequences and unescaped
+/// NULs are accepted as well, so that strings which are valid UTF-8
+/// always decode.
+fn decode_char(data: &[u8]) -> (Option<char>, usize) {
+    let cont = |index: usize| match data.get(index) {
+        Some(&byte) if byte & 0xC0 == 0x80 => Some((byte & 0x3F) as u32),
+        _ => None,
+    };
+    let first = data[0];
+    let result = match first {
+        0x00..=0x7F => Some((first as u32, 1)),
+        // Java's two-byte encoding of NUL.
+        0xC0 if data.get(1) == Some(&0x80) => Some((0, 2)),
+        0xC2..=0xDF => cont(1).map(|b1| (((first & 0x1F) as u32) << 6 | b1, 2)),
+        0xE0..=0xEF => match (cont(1), cont(2)) {
+            (Some(b1), Some(b2)) => {
+                let high = ((first & 0x0F) as u32) << 12 | b1 << 6 | b2;
+                match high {
+                    0..=0x7FF => None,
+                    0xD800..=0xDBFF => match (data.get(3), cont(4), cont(5)) {
+                        (Some(0xED), Some(b4), Some(b5)) => {
+                            let low = 0xD000 | b4 << 6 | b5;
+                            if (0xDC00..=0xDFFF).contains(&low) {
+                                Some((0x10000 + ((high - 0xD800) << 10 | (low - 0xDC00)), 6))
+                            } else {
+                                None
+                            }
+                        }
+                        _ => None,
+                    },
+                    0xDC00..=0xDFFF => None,
+                    _ => Some((high, 3)),
+                }
+            }
+            _ => None,
+        },
+        0xF0..=0xF4 => match (cont(1), cont(2), cont(3)) {
+            (Some(b1), Some(b2), Some(b3)) => {
+                let value = ((first & 0x07) as u32) << 18 | b1 << 12 | b2 << 6 | b3;
+                if value >= 0x10000 {
+                    Some((value, 4))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        },
+        _ => None,
+    };
+    match result.and_then(|(value, len)| char::from_u32(value).map(|ch| (ch, len))) {
+        Some((ch, len)) => (Some(ch), len),
+        None => (None, 1),
     }
 }
 
@@ -50,7 +257,7 @@ where
     T: AsRef<str>,
 {
     fn eq(&self, other: &T) -> bool {
-        if let Ok(result) = self.decode() {
+        if let Some(result) = decode_valid(self.data) {
             result == other.as_ref()
         } else {
             false
@@ -62,13 +269,21 @@ impl<'a> Deref for NbtString<'a> {
     type Target = [u8];
 
     fn deref(&self) -> &Self::Target {
-        &self.data
+        self.data
+    }
+}
+
+/// Displays the decoded string. Invalid sequences are shown as U+FFFD
+/// (�), see [NbtString::to_string_lossy].
+impl<'a> fmt::Display for NbtString<'a> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.to_string_lossy(), fmt)
     }
 }
 
 impl<'a> fmt::Debug for NbtString<'a> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        if let Ok(result) = self.decode() {
+        if let Some(result) = decode_valid(self.data) {
             fmt::Debug::fmt(&result, fmt)
         } else {
             write!(fmt, "\"")?;
@@ -83,3 +298,74 @@ impl<'a> fmt::Debug for NbtString<'a> {
         }
     }
 }
+
+/// An owned copy of an [NbtString], created using
+/// [NbtString::to_owned]. It stores the raw CESU-8 data, so it can be
+/// kept around after the document is dropped without decoding it first.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct OwnedNbtString {
+    data: Vec<u8>,
+}
+
+impl OwnedNbtString {
+    /// Creates a string from raw CESU-8 data. The data isn't validated,
+    /// like when a string is read from a document.
+    pub fn from_bytes(data: Vec<u8>) -> OwnedNbtString {
+        OwnedNbtString { data }
+    }
+
+    /// Borrows the string as an [NbtString].
+    pub fn as_nbt_string(&self) -> NbtString<'_> {
+        NbtString { data: &self.data }
+    }
+
+    /// Same as [NbtString::decode].
+    pub fn decode(&self) -> Result<Cow<'_, str>, Cesu8DecodingError> {
+        decode_valid(&self.data).ok_or(Cesu8DecodingError)
+    }
+
+    /// Same as [NbtString::to_string_lossy].
+    pub fn to_string_lossy(&self) -> Cow<'_, str> {
+        self.as_nbt_string().to_string_lossy()
+    }
+
+    /// Returns the raw CESU-8 data, borrowed from this string.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the raw CESU-8 data.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+impl<'a> From<NbtString<'a>> for OwnedNbtString {
+    fn from(value: NbtString<'a>) -> OwnedNbtString {
+        value.to_owned()
+    }
+}
+
+impl<'a> PartialEq<NbtString<'a>> for OwnedNbtString {
+    fn eq(&self, other: &NbtString<'a>) -> bool {
+        self.data == other.data
+    }
+}
+
+impl<'a> PartialEq<OwnedNbtString> for NbtString<'a> {
+    fn eq(&self, other: &OwnedNbtString) -> bool {
+        self.data == &other.data[..]
+    }
+}
+
+impl fmt::Display for OwnedNbtString {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.as_nbt_string(), fmt)
+    }
+}
+
+impl fmt::Debug for OwnedNbtString {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.as_nbt_string(), fmt)
+    }
+}
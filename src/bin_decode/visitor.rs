@@ -0,0 +1,212 @@
+use crate::bin_decode::{
+    read_root_header, read_type, IntArray, LongArray, NbtParse, NbtString, ParseError,
+    ParseOptions, Reader, Tag,
+};
+use crate::TagType;
+
+/// Returned by [Visitor::visit_compound_start] and
+/// [Visitor::visit_list_start] to decide whether to visit the contents.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Visit {
+    /// Visit each of the elements, followed by the matching end method.
+    Enter,
+    /// Skip over the contents without visiting them. The matching end
+    /// method is not called.
+    Skip,
+}
+
+/// Receives each tag in a document as it's parsed by [visit], so that
+/// a custom data structure can be built in one pass without creating
+/// [Compound][crate::bin_decode::Compound]s or
+/// [List][crate::bin_decode::List]s.
+///
+/// Every method has a default implementation that does nothing and
+/// enters every subtree, so only the methods that are needed have to be
+/// implemented. Names are `None` for the elements of a list, and `Some`
+/// everywhere else, including the root compound.
+pub trait Visitor<'a> {
+    /// Called when a TAG_Compound starts.
+    fn visit_compound_start(&mut self, _name: Option<NbtString<'a>>) -> Visit {
+        Visit::Enter
+    }
+
+    /// Called after the last entry of a TAG_Compound that was entered.
+    fn visit_compound_end(&mut self) {}
+
+    /// Called when a TAG_List starts, with the type and number of its
+    /// elements.
+    fn visit_list_start(
+        &mut self,
+        _name: Option<NbtString<'a>>,
+        _element_type: TagType,
+        _len: usize,
+    ) -> Visit {
+        Visit::Enter
+    }
+
+    /// Called after the last element of a TAG_List that was entered.
+    fn visit_list_end(&mut self) {}
+
+    /// Called for each TAG_Byte.
+    fn visit_byte(&mut self, _name: Option<NbtString<'a>>, _value: i8) {}
+
+    /// Called for each TAG_Short.
+    fn visit_short(&mut self, _name: Option<NbtString<'a>>, _value: i16) {}
+
+    /// Called for each TAG_Int.
+    fn visit_int(&mut self, _name: Option<NbtString<'a>>, _value: i32) {}
+
+    /// Called for each TAG_Long.
+    fn visit_long(&mut self, _name: Option<NbtString<'a>>, _value: i64) {}
+
+    /// Called for each TAG_Float.
+    fn visit_float(&mut self, _name: Option<NbtString<'a>>, _value: f32) {}
+
+    /// Called for each TAG_Double.
+    fn visit_double(&mut self, _name: Option<NbtString<'a>>, _value: f64) {}
+
+    /// Called for each TAG_Byte_Array.
+    fn visit_byte_array(&mut self, _name: Option<NbtString<'a>>, _value: &'a [u8]) {}
+
+    /// Called for each TAG_String.
+    fn visit_string(&mut self, _name: Option<NbtString<'a>>, _value: NbtString<'a>) {}
+
+    /// Called for each TAG_Int_Array.
+    fn visit_int_array(&mut self, _name: Option<NbtString<'a>>, _value: IntArray<'a>) {}
+
+    /// Called for each TAG_Long_Array.
+    fn visit_long_array(&mut self, _name: Option<NbtString<'a>>, _value: LongArray<'a>) {}
+}
+
+/// Parses a document, calling the methods of `visitor` for each tag
+/// in the order that they appear. The data must already be
+/// decompressed, see [Document::visit][crate::bin_decode::Document::visit]
+/// for a version that handles this.
+///
+/// # Errors
+///
+/// Same as [Document::parse][crate::bin_decode::Document::parse]. The
+/// visitor will already have been called for anything before the
+/// error. Subtrees that are skipped are still checked to be well formed.
+///
+/// # Example
+///
+#[cfg_attr(feature = "std", doc = "```rust")]
+#[cfg_attr(not(feature = "std"), doc = "```ignore")]
+/// # use std::error::Error;
+/// # use nobility::bin_decode::Document;
+/// #
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use nobility::bin_decode::{NbtString, Visitor};
+///
+/// #[derive(Default)]
+/// struct CountStrings(usize);
+///
+/// impl<'a> Visitor<'a> for CountStrings {
+///     fn visit_string(&mut self, _name: Option<NbtString<'a>>, _value: NbtString<'a>) {
+///         self.0 += 1;
+///     }
+/// }
+///
+/// # let input = Document::doctest_demo();
+/// let doc = Document::load(input)?;
+/// let mut visitor = CountStrings::default();
+/// doc.visit(&mut visitor)?;
+/// assert_eq!(visitor.0, 1);
+/// # Ok(())
+/// # }
+/// ```
+pub fn visit<'a, V>(data: &'a [u8], visitor: &mut V) -> Result<(), ParseError>
+where
+    V: Visitor<'a>,
+{
+    let mut reader = Reader::with_options(data, ParseOptions::default());
+    let name = read_root_header(&mut reader)?;
+    visit_value(&mut reader, visitor, Some(name), TagType::Compound)
+}
+
+fn visit_value<'a, V>(
+    reader: &mut Reader<'a>,
+    visitor: &mut V,
+    name: Option<NbtString<'a>>,
+    tag: TagType,
+) -> Result<(), ParseError>
+where
+    V: Visitor<'a>,
+{
+    match tag {
+        TagType::Compound => {
+            reader.enter()?;
+            let result = match visitor.visit_compound_start(name) {
+                Visit::Enter => visit_entries(reader, visitor),
+                Visit::Skip => reader.skip_compound_entries(),
+            };
+            reader.exit();
+            result
+        }
+        TagType::List => {
+            reader.enter()?;
+            let result = visit_list(reader, visitor, name);
+            reader.exit();
+            result
+        }
+        tag => {
+            match Tag::read(tag, reader)? {
+                Tag::Byte(value) => visitor.visit_byte(name, value),
+                Tag::Short(value) => visitor.visit_short(name, value),
+                Tag::Int(value) => visitor.visit_int(name, value),
+                Tag::Long(value) => visitor.visit_long(name, value),
+                Tag::Float(value) => visitor.visit_float(name, value),
+                Tag::Double(value) => visitor.visit_double(name, value),
+                Tag::ByteArray(value) => visitor.visit_byte_array(name, value),
+                Tag::String(value) => visitor.visit_string(name, value),
+                Tag::IntArray(value) => visitor.visit_int_array(name, value),
+                Tag::LongArray(value) => visitor.visit_long_array(name, value),
+                Tag::List(_) | Tag::Compound(_) => unreachable!(),
+            }
+            Ok(())
+        }
+    }
+}
+
+fn visit_entries<'a, V>(reader: &mut Reader<'a>, visitor: &mut V) -> Result<(), ParseError>
+where
+    V: Visitor<'a>,
+{
+    loop {
+        let tag = read_type(reader)?;
+        if tag == TagType::End {
+            visitor.visit_compound_end();
+            return Ok(());
+        }
+        let name = NbtString::read(reader)?;
+        visit_value(reader, visitor, Some(name), tag)?;
+    }
+}
+
+fn visit_list<'a, V>(
+    reader: &mut Reader<'a>,
+    visitor: &mut V,
+    name: Option<NbtString<'a>>,
+) -> Result<(), ParseError>
+where
+    V: Visitor<'a>,
+{
+    let element_type = read_type(reader)?;
+    let len = reader.read_list_length(element_type)?;
+    if element_type == TagType::End && len > 0 {
+        return Err(ParseError::UnexpectedEndTag {
+            offset: reader.position,
+        });
+    }
+    match visitor.visit_list_start(name, element_type, len) {
+        Visit::Enter => {
+            for _ in 0..len {
+                visit_value(reader, visitor, None, element_type)?;
+            }
+            visitor.visit_list_end();
+            Ok(())
+        }
+        Visit::Skip => reader.skip_elements(element_type, len),
+    }
+}
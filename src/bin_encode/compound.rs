@@ -1,5 +1,7 @@
-use crate::bin_encode::{CompoundListWriter, NbtWriter, TagWriter};
+use crate::bin_decode::{Cesu8DecodingError, Compound};
+use crate::bin_encode::{value, CompoundListWriter, NbtWriter, TagWriter};
 use crate::TagType;
+use std::io::Write;
 
 /// A builder for a TAG_Compound, allowing fields to be added
 /// sequentially.
@@ -26,15 +28,18 @@ use crate::TagType;
 /// # Panics
 ///
 /// This object will panic on drop if finish() is not called. Otherwise,
-/// an invalid NBT document would be generated.
+/// an invalid NBT document would be generated. This check is skipped if
+/// the thread is already panicking, so that a panic while building a
+/// document doesn't turn into an abort.
 #[derive(Debug)]
-pub struct CompoundWriter<'a> {
-    writer: &'a mut NbtWriter,
+pub struct CompoundWriter<'a, W = Vec<u8>> {
+    writer: &'a mut NbtWriter<W>,
     done: bool,
 }
 
-impl<'a> CompoundWriter<'a> {
-    pub(crate) fn new(writer: &'a mut NbtWriter) -> CompoundWriter<'a> {
+impl<'a, W: Write> CompoundWriter<'a, W> {
+    pub(crate) fn new(writer: &'a mut NbtWriter<W>) -> CompoundWriter<'a, W> {
+        writer.open_compounds += 1;
         CompoundWriter {
             writer,
             done: false,
@@ -42,15 +47,24 @@ impl<'a> CompoundWriter<'a> {
     }
 
     /// Create a new field and return a builder for filling in its value.
-    pub fn field<'b>(&'b mut self, name: &'b str) -> TagWriter<'b> {
+    pub fn field<'b>(&'b mut self, name: &'b str) -> TagWriter<'b, W> {
         TagWriter::new_field(self.writer, name)
     }
 
+    /// Same as [CompoundWriter::field], but the name is given as bytes
+    /// which are written exactly as-is, without being encoded to
+    /// CESU-8. This allows names which aren't valid CESU-8 to be
+    /// preserved when re-encoding a document, e.g. using
+    /// [NbtString::as_bytes][crate::bin_decode::NbtString::as_bytes].
+    pub fn raw_field<'b>(&'b mut self, name: &'b [u8]) -> TagWriter<'b, W> {
+        TagWriter::new_raw_field(self.writer, name)
+    }
+
     /// Creates a compound field. The reason to use this is that
     /// `.field(name).compound()` has too short of a lifetime for the
     /// intermediate TagWriter, and would have to be spread across
     /// multiple variables, causing verbose code.
-    pub fn compound_field<'b>(&'b mut self, name: &'b str) -> CompoundWriter<'b> {
+    pub fn compound_field<'b>(&'b mut self, name: &'b str) -> CompoundWriter<'b, W> {
         self.writer.write_tag(TagType::Compound);
         self.writer.write_string(name);
         CompoundWriter::new(self.writer)
@@ -60,23 +74,138 @@ impl<'a> CompoundWriter<'a> {
     /// `.field(name).compound_list()` has too short of a lifetime for
     /// the intermediate TagWriter, and would have to be spread across
     /// multiple variables, causing verbose code.
-    pub fn compound_list_field<'b>(&'b mut self, name: &'b str) -> CompoundListWriter<'b> {
+    pub fn compound_list_field<'b>(&'b mut self, name: &'b str) -> CompoundListWriter<'b, W> {
         self.writer.write_tag(TagType::List);
         self.writer.write_string(name);
         CompoundListWriter::new(self.writer)
     }
 
+    /// Same as [CompoundWriter::compound_list_field], but the number of
+    /// elements is declared up front and written immediately, rather
+    /// than being filled in when the list is finished.
+    pub fn compound_list_field_with_count<'b>(
+        &'b mut self,
+        name: &'b str,
+        count: usize,
+    ) -> CompoundListWriter<'b, W> {
+        self.writer.write_tag(TagType::List);
+        self.writer.write_string(name);
+        CompoundListWriter::with_count(self.writer, count)
+    }
+
+    /// Creates a compound field containing a copy of a decoded
+    /// [Compound], including everything nested inside of it.
+    pub fn write_compound(&mut self, name: &str, value: &Compound) {
+        self.writer.write_tag(TagType::Compound);
+        self.writer.write_string(name);
+        value::write_compound(self.writer, value);
+    }
+
+    /// Creates a compound field whose body is copied from `body_bytes`
+    /// exactly as-is. The body is the sequence of named tags inside the
+    /// compound followed by the closing TAG_End, which is what
+    /// [NbtWriter::finish] produces for a root compound minus its
+    /// 3-byte tag type and empty name. This allows documents to be
+    /// assembled from pieces that were encoded separately, without
+    /// re-encoding them.
+    ///
+    /// The contents of the body aren't validated, so passing anything
+    /// other than a well formed compound body will produce an invalid
+    /// document.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `body_bytes` doesn't end with TAG_End.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nobility::bin_encode::NbtWriter;
+    /// let mut piece = NbtWriter::new();
+    /// let mut root = piece.root("");
+    /// root.field("enabled").byte(1);
+    /// root.finish();
+    /// let piece = piece.finish();
+    ///
+    /// let mut writer = NbtWriter::new();
+    /// let mut root = writer.root("hello world");
+    /// // Skip the tag type and the empty name.
+    /// root.field_raw_compound("plugin", &piece[3..]);
+    /// root.finish();
+    /// # let _ = writer.finish();
+    /// ```
+    pub fn field_raw_compound(&mut self, name: &str, body_bytes: &[u8]) {
+        if body_bytes.last() != Some(&(TagType::End as u8)) {
+            panic!("Compound body must end with TAG_End");
+        }
+        self.writer.write_tag(TagType::Compound);
+        self.writer.write_string(name);
+        self.writer.write_bytes(body_bytes);
+    }
+
+    /// Copies every entry of a decoded [Compound] into this one,
+    /// passing each name through `rename` first. Returning None from
+    /// `rename` drops the entry, which is useful for migrating data
+    /// between versions.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a name in `source` is not valid CESU-8, as it can't be
+    /// passed to `rename`. Entries before it will have already been
+    /// written.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::error::Error;
+    /// # use nobility::bin_decode::Document;
+    /// # use nobility::bin_encode::NbtWriter;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let doc = Document::load(Document::doctest_demo())?;
+    /// # let (_name, source) = doc.parse()?;
+    /// let mut writer = NbtWriter::new();
+    /// let mut root = writer.root("hello world");
+    /// root.write_from_with_rename(&source, |name| match name {
+    ///     "name" => Some("displayName".to_owned()),
+    ///     "obsolete" => None,
+    ///     other => Some(other.to_owned()),
+    /// })?;
+    /// root.finish();
+    /// # let _ = writer.finish();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn write_from_with_rename<F>(
+        &mut self,
+        source: &Compound,
+        rename: F,
+    ) -> Result<(), Cesu8DecodingError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        for entry in source.iter() {
+            if let Some(name) = rename(&entry.name().decode()?) {
+                self.writer.write_tag(entry.value().tag_type());
+                self.writer.write_string(&name);
+                value::write_value(self.writer, entry.value());
+            }
+        }
+        Ok(())
+    }
+
     /// Finishes the compound tag. This must be called after you're done
     /// appending elements, or a panic will occur on drop.
     pub fn finish(mut self) {
         self.writer.write_tag(TagType::End);
+        self.writer.open_compounds -= 1;
         self.done = true;
     }
 }
 
-impl<'a> Drop for CompoundWriter<'a> {
+impl<'a, W> Drop for CompoundWriter<'a, W> {
     fn drop(&mut self) {
-        if !self.done {
+        if !self.done && !std::thread::panicking() {
             panic!("finish() must be called on CompoundWriter before it goes out of scope.");
         }
     }
@@ -0,0 +1,173 @@
+use crate::bin_encode::{EncodeError, NbtWriter, WriteValue};
+use crate::TagType;
+use serde_json::{Number, Value};
+use std::convert::TryFrom;
+use std::fmt;
+
+/// Failures which can occur while converting JSON with [from_json].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum JsonToNbtError {
+    /// The root value was not a JSON object, so it can't become the
+    /// root compound.
+    RootNotObject,
+    /// NBT has nothing that corresponds to null.
+    Null,
+    /// An array contained values which don't have the same type and
+    /// can't be converted to a common one.
+    MixedList,
+    /// A key or string is longer than 65535 bytes once it's been
+    /// encoded to CESU-8, so its length can't be stored in NBT. The
+    /// length is in bytes, after encoding.
+    StringTooLong { length: usize },
+}
+
+impl fmt::Display for JsonToNbtError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JsonToNbtError::RootNotObject => write!(fmt, "Root JSON value must be an object"),
+            JsonToNbtError::Null => write!(fmt, "JSON null can't be converted to NBT"),
+            JsonToNbtError::MixedList => write!(
+                fmt,
+                "JSON array contains values that can't be converted to a single type"
+            ),
+            JsonToNbtError::StringTooLong { length } => write!(
+                fmt,
+                "String of {} bytes is too long for NBT, the limit is {}",
+                length,
+                u16::MAX
+            ),
+        }
+    }
+}
+
+impl std::error::Error for JsonToNbtError {}
+
+/// Encodes a JSON object as an NBT document, with the given name for
+/// the root compound. This is the reverse of
+/// [Compound::to_json][crate::bin_decode::Compound::to_json], although
+/// type information that was lost can't be recovered. Requires the
+/// `json` feature.
+///
+/// - Objects become compounds.
+/// - Arrays become lists. If the elements are a mix of numbers, they're
+///   all converted to the widest type used, otherwise they must all have
+///   the same type. Empty arrays become lists with an element type of
+///   TAG_End, the same as Minecraft writes.
+/// - Integers become TAG_Int if they fit, otherwise TAG_Long. Other
+///   numbers, including integers too large for TAG_Long, become
+///   TAG_Double.
+/// - Booleans become TAG_Byte 0 or 1.
+/// - Strings become TAG_String.
+///
+/// # Errors
+///
+/// Fails if `value` isn't an object, if it contains null anywhere, if
+/// an array can't be converted to a list, or if a key or string
+/// (including `root_name`) is too long to be stored. See
+/// [JsonToNbtError].
+///
+/// # Example
+///
+/// ```rust
+/// use nobility::bin_encode::from_json;
+/// use serde_json::json;
+///
+/// let value = json!({ "name": "Bananrama", "scores": [1, 2.5] });
+/// let data = from_json("hello world", &value).unwrap();
+/// # let _ = data;
+/// ```
+pub fn from_json(root_name: &str, value: &Value) -> Result<Vec<u8>, JsonToNbtError> {
+    let object = value.as_object().ok_or(JsonToNbtError::RootNotObject)?;
+    check_string(root_name)?;
+    let mut entries = Vec::with_capacity(object.len());
+    for (key, value) in object {
+        entries.push((check_string(key)?, convert(value)?));
+    }
+
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root(root_name);
+    for (key, value) in &entries {
+        root.field(key).write(value);
+    }
+    root.finish();
+    Ok(writer.finish())
+}
+
+fn convert(value: &Value) -> Result<WriteValue<'_>, JsonToNbtError> {
+    match value {
+        Value::Null => Err(JsonToNbtError::Null),
+        Value::Bool(value) => Ok(WriteValue::Byte(*value as i8)),
+        Value::Number(value) => Ok(convert_number(value)),
+        Value::String(value) => Ok(WriteValue::String(check_string(value)?)),
+        Value::Array(elements) => {
+            let elements = elements
+                .iter()
+                .map(convert)
+                .collect::<Result<Vec<_>, _>>()?;
+            unify_list(elements).map(WriteValue::List)
+        }
+        Value::Object(object) => {
+            let mut entries = Vec::with_capacity(object.len());
+            for (key, value) in object {
+                entries.push((check_string(key)?, convert(value)?));
+            }
+            Ok(WriteValue::Compound(entries))
+        }
+    }
+}
+
+/// Makes sure a string can be written, as the writer would panic
+/// otherwise.
+fn check_string(value: &str) -> Result<&str, JsonToNbtError> {
+    match NbtWriter::check_string(value) {
+        Err(EncodeError::StringTooLong { length }) => Err(JsonToNbtError::StringTooLong { length }),
+        _ => Ok(value),
+    }
+}
+
+fn convert_number(value: &Number) -> WriteValue<'static> {
+    if let Some(value) = value.as_i64() {
+        if let Ok(value) = i32::try_from(value) {
+            WriteValue::Int(value)
+        } else {
+            WriteValue::Long(value)
+        }
+    } else {
+        // Either a fraction, or an integer too large for i64.
+        WriteValue::Double(value.as_f64().unwrap_or(f64::NAN))
+    }
+}
+
+/// Converts mixed numbers to the widest type among them, and checks
+/// that everything else has a single type.
+fn unify_list(elements: Vec<WriteValue<'_>>) -> Result<Vec<WriteValue<'_>>, JsonToNbtError> {
+    let rank = |tag: TagType| match tag {
+        TagType::Int => Some(0),
+        TagType::Long => Some(1),
+        TagType::Double => Some(2),
+        _ => None,
+    };
+    let first = match elements.first() {
+        Some(first) => first.tag_type(),
+        None => return Ok(elements),
+    };
+    if elements.iter().all(|element| element.tag_type() == first) {
+        return Ok(elements);
+    }
+    let widest = elements
+        .iter()
+        .map(|element| rank(element.tag_type()).ok_or(JsonToNbtError::MixedList))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .max();
+    Ok(elements
+        .into_iter()
+        .map(|element| match (widest, element) {
+            (Some(2), WriteValue::Int(value)) => WriteValue::Double(value as f64),
+            (Some(2), WriteValue::Long(value)) => WriteValue::Double(value as f64),
+            (Some(1), WriteValue::Int(value)) => WriteValue::Long(value as i64),
+            (_, element) => element,
+        })
+        .collect())
+}
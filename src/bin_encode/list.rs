@@ -1,6 +1,7 @@
-use crate::bin_encode::{CompoundWriter, NbtWriter};
+use crate::bin_encode::{checked_length, CompoundWriter, EncodeError, NbtWriter};
 use crate::TagType;
-use byteorder::{BigEndian, ByteOrder};
+use std::fmt;
+use std::io::Write;
 
 /// A builder for a TAG_List of [TAG_Compounds][CompoundWriter].
 ///
@@ -38,24 +39,60 @@ use byteorder::{BigEndian, ByteOrder};
 ///
 /// # Panics
 ///
-/// This object will panic on drop if finish() is not called.
+/// This object will panic on drop if finish() is not called, unless the
+/// thread is already panicking.
+///
+/// When the number of elements was declared up front (e.g. using
+/// [CompoundWriter::compound_list_field_with_count]), adding more
+/// elements than that will panic, as will calling finish() after adding
+/// fewer. [CompoundListWriter::try_finish] can be used to handle the
+/// latter case without panicking.
+///
+/// # Streaming
+///
+/// Unless the number of elements was declared up front, the length is
+/// filled in when the list is finished. This means that a writer
+/// created using [NbtWriter::new_writer] has to hold the list in memory
+/// until then, rather than streaming it out.
 #[derive(Debug)]
-pub struct CompoundListWriter<'a> {
-    writer: &'a mut NbtWriter,
+pub struct CompoundListWriter<'a, W = Vec<u8>> {
+    writer: &'a mut NbtWriter<W>,
     start_offset: usize,
     length: usize,
+    expected: Option<usize>,
+    open_compounds: usize,
     done: bool,
 }
 
-impl<'a> CompoundListWriter<'a> {
-    pub(crate) fn new(writer: &'a mut NbtWriter) -> CompoundListWriter<'a> {
+impl<'a, W: Write> CompoundListWriter<'a, W> {
+    pub(crate) fn new(writer: &'a mut NbtWriter<W>) -> CompoundListWriter<'a, W> {
         writer.write_tag(TagType::Compound);
-        let start_offset = writer.get_vec().len();
+        let start_offset = writer.pin();
         writer.write_u32(0);
         CompoundListWriter {
+            open_compounds: writer.open_compounds,
             writer,
             start_offset,
             length: 0,
+            expected: None,
+            done: false,
+        }
+    }
+
+    /// Writes the length immediately instead of going back and filling
+    /// it in once the list is finished.
+    pub(crate) fn with_count(
+        writer: &'a mut NbtWriter<W>,
+        count: usize,
+    ) -> CompoundListWriter<'a, W> {
+        writer.write_tag(TagType::Compound);
+        writer.write_length(count);
+        CompoundListWriter {
+            open_compounds: writer.open_compounds,
+            writer,
+            start_offset: 0,
+            length: 0,
+            expected: Some(count),
             done: false,
         }
     }
@@ -63,31 +100,185 @@ impl<'a> CompoundListWriter<'a> {
     /// Start a new element in the list, returning a CompoundWriter to
     /// build it. `finish` must be called on the builder before
     /// additional elements can be added.
-    pub fn element(&mut self) -> CompoundWriter {
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of elements was declared up front, and this
+    /// would exceed it. Also panics if the previous element was never
+    /// finished, e.g. because it was passed to [std::mem::forget].
+    pub fn element(&mut self) -> CompoundWriter<'_, W> {
+        self.check_elements_finished();
+        if let Some(expected) = self.expected {
+            if self.length >= expected {
+                panic!(
+                    "CompoundListWriter was declared with {} elements, can't add more",
+                    expected
+                );
+            }
+        }
         self.length += 1;
         CompoundWriter::new(self.writer)
     }
 
     /// Must be called before the builder goes out of scope, otherwise
     /// an invalid NBT document would be generated.
-    pub fn finish(mut self) {
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of elements was declared up front, and
+    /// fewer elements than that were added.
+    pub fn finish(self) {
+        if let Err(err) = self.try_finish() {
+            panic!("{}", err);
+        }
+    }
+
+    /// Same as [CompoundListWriter::finish], but returns an error
+    /// instead of panicking if the number of elements was declared up
+    /// front and fewer were added. In that case the document will be
+    /// invalid.
+    ///
+    /// # Errors
+    ///
+    /// Returns [EncodeError::CountMismatch] if the declared number of
+    /// elements doesn't match.
+    pub fn try_finish(mut self) -> Result<(), EncodeError> {
         self.done = true;
-        let mut bytes = [0, 0, 0, 0];
-        BigEndian::write_u32(&mut bytes, self.length as u32);
+        self.check_elements_finished();
+        if let Some(expected) = self.expected {
+            if expected != self.length {
+                return Err(EncodeError::CountMismatch {
+                    expected,
+                    actual: self.length,
+                });
+            }
+            return Ok(());
+        }
         // Somewhat of a hack, but it makes the interface nicer. Goes
         // back and overwrites the length field with the true value once
         // this builder has been finalized.
-        let vec = self.writer.get_vec();
-        for (i, byte) in bytes.iter().enumerate() {
-            vec[self.start_offset + i] = *byte;
+        self.writer
+            .patch_u32(self.start_offset, checked_length(self.length));
+        Ok(())
+    }
+}
+
+impl<'a, W> CompoundListWriter<'a, W> {
+    fn check_elements_finished(&self) {
+        if self.writer.open_compounds != self.open_compounds {
+            panic!("The previous element of CompoundListWriter must be finished before adding another or finishing the list");
         }
     }
 }
 
-impl<'a> Drop for CompoundListWriter<'a> {
+impl<'a, W> Drop for CompoundListWriter<'a, W> {
     fn drop(&mut self) {
-        if !self.done {
+        if !self.done && !std::thread::panicking() {
             panic!("finish() must be called on CompoundListWriter before going out of scope");
         }
     }
 }
+
+/// A builder for a TAG_List of a primitive type (Byte, Short, Int,
+/// Long, Float, or Double), where elements are added one at a time.
+/// This is created using methods such as
+/// [TagWriter::int_list_builder][crate::bin_encode::TagWriter::int_list_builder],
+/// and is useful when the elements come from somewhere that isn't
+/// easily turned into an iterator for
+/// [TagWriter::int_list_iter][crate::bin_encode::TagWriter::int_list_iter].
+///
+/// # Example
+///
+/// ```rust
+/// # use nobility::bin_encode::NbtWriter;
+/// # let mut writer = NbtWriter::new();
+/// # let mut root = writer.root("test");
+/// let mut field = root.field("EvenNumbers");
+/// let mut list = field.int_list_builder();
+/// for value in 0..10 {
+///     if value % 2 == 0 {
+///         list.push(value);
+///     }
+/// }
+/// // finish() call is required.
+/// list.finish();
+/// # root.finish();
+/// # let _ = writer.finish();
+/// ```
+///
+/// # Panics
+///
+/// This object will panic on drop if finish() is not called, unless the
+/// thread is already panicking.
+///
+/// # Streaming
+///
+/// Like [CompoundListWriter], the length is filled in when the list is
+/// finished, so a writer created using [NbtWriter::new_writer] has to
+/// hold the list in memory until then.
+pub struct PrimitiveListWriter<'a, T, W = Vec<u8>> {
+    writer: &'a mut NbtWriter<W>,
+    start_offset: usize,
+    length: usize,
+    write: fn(&mut NbtWriter<W>, T),
+    done: bool,
+}
+
+impl<'a, T, W: Write> PrimitiveListWriter<'a, T, W> {
+    pub(crate) fn new(
+        writer: &'a mut NbtWriter<W>,
+        element: TagType,
+        write: fn(&mut NbtWriter<W>, T),
+    ) -> PrimitiveListWriter<'a, T, W> {
+        writer.write_tag(element);
+        let start_offset = writer.pin();
+        writer.write_u32(0);
+        PrimitiveListWriter {
+            writer,
+            start_offset,
+            length: 0,
+            write,
+            done: false,
+        }
+    }
+
+    /// Adds an element to the end of the list.
+    pub fn push(&mut self, value: T) {
+        (self.write)(self.writer, value);
+        self.length += 1;
+    }
+
+    /// Returns the number of elements added so far.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns true if no elements have been added yet.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Must be called before the builder goes out of scope, otherwise
+    /// an invalid NBT document would be generated.
+    pub fn finish(mut self) {
+        self.done = true;
+        self.writer
+            .patch_u32(self.start_offset, checked_length(self.length));
+    }
+}
+
+impl<'a, T, W> fmt::Debug for PrimitiveListWriter<'a, T, W> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("PrimitiveListWriter")
+            .field("length", &self.length)
+            .finish()
+    }
+}
+
+impl<'a, T, W> Drop for PrimitiveListWriter<'a, T, W> {
+    fn drop(&mut self) {
+        if !self.done && !std::thread::panicking() {
+            panic!("finish() must be called on PrimitiveListWriter before going out of scope");
+        }
+    }
+}
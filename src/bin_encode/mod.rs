@@ -19,22 +19,74 @@
 //! # let _unused = result;
 //! ```
 
+use crate::bin_decode::string::encode_java;
+use crate::bin_decode::{read_root, ParseError, ParseOptions, Reader};
 use crate::TagType;
-use byteorder::{BigEndian, ByteOrder};
-use cesu8::to_java_cesu8;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::convert::TryFrom;
 use std::fmt;
+use std::fs::File;
+use std::hash::Hasher;
+use std::io::Error as IoError;
+use std::io::Write;
+use std::path::Path;
 
 mod compound;
+#[cfg(feature = "json")]
+mod json;
 mod list;
 mod tag;
+mod value;
 
 pub use compound::CompoundWriter;
-pub use list::CompoundListWriter;
+#[cfg(feature = "json")]
+pub use json::{from_json, JsonToNbtError};
+pub use list::{CompoundListWriter, PrimitiveListWriter};
 pub use tag::TagWriter;
+pub use value::WriteValue;
+
+/// Failures which can occur while encoding a document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EncodeError {
+    /// A list was declared as having a certain number of elements, but
+    /// a different number of elements were written.
+    CountMismatch { expected: usize, actual: usize },
+    /// A string is too long to be stored in NBT once it's been encoded
+    /// to CESU-8, as its length has to fit in 16 bits. The length is in
+    /// bytes, after encoding.
+    StringTooLong { length: usize },
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EncodeError::CountMismatch { expected, actual } => write!(
+                fmt,
+                "List was declared with {} elements, but {} were written",
+                expected, actual
+            ),
+            EncodeError::StringTooLong { length } => write!(
+                fmt,
+                "String of {} bytes is too long for NBT, the limit is {}",
+                length,
+                u16::MAX
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EncodeError {}
 
 /// This object owns the buffer that the NBT is being written into. It
 /// represents one document.
 ///
+/// By default the whole document is kept in memory and returned by
+/// [NbtWriter::finish]. A writer created with [NbtWriter::new_writer]
+/// instead writes to any [Write] as it goes, which avoids holding large
+/// documents in memory.
+///
 /// # Example
 ///
 /// ```rust
@@ -49,45 +101,334 @@ pub use tag::TagWriter;
 /// let result: Vec<u8> = writer.finish();
 /// # let _unused = result;
 /// ```
-#[derive(Default)]
-pub struct NbtWriter {
+pub struct NbtWriter<W = Vec<u8>> {
     output: Vec<u8>,
+    sink: W,
+    streaming: bool,
+    pinned: usize,
+    // Number of CompoundWriters that haven't been finished yet, used to
+    // catch list elements that were never finished.
+    open_compounds: usize,
+    error: Option<IoError>,
     done: bool,
+    little_endian: bool,
+}
+
+/// How much data is buffered before a streaming writer passes it on to
+/// its sink.
+const FLUSH_THRESHOLD: usize = 8192;
+
+/// Converts a number to bytes in the byte order used by `$writer`.
+macro_rules! endian_bytes {
+    ($writer:expr, $value:expr) => {
+        if $writer.little_endian {
+            $value.to_le_bytes()
+        } else {
+            $value.to_be_bytes()
+        }
+    };
+}
+
+impl Default for NbtWriter {
+    fn default() -> NbtWriter {
+        NbtWriter::new()
+    }
 }
 
 impl NbtWriter {
     /// Creates a new empty writer.
     pub fn new() -> NbtWriter {
+        NbtWriter::with_capacity(0)
+    }
+
+    /// Creates a new empty writer, with room for `capacity` bytes of
+    /// output before the buffer has to grow. Useful when the size of
+    /// the document is roughly known ahead of time.
+    pub fn with_capacity(capacity: usize) -> NbtWriter {
+        NbtWriter {
+            output: Vec::with_capacity(capacity),
+            sink: vec![],
+            streaming: false,
+            pinned: 0,
+            open_compounds: 0,
+            error: None,
+            done: false,
+            little_endian: false,
+        }
+    }
+
+    /// Creates a new empty writer which produces little-endian NBT, the
+    /// variant used by Bedrock edition. Every number is written in
+    /// little-endian byte order, including the length prefixes of
+    /// strings, lists, and arrays. Strings are written as UTF-8 rather
+    /// than CESU-8, which is also what Bedrock uses.
+    ///
+    /// Data which is written as raw bytes, such as by
+    /// [TagWriter::int_array_raw] or [TagWriter::raw_string], is copied
+    /// as-is, and has to already be in the right format.
+    ///
+    /// The result can be loaded with
+    /// [Document::load_little_endian][crate::bin_decode::Document::load_little_endian].
+    /// To write little-endian NBT into a sink as it's built, use
+    /// [NbtWriter::new_writer_little_endian].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use nobility::bin_encode::NbtWriter;
+    ///
+    /// let mut writer = NbtWriter::new_little_endian();
+    /// let mut root = writer.root("");
+    /// root.field("StorageVersion").int(10);
+    /// root.finish();
+    ///
+    /// let result = writer.finish();
+    /// assert_eq!(&result[3..8], &[3, 14, 0, b'S', b't']);
+    /// ```
+    pub fn new_little_endian() -> NbtWriter {
+        NbtWriter {
+            little_endian: true,
+            ..NbtWriter::new()
+        }
+    }
+
+    /// Checks ahead of time whether a string can be written by
+    /// [TagWriter::string] or used as a field name, which would
+    /// otherwise panic.
+    ///
+    /// Any `&str` can be represented in NBT's CESU-8, and decodes back
+    /// to the same string, but some characters take up more space than
+    /// in UTF-8: `\0` is encoded as 2 bytes, and characters outside of
+    /// the Basic Multilingual Plane (such as emoji) are encoded as a
+    /// surrogate pair of 6 bytes. Because of this, a string which fits
+    /// in UTF-8 may still be too long once encoded.
+    ///
+    /// # Errors
+    ///
+    /// Returns [EncodeError::StringTooLong] if the encoded string is
+    /// longer than 65535 bytes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use nobility::bin_encode::{EncodeError, NbtWriter};
+    ///
+    /// assert_eq!(NbtWriter::check_string("hello\0\u{1F600}"), Ok(()));
+    ///
+    /// let long = "\0".repeat(40000);
+    /// assert_eq!(
+    ///     NbtWriter::check_string(&long),
+    ///     Err(EncodeError::StringTooLong { length: 80000 })
+    /// );
+    /// ```
+    pub fn check_string(value: &str) -> Result<(), EncodeError> {
+        let length = value
+            .chars()
+            .map(|ch| match ch as u32 {
+                0 => 2,
+                0x01..=0x7F => 1,
+                0x80..=0x7FF => 2,
+                0x800..=0xFFFF => 3,
+                _ => 6,
+            })
+            .sum();
+        if length > u16::MAX as usize {
+            Err(EncodeError::StringTooLong { length })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Finalizes the NBT document and returns the buffer for use.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if root() was never called, as this would
+    /// result in an invalid document.
+    pub fn finish(mut self) -> Vec<u8> {
+        if !self.done {
+            panic!();
+        }
+        if self.sink.is_empty() {
+            self.output
+        } else {
+            self.sink.append(&mut self.output);
+            self.sink
+        }
+    }
+
+    /// Same as [NbtWriter::finish], but compresses the document with
+    /// gzip, which is how most NBT files such as `level.dat` are
+    /// stored. The result can be loaded with
+    /// [Document::load][crate::bin_decode::Document::load].
+    ///
+    /// `level` is the compression level, from 0 (no compression) to 9
+    /// (best compression). Levels above 9 are clamped to 9.
+    ///
+    /// # Panics
+    ///
+    /// Same as [NbtWriter::finish].
+    pub fn finish_gzip(self, level: u32) -> Vec<u8> {
+        let data = self.finish();
+        let mut encoder = GzEncoder::new(vec![], Compression::new(level.min(9)));
+        // Writing into a Vec can't fail.
+        encoder.write_all(&data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    /// Returns the document that has been written so far, without
+    /// consuming the writer. Once the root compound is finished, this
+    /// is the same as what [NbtWriter::finish] would return. Together
+    /// with [NbtWriter::reset], this allows one writer to be reused for
+    /// many documents.
+    ///
+    /// This is only meant for writers created using [NbtWriter::new]
+    /// or [NbtWriter::with_capacity]. A writer created using
+    /// [NbtWriter::new_writer] passes most of the document on to its
+    /// sink as it goes, so only the part that hasn't been flushed yet
+    /// would be returned. Debug builds panic in that case.
+    pub fn as_bytes(&self) -> &[u8] {
+        debug_assert!(!self.streaming, "as_bytes() called on a streaming writer");
+        &self.output
+    }
+
+    /// Clears the writer so that another document can be written into
+    /// it, keeping the buffer that was allocated for the previous one.
+    /// This avoids allocating a new buffer for each document when
+    /// encoding many of them in a loop. For a writer created using
+    /// [NbtWriter::new_writer], whatever was already passed on to the
+    /// sink is discarded too, along with any error from writing it.
+    ///
+    /// Builders such as [CompoundWriter] borrow the writer mutably, so
+    /// the borrow checker won't allow this to be called while any of
+    /// them are still in use.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use nobility::bin_encode::NbtWriter;
+    ///
+    /// let mut writer = NbtWriter::new();
+    /// let mut documents = vec![];
+    /// for x in 0..4 {
+    ///     writer.reset();
+    ///     let mut root = writer.root("chunk");
+    ///     root.field("xPos").int(x);
+    ///     root.finish();
+    ///     documents.push(writer.as_bytes().to_vec());
+    /// }
+    /// ```
+    pub fn reset(&mut self) {
+        self.output.clear();
+        self.sink.clear();
+        self.error = None;
+        self.pinned = 0;
+        self.open_compounds = 0;
+        self.done = false;
+    }
+}
+
+impl<W: Write> NbtWriter<W> {
+    /// Creates a writer which writes the document into `sink` as it's
+    /// built, instead of keeping all of it in memory. Data is buffered
+    /// and written in chunks, so wrapping `sink` in a
+    /// [BufWriter][std::io::BufWriter] is not necessary.
+    ///
+    /// The builders work the same way as with [NbtWriter::new]. Errors
+    /// from `sink` are returned by [NbtWriter::finish_writer], and
+    /// anything written after an error is discarded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), std::io::Error> {
+    /// use nobility::bin_encode::NbtWriter;
+    ///
+    /// let mut output = vec![];
+    /// let mut writer = NbtWriter::new_writer(&mut output);
+    /// let mut root = writer.root("hello world");
+    /// root.field("name").string("Bananrama");
+    /// root.finish();
+    /// writer.finish_writer()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_writer(sink: W) -> NbtWriter<W> {
         NbtWriter {
             output: vec![],
+            sink,
+            streaming: true,
+            pinned: 0,
+            open_compounds: 0,
+            error: None,
             done: false,
+            little_endian: false,
+        }
+    }
+
+    /// Same as [NbtWriter::new_writer], but produces little-endian NBT
+    /// like [NbtWriter::new_little_endian].
+    pub fn new_writer_little_endian(sink: W) -> NbtWriter<W> {
+        NbtWriter {
+            little_endian: true,
+            ..NbtWriter::new_writer(sink)
         }
     }
 
     /// Creates the root tag with the given name and returns a builder
     /// for it.
-    pub fn root<'a>(&'a mut self, name: &str) -> CompoundWriter<'a> {
+    pub fn root<'a>(&'a mut self, name: &str) -> CompoundWriter<'a, W> {
         self.done = true;
         self.write_tag(TagType::Compound);
         self.write_string(name);
         CompoundWriter::new(self)
     }
 
-    /// Finalizes the NBT document and returns the buffer for use.
+    /// Finalizes a document created using [NbtWriter::new_writer],
+    /// writing out whatever is still buffered and flushing the sink,
+    /// then returns the sink.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error that happened while writing to the sink.
     ///
     /// # Panics
     ///
     /// This method panics if root() was never called, as this would
     /// result in an invalid document.
-    pub fn finish(self) -> Vec<u8> {
+    pub fn finish_writer(mut self) -> Result<W, IoError> {
         if !self.done {
             panic!();
         }
-        self.output
+        self.flush();
+        if let Some(err) = self.error.take() {
+            return Err(err);
+        }
+        self.sink.flush()?;
+        Ok(self.sink)
     }
 
-    pub(crate) fn get_vec(&mut self) -> &mut Vec<u8> {
-        &mut self.output
+    /// Passes the buffered data on to the sink.
+    fn flush(&mut self) {
+        if self.error.is_none() {
+            if let Err(err) = self.sink.write_all(&self.output) {
+                self.error = Some(err);
+            }
+        }
+        self.output.clear();
+    }
+
+    /// Marks the current position as one that will be overwritten
+    /// later using [NbtWriter::patch_u32], and returns it. Nothing will
+    /// be passed on to the sink until then.
+    pub(crate) fn pin(&mut self) -> usize {
+        self.pinned += 1;
+        self.output.len()
+    }
+
+    pub(crate) fn patch_u32(&mut self, offset: usize, value: u32) {
+        self.output[offset..offset + 4].copy_from_slice(&endian_bytes!(self, value));
+        self.pinned -= 1;
     }
 
     pub(crate) fn write_i8(&mut self, value: i8) {
@@ -95,45 +436,31 @@ impl NbtWriter {
     }
 
     pub(crate) fn write_i16(&mut self, value: i16) {
-        let mut buf = [0, 0];
-        BigEndian::write_i16(&mut buf, value);
-        self.output.extend(&buf);
+        self.output.extend(&endian_bytes!(self, value));
     }
 
     pub(crate) fn write_i32(&mut self, value: i32) {
-        let mut buf = [0, 0, 0, 0];
-        BigEndian::write_i32(&mut buf, value);
-        self.output.extend(&buf);
+        self.output.extend(&endian_bytes!(self, value));
     }
 
     pub(crate) fn write_i64(&mut self, value: i64) {
-        let mut buf = [0, 0, 0, 0, 0, 0, 0, 0];
-        BigEndian::write_i64(&mut buf, value);
-        self.output.extend(&buf);
+        self.output.extend(&endian_bytes!(self, value));
     }
 
     pub(crate) fn write_u16(&mut self, value: u16) {
-        let mut buf = [0, 0];
-        BigEndian::write_u16(&mut buf, value);
-        self.output.extend(&buf);
+        self.output.extend(&endian_bytes!(self, value));
     }
 
     pub(crate) fn write_u32(&mut self, value: u32) {
-        let mut buf = [0, 0, 0, 0];
-        BigEndian::write_u32(&mut buf, value);
-        self.output.extend(&buf);
+        self.output.extend(&endian_bytes!(self, value));
     }
 
     pub(crate) fn write_f32(&mut self, value: f32) {
-        let mut buf = [0, 0, 0, 0];
-        BigEndian::write_f32(&mut buf, value);
-        self.output.extend(&buf);
+        self.output.extend(&endian_bytes!(self, value));
     }
 
     pub(crate) fn write_f64(&mut self, value: f64) {
-        let mut buf = [0, 0, 0, 0, 0, 0, 0, 0];
-        BigEndian::write_f64(&mut buf, value);
-        self.output.extend(&buf);
+        self.output.extend(&endian_bytes!(self, value));
     }
 
     pub(crate) fn write_bytes(&mut self, data: &[u8]) {
@@ -141,20 +468,258 @@ impl NbtWriter {
     }
 
     pub(crate) fn write_tag(&mut self, tag: TagType) {
+        // Every tag starts here, so this is a convenient place to check
+        // whether the buffer should be passed on to the sink.
+        if self.streaming && self.pinned == 0 && self.output.len() >= FLUSH_THRESHOLD {
+            self.flush();
+        }
         self.output.push(tag as u8);
     }
 
     pub(crate) fn write_string(&mut self, input: &str) {
-        let data = to_java_cesu8(input);
-        self.write_u16(data.len() as u16);
-        self.write_bytes(&data);
+        if self.little_endian {
+            self.write_raw_string(input.as_bytes());
+            return;
+        }
+        self.write_raw_string(&encode_java(input));
+    }
+
+    /// Writes a string which has already been encoded to CESU-8.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the string is longer than 65535 bytes, as the length
+    /// wouldn't fit in the prefix.
+    pub(crate) fn write_raw_string(&mut self, data: &[u8]) {
+        let length = u16::try_from(data.len()).unwrap_or_else(|_| {
+            panic!(
+                "String of {} bytes is too long for NBT, the limit is {}",
+                data.len(),
+                u16::MAX
+            )
+        });
+        self.write_u16(length);
+        self.write_bytes(data);
+    }
+
+    /// Writes the length prefix of an array or list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length doesn't fit in 32 bits.
+    pub(crate) fn write_length(&mut self, length: usize) {
+        self.write_u32(checked_length(length));
+    }
+}
+
+/// How many bytes [HashWriter] collects before passing them on to its
+/// hasher.
+const HASH_BLOCK_SIZE: usize = 4096;
+
+/// Adapts a [Hasher] into a [Write], so that anything written is fed
+/// into the hasher. Used by [NbtWriter::with_hasher].
+///
+/// Many hashers give different results depending on how their input is
+/// split up between [Hasher::write] calls, so the input is collected
+/// into fixed-size blocks before being hashed. This way the result only
+/// depends on the bytes written, and not on how they were split up
+/// between calls to [Write::write].
+#[derive(Debug, Clone, Default)]
+pub struct HashWriter<H> {
+    hasher: H,
+    block: Vec<u8>,
+}
+
+impl<H: Hasher> HashWriter<H> {
+    /// Creates a HashWriter which feeds anything written into `hasher`.
+    pub fn new(hasher: H) -> HashWriter<H> {
+        HashWriter {
+            hasher,
+            block: Vec::with_capacity(HASH_BLOCK_SIZE),
+        }
+    }
+
+    /// Passes any partially filled block on to the hasher, and returns
+    /// the resulting hash.
+    pub fn finish(mut self) -> u64 {
+        if !self.block.is_empty() {
+            self.hasher.write(&self.block);
+        }
+        self.hasher.finish()
+    }
+}
+
+impl<H: Hasher> Write for HashWriter<H> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, IoError> {
+        let mut rest = buf;
+        while !rest.is_empty() {
+            let amount = rest.len().min(HASH_BLOCK_SIZE - self.block.len());
+            self.block.extend_from_slice(&rest[..amount]);
+            rest = &rest[amount..];
+            if self.block.len() == HASH_BLOCK_SIZE {
+                self.hasher.write(&self.block);
+                self.block.clear();
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), IoError> {
+        Ok(())
+    }
+}
+
+impl<H: Hasher> NbtWriter<HashWriter<H>> {
+    /// Creates a writer which feeds the document into `hasher` as it's
+    /// built, instead of keeping it in memory. This allows computing a
+    /// digest of a large document without holding all of it at once.
+    ///
+    /// The result is the same as writing the output of
+    /// [NbtWriter::finish] into a [HashWriter] created with the same
+    /// hasher, regardless of how the document was built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use nobility::bin_encode::NbtWriter;
+    /// use std::collections::hash_map::DefaultHasher;
+    ///
+    /// let mut writer = NbtWriter::with_hasher(DefaultHasher::new());
+    /// let mut root = writer.root("hello world");
+    /// root.field("name").string("Bananrama");
+    /// root.finish();
+    /// let hash: u64 = writer.finish_hash();
+    /// # let _ = hash;
+    /// ```
+    pub fn with_hasher(hasher: H) -> NbtWriter<HashWriter<H>> {
+        NbtWriter::new_writer(HashWriter::new(hasher))
+    }
+
+    /// Finalizes a document created using [NbtWriter::with_hasher],
+    /// and returns the hash of its contents.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if root() was never called, as this would
+    /// result in an invalid document.
+    pub fn finish_hash(self) -> u64 {
+        // Hashers can't fail.
+        self.finish_writer().unwrap().finish()
     }
 }
 
-impl fmt::Debug for NbtWriter {
+/// Writes an encoded document to a file, such as the output of
+/// [NbtWriter::finish]. If `compress` is true, it's compressed with gzip
+/// first, which is the format Minecraft uses for most files. Either way,
+/// it can be read back with
+/// [Document::open][crate::bin_decode::Document::open].
+///
+/// # Errors
+///
+/// Fails if the file can't be created or written to.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # fn main() -> Result<(), std::io::Error> {
+/// use nobility::bin_encode::{save_to_path, NbtWriter};
+///
+/// let mut writer = NbtWriter::new();
+/// let mut root = writer.root("hello world");
+/// root.field("name").string("Bananrama");
+/// root.finish();
+/// save_to_path(&writer.finish(), "hello_world.nbt", true)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn save_to_path<P: AsRef<Path>>(bytes: &[u8], path: P, compress: bool) -> Result<(), IoError> {
+    if compress {
+        let mut encoder = GzEncoder::new(File::create(path)?, Compression::default());
+        encoder.write_all(bytes)?;
+        encoder.finish()?;
+        Ok(())
+    } else {
+        std::fs::write(path, bytes)
+    }
+}
+
+/// Converts the length of an array or list to the 32 bits used to store
+/// it.
+///
+/// # Panics
+///
+/// Panics if the length is too large, rather than writing a corrupt
+/// document.
+pub(crate) fn checked_length(length: usize) -> u32 {
+    u32::try_from(length).unwrap_or_else(|_| {
+        panic!(
+            "Length {} is too long for NBT, the limit is {}",
+            length,
+            u32::MAX
+        )
+    })
+}
+
+/// Parses an uncompressed document and re-encodes it in a canonical
+/// form, where the entries of every compound are sorted by name and
+/// empty lists are always written with an element type of TAG_End.
+/// Documents which only differ in those ways will produce identical
+/// output, which makes it suitable for hashing or deduplication.
+///
+/// # Errors
+///
+/// Fails if the input can't be parsed, see
+/// [Document::parse][crate::bin_decode::Document::parse].
+pub fn canonicalize(data: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let mut reader = Reader::with_options(data, ParseOptions::default());
+    let (name, root) = read_root(&mut reader)?;
+
+    let mut writer = NbtWriter::new();
+    writer.write_tag(TagType::Compound);
+    writer.write_raw_string(name.as_bytes());
+    value::write_compound_canonical(&mut writer, &root);
+    writer.done = true;
+    Ok(writer.finish())
+}
+
+/// Checks whether two uncompressed documents are the same after being
+/// passed through [canonicalize].
+///
+/// # Example
+///
+/// ```rust
+/// use nobility::bin_encode::{canonical_eq, NbtWriter};
+///
+/// let mut writer = NbtWriter::new();
+/// let mut root = writer.root("test");
+/// root.field("a").int(1);
+/// root.field("b").int(2);
+/// root.finish();
+/// let first = writer.finish();
+///
+/// let mut writer = NbtWriter::new();
+/// let mut root = writer.root("test");
+/// root.field("b").int(2);
+/// root.field("a").int(1);
+/// root.finish();
+/// let second = writer.finish();
+///
+/// assert!(first != second);
+/// assert!(canonical_eq(&first, &second).unwrap());
+/// ```
+///
+/// # Errors
+///
+/// Fails if either input can't be parsed.
+pub fn canonical_eq(a: &[u8], b: &[u8]) -> Result<bool, ParseError> {
+    Ok(canonicalize(a)? == canonicalize(b)?)
+}
+
+impl<W> fmt::Debug for NbtWriter<W> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         fmt.debug_struct("NbtWriter")
-            .field("bytes_written", &self.output.len())
+            .field("bytes_buffered", &self.output.len())
+            .field("streaming", &self.streaming)
             .field("done", &self.done)
             .finish()
     }
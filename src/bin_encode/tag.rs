@@ -1,6 +1,19 @@
-use crate::bin_encode::{CompoundListWriter, CompoundWriter, NbtWriter};
+use crate::bin_decode::Tag;
+use crate::bin_encode::{
+    checked_length, value, CompoundListWriter, CompoundWriter, NbtWriter, PrimitiveListWriter,
+    WriteValue,
+};
 use crate::TagType;
 use byteorder::{BigEndian, ByteOrder};
+use std::io::Write;
+
+#[derive(Debug)]
+enum FieldName<'a> {
+    /// Encoded to CESU-8 when written.
+    Str(&'a str),
+    /// Already encoded, written as-is.
+    Raw(&'a [u8]),
+}
 
 /// A builder for creating NBT tags. This is created using [CompoundWriter::field].
 ///
@@ -14,25 +27,40 @@ use byteorder::{BigEndian, ByteOrder};
 /// player.field("Health").int(20);
 ///
 /// // There is no bool type in NBT, so bytes 0 and 1 are used instead.
-/// player.field("EnjoysWritingDocumentation").byte(1);
+/// player.field("EnjoysWritingDocumentation").bool(true);
 ///
 /// player.field("FavoriteNumbers").float_list(&[3.14159, 7.0, 2147483647.0]);
 ///
 /// # player.finish();
 /// # let _ = writer.finish();
 /// ```
+///
+/// # Panics
+///
+/// NBT can't represent strings (including field names) longer than
+/// 65535 bytes once encoded as CESU-8, or arrays and lists with more
+/// than [u32::MAX] elements. Writing one of these panics, instead of
+/// producing a corrupt document.
 #[derive(Debug)]
-pub struct TagWriter<'a> {
-    writer: &'a mut NbtWriter,
-    name: Option<&'a str>,
+pub struct TagWriter<'a, W = Vec<u8>> {
+    writer: &'a mut NbtWriter<W>,
+    name: Option<FieldName<'a>>,
     done: bool,
 }
 
-impl<'a> TagWriter<'a> {
-    pub(crate) fn new_field(writer: &'a mut NbtWriter, name: &'a str) -> TagWriter<'a> {
+impl<'a, W: Write> TagWriter<'a, W> {
+    pub(crate) fn new_field(writer: &'a mut NbtWriter<W>, name: &'a str) -> TagWriter<'a, W> {
         TagWriter {
             writer,
-            name: Some(name),
+            name: Some(FieldName::Str(name)),
+            done: false,
+        }
+    }
+
+    pub(crate) fn new_raw_field(writer: &'a mut NbtWriter<W>, name: &'a [u8]) -> TagWriter<'a, W> {
+        TagWriter {
+            writer,
+            name: Some(FieldName::Raw(name)),
             done: false,
         }
     }
@@ -43,8 +71,10 @@ impl<'a> TagWriter<'a> {
         }
 
         self.writer.write_tag(tag);
-        if let Some(name) = self.name {
-            self.writer.write_string(name);
+        match self.name {
+            Some(FieldName::Str(name)) => self.writer.write_string(name),
+            Some(FieldName::Raw(name)) => self.writer.write_raw_string(name),
+            None => (),
         }
         self.done = true;
     }
@@ -55,6 +85,12 @@ impl<'a> TagWriter<'a> {
         self.writer.write_i8(value);
     }
 
+    /// Create a TAG_Byte of 0 or 1. NBT has no boolean type, so this
+    /// is what Minecraft uses instead.
+    pub fn bool(&mut self, value: bool) {
+        self.byte(value as i8);
+    }
+
     /// Create a TAG_Short.
     pub fn short(&mut self, value: i16) {
         self.header(TagType::Short);
@@ -88,11 +124,38 @@ impl<'a> TagWriter<'a> {
     /// Create a TAG_Byte_Array.
     pub fn byte_array(&mut self, data: &[u8]) {
         self.header(TagType::ByteArray);
-        self.writer.write_u32(data.len() as u32);
+        self.writer.write_length(data.len());
         self.writer.write_bytes(data);
     }
 
-    /// Create a TAG_String.
+    /// Create a TAG_Byte_Array by packing booleans into bytes,
+    /// LSB-first. The last byte is padded with zeroes. This is the
+    /// inverse of [Tag::byte_array_bit][crate::bin_decode::Tag::byte_array_bit].
+    pub fn byte_array_from_bits<I>(&mut self, bits: I)
+    where
+        I: IntoIterator<Item = bool>,
+    {
+        let mut data = vec![];
+        for (index, bit) in bits.into_iter().enumerate() {
+            if index % 8 == 0 {
+                data.push(0);
+            }
+            if bit {
+                *data.last_mut().unwrap() |= 1 << (index % 8);
+            }
+        }
+        self.byte_array(&data);
+    }
+
+    /// Create a TAG_String. The string is encoded to Java's CESU-8,
+    /// where `\0` takes up 2 bytes and characters outside of the Basic
+    /// Multilingual Plane take up 6 bytes. It decodes back to the same
+    /// string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the encoded string is longer than 65535 bytes. Use
+    /// [NbtWriter::check_string] to check for this ahead of time.
     pub fn string(&mut self, value: &str) {
         self.header(TagType::String);
         self.writer.write_string(value);
@@ -103,12 +166,11 @@ impl<'a> TagWriter<'a> {
     /// UTF-8.
     pub fn raw_string(&mut self, data: &[u8]) {
         self.header(TagType::String);
-        self.writer.write_u16(data.len() as u16);
-        self.writer.write_bytes(data);
+        self.writer.write_raw_string(data);
     }
 
     /// Create a TAG_Compound and returns a builder for its contents.
-    pub fn compound(&'a mut self) -> CompoundWriter<'a> {
+    pub fn compound(&'a mut self) -> CompoundWriter<'a, W> {
         self.header(TagType::Compound);
         CompoundWriter::new(self.writer)
     }
@@ -116,26 +178,79 @@ impl<'a> TagWriter<'a> {
     /// Create a TAG_Int_Array from the given slice.
     pub fn int_array(&mut self, data: &[i32]) {
         self.header(TagType::IntArray);
-        self.writer.write_u32(data.len() as u32);
+        self.writer.write_length(data.len());
         for element in data {
             self.writer.write_i32(*element);
         }
     }
 
+    /// Create a TAG_Int_Array from a fixed-size array. This is the
+    /// counterpart to [IntArray::as_array][crate::bin_decode::NbtArray::as_array].
+    pub fn int_array_from<const N: usize>(&mut self, data: [i32; N]) {
+        self.int_array(&data);
+    }
+
+    /// Create a TAG_Int_Array from elements that are already in
+    /// big-endian byte order, such as from
+    /// [NbtArray::as_raw_bytes][crate::bin_decode::NbtArray::as_raw_bytes].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `bytes` isn't a multiple of 4.
+    pub fn int_array_raw(&mut self, bytes: &[u8]) {
+        self.array_raw(TagType::IntArray, 4, bytes);
+    }
+
     /// Create a TAG_Long_Array from the given slice.
     pub fn long_array(&mut self, data: &[i64]) {
-        self.header(TagType::IntArray);
-        self.writer.write_u32(data.len() as u32);
+        self.header(TagType::LongArray);
+        self.writer.write_length(data.len());
         for element in data {
             self.writer.write_i64(*element);
         }
     }
 
+    /// Same as [TagWriter::int_array_raw], but creates a
+    /// TAG_Long_Array.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `bytes` isn't a multiple of 8.
+    pub fn long_array_raw(&mut self, bytes: &[u8]) {
+        self.array_raw(TagType::LongArray, 8, bytes);
+    }
+
+    fn array_raw(&mut self, tag: TagType, size: usize, bytes: &[u8]) {
+        if bytes.len() % size != 0 {
+            panic!(
+                "{} bytes can't be split into {} byte elements for {}",
+                bytes.len(),
+                size,
+                tag.name()
+            );
+        }
+        self.header(tag);
+        self.writer.write_length(bytes.len() / size);
+        self.writer.write_bytes(bytes);
+    }
+
+    /// Create an empty TAG_List with the given element type. Passing
+    /// [TagType::End] matches what Minecraft writes for empty lists,
+    /// which is also what
+    /// [List::from_empty][crate::bin_decode::List::from_empty] and
+    /// [List::element_type][crate::bin_decode::List::element_type] use
+    /// for them.
+    pub fn empty_list(&mut self, element: TagType) {
+        self.header(TagType::List);
+        self.writer.write_tag(element);
+        self.writer.write_u32(0);
+    }
+
     /// Create a TAG_List of TAG_Byte.
     pub fn byte_list(&mut self, data: &[u8]) {
         self.header(TagType::List);
         self.writer.write_tag(TagType::Byte);
-        self.writer.write_u32(data.len() as u32);
+        self.writer.write_length(data.len());
         self.writer.write_bytes(data);
     }
 
@@ -143,7 +258,7 @@ impl<'a> TagWriter<'a> {
     pub fn short_list(&mut self, data: &[i16]) {
         self.header(TagType::List);
         self.writer.write_tag(TagType::Short);
-        self.writer.write_u32(data.len() as u32);
+        self.writer.write_length(data.len());
         for element in data {
             self.writer.write_i16(*element);
         }
@@ -153,7 +268,7 @@ impl<'a> TagWriter<'a> {
     pub fn int_list(&mut self, data: &[i32]) {
         self.header(TagType::List);
         self.writer.write_tag(TagType::Int);
-        self.writer.write_u32(data.len() as u32);
+        self.writer.write_length(data.len());
         for element in data {
             self.writer.write_i32(*element);
         }
@@ -163,7 +278,7 @@ impl<'a> TagWriter<'a> {
     pub fn long_list(&mut self, data: &[i64]) {
         self.header(TagType::List);
         self.writer.write_tag(TagType::Long);
-        self.writer.write_u32(data.len() as u32);
+        self.writer.write_length(data.len());
         for element in data {
             self.writer.write_i64(*element);
         }
@@ -173,7 +288,7 @@ impl<'a> TagWriter<'a> {
     pub fn float_list(&mut self, data: &[f32]) {
         self.header(TagType::List);
         self.writer.write_tag(TagType::Float);
-        self.writer.write_u32(data.len() as u32);
+        self.writer.write_length(data.len());
         for element in data {
             self.writer.write_f32(*element);
         }
@@ -183,19 +298,120 @@ impl<'a> TagWriter<'a> {
     pub fn double_list(&mut self, data: &[f64]) {
         self.header(TagType::List);
         self.writer.write_tag(TagType::Double);
-        self.writer.write_u32(data.len() as u32);
+        self.writer.write_length(data.len());
         for element in data {
             self.writer.write_f64(*element);
         }
     }
 
+    /// Create a TAG_List of TAG_Byte from an iterator. The length is
+    /// filled in afterwards, so the elements don't have to be collected
+    /// first.
+    pub fn byte_list_iter<I: IntoIterator<Item = i8>>(&mut self, iter: I) {
+        self.list_iter(TagType::Byte, iter, NbtWriter::write_i8);
+    }
+
+    /// Create a TAG_List of TAG_Short from an iterator. See
+    /// [TagWriter::byte_list_iter].
+    pub fn short_list_iter<I: IntoIterator<Item = i16>>(&mut self, iter: I) {
+        self.list_iter(TagType::Short, iter, NbtWriter::write_i16);
+    }
+
+    /// Create a TAG_List of TAG_Int from an iterator. See
+    /// [TagWriter::byte_list_iter].
+    pub fn int_list_iter<I: IntoIterator<Item = i32>>(&mut self, iter: I) {
+        self.list_iter(TagType::Int, iter, NbtWriter::write_i32);
+    }
+
+    /// Create a TAG_List of TAG_Long from an iterator. See
+    /// [TagWriter::byte_list_iter].
+    pub fn long_list_iter<I: IntoIterator<Item = i64>>(&mut self, iter: I) {
+        self.list_iter(TagType::Long, iter, NbtWriter::write_i64);
+    }
+
+    /// Create a TAG_List of TAG_Float from an iterator. See
+    /// [TagWriter::byte_list_iter].
+    pub fn float_list_iter<I: IntoIterator<Item = f32>>(&mut self, iter: I) {
+        self.list_iter(TagType::Float, iter, NbtWriter::write_f32);
+    }
+
+    /// Create a TAG_List of TAG_Double from an iterator. See
+    /// [TagWriter::byte_list_iter].
+    pub fn double_list_iter<I: IntoIterator<Item = f64>>(&mut self, iter: I) {
+        self.list_iter(TagType::Double, iter, NbtWriter::write_f64);
+    }
+
+    /// Create a TAG_List of TAG_Byte, and return a builder which
+    /// elements can be pushed onto one at a time. See
+    /// [PrimitiveListWriter].
+    pub fn byte_list_builder(&'a mut self) -> PrimitiveListWriter<'a, i8, W> {
+        self.list_builder(TagType::Byte, NbtWriter::write_i8)
+    }
+
+    /// Create a TAG_List of TAG_Short, and return a builder for it. See
+    /// [TagWriter::byte_list_builder].
+    pub fn short_list_builder(&'a mut self) -> PrimitiveListWriter<'a, i16, W> {
+        self.list_builder(TagType::Short, NbtWriter::write_i16)
+    }
+
+    /// Create a TAG_List of TAG_Int, and return a builder for it. See
+    /// [TagWriter::byte_list_builder].
+    pub fn int_list_builder(&'a mut self) -> PrimitiveListWriter<'a, i32, W> {
+        self.list_builder(TagType::Int, NbtWriter::write_i32)
+    }
+
+    /// Create a TAG_List of TAG_Long, and return a builder for it. See
+    /// [TagWriter::byte_list_builder].
+    pub fn long_list_builder(&'a mut self) -> PrimitiveListWriter<'a, i64, W> {
+        self.list_builder(TagType::Long, NbtWriter::write_i64)
+    }
+
+    /// Create a TAG_List of TAG_Float, and return a builder for it. See
+    /// [TagWriter::byte_list_builder].
+    pub fn float_list_builder(&'a mut self) -> PrimitiveListWriter<'a, f32, W> {
+        self.list_builder(TagType::Float, NbtWriter::write_f32)
+    }
+
+    /// Create a TAG_List of TAG_Double, and return a builder for it.
+    /// See [TagWriter::byte_list_builder].
+    pub fn double_list_builder(&'a mut self) -> PrimitiveListWriter<'a, f64, W> {
+        self.list_builder(TagType::Double, NbtWriter::write_f64)
+    }
+
+    fn list_builder<T>(
+        &'a mut self,
+        element: TagType,
+        func: fn(&mut NbtWriter<W>, T),
+    ) -> PrimitiveListWriter<'a, T, W> {
+        self.header(TagType::List);
+        PrimitiveListWriter::new(self.writer, element, func)
+    }
+
+    fn list_iter<T, I>(&mut self, element: TagType, iter: I, func: fn(&mut NbtWriter<W>, T))
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self.header(TagType::List);
+        self.writer.write_tag(element);
+        // Same as CompoundListWriter, the length is overwritten once
+        // all of the elements have been written.
+        let offset = self.writer.pin();
+        self.writer.write_u32(0);
+        let mut length = 0;
+        for value in iter {
+            func(self.writer, value);
+            length += 1;
+        }
+        self.writer.patch_u32(offset, checked_length(length));
+    }
+
     /// Create a TAG_List of TAG_String.
     pub fn string_list(&mut self, data: &[&str]) {
         self.header(TagType::List);
         self.writer.write_tag(TagType::String);
-        self.writer.write_u32(data.len() as u32);
+        self.writer.write_length(data.len());
         for element in data {
-            self.writer.write_string(*element);
+            self.writer.write_string(element);
         }
     }
 
@@ -203,19 +419,26 @@ impl<'a> TagWriter<'a> {
     pub fn byte_array_list(&mut self, data: &[&[u8]]) {
         self.header(TagType::List);
         self.writer.write_tag(TagType::ByteArray);
-        self.writer.write_u32(data.len() as u32);
+        self.writer.write_length(data.len());
         for element in data {
-            self.writer.write_u32(element.len() as u32);
+            self.writer.write_length(element.len());
             self.writer.write_bytes(element);
         }
     }
 
     /// Create a TAG_List of TAG_Compound.
-    pub fn compound_list(&'a mut self) -> CompoundListWriter<'a> {
+    pub fn compound_list(&'a mut self) -> CompoundListWriter<'a, W> {
         self.header(TagType::List);
         CompoundListWriter::new(self.writer)
     }
 
+    /// Create a TAG_List of TAG_Compound, where the number of elements
+    /// is declared up front. See [CompoundListWriter] for details.
+    pub fn compound_list_with_count(&'a mut self, count: usize) -> CompoundListWriter<'a, W> {
+        self.header(TagType::List);
+        CompoundListWriter::with_count(self.writer, count)
+    }
+
     /// Writes the bytes of a UUID in the Minecraft 1.16+ format
     /// (TAG_Int_Array of length 4).
     pub fn uuid_bytes(&mut self, bytes: [u8; 16]) {
@@ -234,6 +457,26 @@ impl<'a> TagWriter<'a> {
         self.uuid_bytes(*uuid.as_bytes());
     }
 
+    /// Writes a dynamically typed [WriteValue], picking the tag type
+    /// based on its variant.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a [WriteValue::List] inside of the value has elements
+    /// of differing types.
+    pub fn write(&mut self, value: &WriteValue) {
+        self.header(value.tag_type());
+        value::write_dynamic(self.writer, value);
+    }
+
+    /// Writes a decoded [Tag], including everything nested inside of
+    /// it. Strings and names are copied as raw bytes, so invalid CESU-8
+    /// is preserved exactly.
+    pub fn write_tag_value(&mut self, value: &Tag) {
+        self.header(value.tag_type());
+        value::write_value(self.writer, value);
+    }
+
     // todo: list list, compound list, int array list, long array list
 
     /// Returns whether or not the tag has been written into.
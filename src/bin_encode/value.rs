@@ -0,0 +1,294 @@
+//! Writing of dynamically typed values, either from [WriteValue] or
+//! from decoded tags. Names and strings in decoded tags are written
+//! using their raw bytes, so that invalid CESU-8 is preserved exactly.
+
+use crate::bin_decode::{Compound, List, NbtArray, NbtPrimitive, Tag};
+use crate::bin_encode::NbtWriter;
+use crate::TagType;
+use std::io::Write;
+
+/// A dynamically typed value that can be written using
+/// [TagWriter::write][crate::bin_encode::TagWriter::write], for when
+/// the structure of the data isn't known at compile time.
+///
+/// # Example
+///
+/// ```rust
+/// # use nobility::bin_encode::NbtWriter;
+/// use nobility::bin_encode::WriteValue;
+///
+/// let value = WriteValue::Compound(vec![
+///     ("Name", WriteValue::String("Alice")),
+///     ("Scores", WriteValue::List(vec![WriteValue::Int(3), WriteValue::Int(5)])),
+/// ]);
+///
+/// # let mut writer = NbtWriter::new();
+/// # let mut root = writer.root("test");
+/// root.field("Player").write(&value);
+/// # root.finish();
+/// # let _ = writer.finish();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum WriteValue<'a> {
+    /// Written as TAG_Byte.
+    Byte(i8),
+    /// Written as TAG_Short.
+    Short(i16),
+    /// Written as TAG_Int.
+    Int(i32),
+    /// Written as TAG_Long.
+    Long(i64),
+    /// Written as TAG_Float.
+    Float(f32),
+    /// Written as TAG_Double.
+    Double(f64),
+    /// Written as TAG_Byte_Array.
+    ByteArray(&'a [u8]),
+    /// Written as TAG_String.
+    String(&'a str),
+    /// Written as TAG_Int_Array.
+    IntArray(&'a [i32]),
+    /// Written as TAG_Long_Array.
+    LongArray(&'a [i64]),
+    /// Written as TAG_List. All of the elements must have the same
+    /// type. Empty lists are written with an element type of TAG_End.
+    List(Vec<WriteValue<'a>>),
+    /// Written as TAG_Compound, with the entries in the same order.
+    Compound(Vec<(&'a str, WriteValue<'a>)>),
+}
+
+impl<'a> WriteValue<'a> {
+    /// Returns the type of tag that this value will be written as.
+    pub fn tag_type(&self) -> TagType {
+        match self {
+            WriteValue::Byte(_) => TagType::Byte,
+            WriteValue::Short(_) => TagType::Short,
+            WriteValue::Int(_) => TagType::Int,
+            WriteValue::Long(_) => TagType::Long,
+            WriteValue::Float(_) => TagType::Float,
+            WriteValue::Double(_) => TagType::Double,
+            WriteValue::ByteArray(_) => TagType::ByteArray,
+            WriteValue::String(_) => TagType::String,
+            WriteValue::IntArray(_) => TagType::IntArray,
+            WriteValue::LongArray(_) => TagType::LongArray,
+            WriteValue::List(_) => TagType::List,
+            WriteValue::Compound(_) => TagType::Compound,
+        }
+    }
+}
+
+/// Writes the payload of a [WriteValue], not including its type or
+/// name.
+///
+/// # Panics
+///
+/// Panics if a list contains elements of differing types.
+pub(crate) fn write_dynamic<W: Write>(writer: &mut NbtWriter<W>, value: &WriteValue) {
+    match value {
+        WriteValue::Byte(value) => writer.write_i8(*value),
+        WriteValue::Short(value) => writer.write_i16(*value),
+        WriteValue::Int(value) => writer.write_i32(*value),
+        WriteValue::Long(value) => writer.write_i64(*value),
+        WriteValue::Float(value) => writer.write_f32(*value),
+        WriteValue::Double(value) => writer.write_f64(*value),
+        WriteValue::ByteArray(value) => write_byte_array(writer, value),
+        WriteValue::String(value) => writer.write_string(value),
+        WriteValue::IntArray(value) => {
+            writer.write_length(value.len());
+            for element in value.iter() {
+                writer.write_i32(*element);
+            }
+        }
+        WriteValue::LongArray(value) => {
+            writer.write_length(value.len());
+            for element in value.iter() {
+                writer.write_i64(*element);
+            }
+        }
+        WriteValue::List(elements) => {
+            let element_type = elements.first().map_or(TagType::End, WriteValue::tag_type);
+            if elements
+                .iter()
+                .any(|element| element.tag_type() != element_type)
+            {
+                panic!("All elements of WriteValue::List must have the same type");
+            }
+            writer.write_tag(element_type);
+            writer.write_length(elements.len());
+            for element in elements {
+                write_dynamic(writer, element);
+            }
+        }
+        WriteValue::Compound(entries) => {
+            for (name, value) in entries {
+                writer.write_tag(value.tag_type());
+                writer.write_string(name);
+                write_dynamic(writer, value);
+            }
+            writer.write_tag(TagType::End);
+        }
+    }
+}
+
+/// Writes the payload of a tag, not including its type or name.
+pub(crate) fn write_value<W: Write>(writer: &mut NbtWriter<W>, value: &Tag) {
+    match value {
+        Tag::Byte(value) => writer.write_i8(*value),
+        Tag::Short(value) => writer.write_i16(*value),
+        Tag::Int(value) => writer.write_i32(*value),
+        Tag::Long(value) => writer.write_i64(*value),
+        Tag::Float(value) => writer.write_f32(*value),
+        Tag::Double(value) => writer.write_f64(*value),
+        Tag::ByteArray(value) => write_byte_array(writer, value),
+        Tag::String(value) => writer.write_raw_string(value.as_bytes()),
+        Tag::IntArray(value) => write_array(writer, value, NbtWriter::write_i32),
+        Tag::LongArray(value) => write_array(writer, value, NbtWriter::write_i64),
+        Tag::List(value) => write_list(writer, value),
+        Tag::Compound(value) => write_compound(writer, value),
+    }
+}
+
+/// Writes every entry of the compound, followed by TAG_End.
+pub(crate) fn write_compound<W: Write>(writer: &mut NbtWriter<W>, compound: &Compound) {
+    for entry in compound.iter() {
+        writer.write_tag(entry.value().tag_type());
+        writer.write_raw_string(entry.name().as_bytes());
+        write_value(writer, entry.value());
+    }
+    writer.write_tag(TagType::End);
+}
+
+/// Same as [write_compound], but in a canonical form: entries are
+/// sorted by the bytes of their names, and empty lists always use
+/// TAG_End as their element type. This is applied recursively.
+pub(crate) fn write_compound_canonical<W: Write>(writer: &mut NbtWriter<W>, compound: &Compound) {
+    let mut entries: Vec<_> = compound.iter().collect();
+    entries.sort_by(|left, right| left.name().as_bytes().cmp(right.name().as_bytes()));
+    for entry in entries {
+        let value = entry.value();
+        writer.write_tag(value.tag_type());
+        writer.write_raw_string(entry.name().as_bytes());
+        match value {
+            Tag::Compound(value) => write_compound_canonical(writer, value),
+            Tag::List(value) => write_list_canonical(writer, value),
+            value => write_value(writer, value),
+        }
+    }
+    writer.write_tag(TagType::End);
+}
+
+fn write_list_canonical<W: Write>(writer: &mut NbtWriter<W>, list: &List) {
+    match list {
+        list if list.is_empty() => {
+            writer.write_tag(TagType::End);
+            writer.write_u32(0);
+        }
+        List::Compound(list) => {
+            writer.write_tag(TagType::Compound);
+            writer.write_length(list.len());
+            for element in list.iter() {
+                write_compound_canonical(writer, element);
+            }
+        }
+        List::List(list) => {
+            writer.write_tag(TagType::List);
+            writer.write_length(list.len());
+            for element in list.iter() {
+                write_list_canonical(writer, element);
+            }
+        }
+        list => write_list(writer, list),
+    }
+}
+
+fn write_byte_array<W: Write>(writer: &mut NbtWriter<W>, data: &[u8]) {
+    writer.write_length(data.len());
+    writer.write_bytes(data);
+}
+
+fn write_array<T, W>(writer: &mut NbtWriter<W>, array: &NbtArray<T>, func: fn(&mut NbtWriter<W>, T))
+where
+    T: NbtPrimitive,
+    W: Write,
+{
+    writer.write_length(array.len());
+    for element in array.iter() {
+        func(writer, element);
+    }
+}
+
+fn write_list<W: Write>(writer: &mut NbtWriter<W>, list: &List) {
+    match list {
+        List::End => {
+            writer.write_tag(TagType::End);
+            writer.write_u32(0);
+        }
+        List::Byte(list) => {
+            writer.write_tag(TagType::Byte);
+            write_array(writer, list, NbtWriter::write_i8);
+        }
+        List::Short(list) => {
+            writer.write_tag(TagType::Short);
+            write_array(writer, list, NbtWriter::write_i16);
+        }
+        List::Int(list) => {
+            writer.write_tag(TagType::Int);
+            write_array(writer, list, NbtWriter::write_i32);
+        }
+        List::Long(list) => {
+            writer.write_tag(TagType::Long);
+            write_array(writer, list, NbtWriter::write_i64);
+        }
+        List::Float(list) => {
+            writer.write_tag(TagType::Float);
+            write_array(writer, list, NbtWriter::write_f32);
+        }
+        List::Double(list) => {
+            writer.write_tag(TagType::Double);
+            write_array(writer, list, NbtWriter::write_f64);
+        }
+        List::ByteArray(list) => {
+            writer.write_tag(TagType::ByteArray);
+            writer.write_length(list.len());
+            for element in list.iter() {
+                write_byte_array(writer, element);
+            }
+        }
+        List::String(list) => {
+            writer.write_tag(TagType::String);
+            writer.write_length(list.len());
+            for element in list.iter() {
+                writer.write_raw_string(element.as_bytes());
+            }
+        }
+        List::Compound(list) => {
+            writer.write_tag(TagType::Compound);
+            writer.write_length(list.len());
+            for element in list.iter() {
+                write_compound(writer, element);
+            }
+        }
+        List::List(list) => {
+            writer.write_tag(TagType::List);
+            writer.write_length(list.len());
+            for element in list.iter() {
+                write_list(writer, element);
+            }
+        }
+        List::IntArray(list) => {
+            writer.write_tag(TagType::IntArray);
+            writer.write_length(list.len());
+            for element in list.iter() {
+                write_array(writer, element, NbtWriter::write_i32);
+            }
+        }
+        List::LongArray(list) => {
+            writer.write_tag(TagType::LongArray);
+            writer.write_length(list.len());
+            for element in list.iter() {
+                write_array(writer, element, NbtWriter::write_i64);
+            }
+        }
+    }
+}
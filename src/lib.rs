@@ -6,13 +6,30 @@
 //!
 //! The encoder is builder-based and does not take in any kind of
 //! document structure.
+//!
+//! Everything is available with the default `std` feature. Without it,
+//! the crate is `no_std` and only needs `alloc`, which leaves the parts
+//! of the decoder that work on data that's already in memory, such as
+//! [bin_decode::parse_borrowed].
 
 #![doc(html_root_url = "https://docs.rs/nobility/0.2.0")]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
 /// Contains the implementation of the binary format decoder.
 pub mod bin_decode;
 /// Contains the implementation of the binary format encoder.
+#[cfg(feature = "std")]
 pub mod bin_encode;
+/// Contains a reader and writer for Anvil region files, which are how
+/// Minecraft stores chunks on disk.
+#[cfg(feature = "std")]
+pub mod region;
+/// Contains a parser for SNBT, the text format used in Minecraft
+/// commands.
+#[cfg(feature = "std")]
+pub mod snbt;
 
 /// NBT tags are a 1-byte value used to specify which type is going to
 /// follow. The integer values of each enum corresponds to the actual
@@ -52,3 +69,107 @@ pub enum TagType {
     /// Contains a [bin_decode::LongArray]. `TAG_Long_Array`, ID 12.
     LongArray = 12,
 }
+
+impl TagType {
+    /// Looks up the tag with the given ID, which is the inverse of
+    /// `tag as u8`. Returns None for IDs that aren't known.
+    pub fn from_u8(value: u8) -> Option<TagType> {
+        match value {
+            0 => Some(TagType::End),
+            1 => Some(TagType::Byte),
+            2 => Some(TagType::Short),
+            3 => Some(TagType::Int),
+            4 => Some(TagType::Long),
+            5 => Some(TagType::Float),
+            6 => Some(TagType::Double),
+            7 => Some(TagType::ByteArray),
+            8 => Some(TagType::String),
+            9 => Some(TagType::List),
+            10 => Some(TagType::Compound),
+            11 => Some(TagType::IntArray),
+            12 => Some(TagType::LongArray),
+            _ => None,
+        }
+    }
+
+    /// Returns true for the tags that contain a single number, Byte
+    /// through Double.
+    pub fn is_primitive(&self) -> bool {
+        self.element_size().is_some()
+    }
+
+    /// Returns true for ByteArray, IntArray, and LongArray.
+    pub fn is_array(&self) -> bool {
+        matches!(
+            self,
+            TagType::ByteArray | TagType::IntArray | TagType::LongArray
+        )
+    }
+
+    /// Returns true for List and Compound, which contain other tags.
+    pub fn is_container(&self) -> bool {
+        matches!(self, TagType::List | TagType::Compound)
+    }
+
+    /// Returns the size in bytes of the primitive tags, which always
+    /// have the same size. Returns None for all other tags.
+    pub fn element_size(&self) -> Option<usize> {
+        match self {
+            TagType::Byte => Some(1),
+            TagType::Short => Some(2),
+            TagType::Int => Some(4),
+            TagType::Long => Some(8),
+            TagType::Float => Some(4),
+            TagType::Double => Some(8),
+            _ => None,
+        }
+    }
+
+    /// Returns the name used for this tag in the specification, such
+    /// as `"TAG_Byte"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TagType::End => "TAG_End",
+            TagType::Byte => "TAG_Byte",
+            TagType::Short => "TAG_Short",
+            TagType::Int => "TAG_Int",
+            TagType::Long => "TAG_Long",
+            TagType::Float => "TAG_Float",
+            TagType::Double => "TAG_Double",
+            TagType::ByteArray => "TAG_Byte_Array",
+            TagType::String => "TAG_String",
+            TagType::List => "TAG_List",
+            TagType::Compound => "TAG_Compound",
+            TagType::IntArray => "TAG_Int_Array",
+            TagType::LongArray => "TAG_Long_Array",
+        }
+    }
+}
+
+/// Converts a UUID from the format used since Minecraft 1.16, a
+/// TAG_Int_Array of 4 elements, to the older format of two longs stored
+/// in separate fields such as `UUIDMost` and `UUIDLeast`. The most
+/// significant half comes first in both.
+///
+/// # Example
+///
+/// ```rust
+/// use nobility::{uuid_ints_to_longs, uuid_longs_to_ints};
+///
+/// let ints = [1, 2, 3, 4];
+/// let longs = uuid_ints_to_longs(ints);
+/// assert_eq!(longs, [(1 << 32) | 2, (3 << 32) | 4]);
+/// assert_eq!(uuid_longs_to_ints(longs), ints);
+/// ```
+pub fn uuid_ints_to_longs(ints: [i32; 4]) -> [i64; 2] {
+    let join = |high: i32, low: i32| ((high as u32 as u64) << 32 | low as u32 as u64) as i64;
+    [join(ints[0], ints[1]), join(ints[2], ints[3])]
+}
+
+/// The inverse of [uuid_ints_to_longs], converting the two long form
+/// of a UUID into the TAG_Int_Array form used since Minecraft 1.16.
+pub fn uuid_longs_to_ints(longs: [i64; 2]) -> [i32; 4] {
+    let high = |value: i64| (value >> 32) as i32;
+    let low = |value: i64| value as i32;
+    [high(longs[0]), low(longs[0]), high(longs[1]), low(longs[1])]
+}
@@ -0,0 +1,262 @@
+use crate::bin_decode::{Document, ParseError};
+use byteorder::{BigEndian, ByteOrder, WriteBytesExt};
+use flate2::read::{GzDecoder, ZlibDecoder};
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use std::fmt;
+use std::io::{Error as IoError, ErrorKind, Read, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Region files are split into sectors of this many bytes.
+const SECTOR_SIZE: usize = 4096;
+/// The offset table and the timestamp table, one sector each.
+const HEADER_SIZE: usize = SECTOR_SIZE * 2;
+/// The header stores each chunk's size in sectors as a single byte.
+const MAX_CHUNK_SECTORS: usize = 255;
+
+/// An Anvil region file (`.mca`), which stores the chunks of a 32x32
+/// chunk area, each compressed separately.
+///
+/// The whole file is kept in memory, and chunks are decompressed when
+/// they're requested.
+///
+/// # Example
+///
+/// ```rust
+/// # use std::error::Error;
+/// #
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use nobility::region::RegionFile;
+///
+/// let region = RegionFile::open("files/sample.mca")?;
+/// if let Some(chunk) = region.chunk(0, 0) {
+///     let chunk = chunk?;
+///     let (_name, root) = chunk.parse()?;
+///     println!("{:#?}", root);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct RegionFile {
+    data: Vec<u8>,
+}
+
+impl RegionFile {
+    /// Reads a region file from any source implementing Read.
+    ///
+    /// # Errors
+    ///
+    /// Fails if reading from `input` fails, or with
+    /// [ErrorKind::InvalidData] if there's less data than the 8KiB
+    /// header. Empty files are accepted, since Minecraft sometimes
+    /// creates them, and have no chunks.
+    pub fn load<R: Read>(mut input: R) -> Result<RegionFile, IoError> {
+        let mut data = vec![];
+        input.read_to_end(&mut data)?;
+        if data.is_empty() {
+            data.resize(HEADER_SIZE, 0);
+        } else if data.len() < HEADER_SIZE {
+            return Err(IoError::new(
+                ErrorKind::InvalidData,
+                "Region file is smaller than its header",
+            ));
+        }
+        Ok(RegionFile { data })
+    }
+
+    /// Reads a region file from disk. See [RegionFile::load].
+    ///
+    /// # Errors
+    ///
+    /// Same as [RegionFile::load].
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<RegionFile, IoError> {
+        RegionFile::load(std::fs::File::open(path)?)
+    }
+
+    /// Decompresses the chunk at the given coordinates, returning None
+    /// if it hasn't been generated. Coordinates are taken modulo 32, so
+    /// either the chunk's absolute coordinates or its position within
+    /// the region can be used.
+    ///
+    /// Chunks compressed with gzip (scheme 1), zlib (scheme 2) or not
+    /// compressed at all (scheme 3) are supported.
+    ///
+    /// # Errors
+    ///
+    /// Returns [ParseError::UnknownCompression] if the chunk uses any
+    /// other scheme, or is stored outside of the region file. Returns
+    /// [ParseError::CorruptChunk] if the header points outside of the
+    /// file, or if the chunk fails to decompress.
+    pub fn chunk(&self, x: i32, z: i32) -> Option<Result<Document, ParseError>> {
+        let index = chunk_index(x, z);
+        let location = BigEndian::read_u32(&self.data[index * 4..]);
+        if location == 0 {
+            return None;
+        }
+        Some(self.read_chunk((location >> 8) as usize * SECTOR_SIZE))
+    }
+
+    /// Returns when the chunk at the given coordinates was last saved,
+    /// in seconds since the Unix epoch, or None if it hasn't been
+    /// generated. Coordinates are taken modulo 32, like
+    /// [RegionFile::chunk].
+    pub fn timestamp(&self, x: i32, z: i32) -> Option<u32> {
+        let index = chunk_index(x, z);
+        if BigEndian::read_u32(&self.data[index * 4..]) == 0 {
+            return None;
+        }
+        Some(BigEndian::read_u32(&self.data[SECTOR_SIZE + index * 4..]))
+    }
+
+    fn read_chunk(&self, offset: usize) -> Result<Document, ParseError> {
+        let corrupt = ParseError::CorruptChunk { offset };
+        let prefix = self.data.get(offset..offset + 5).ok_or(corrupt)?;
+        // The length includes the compression scheme byte.
+        let length = BigEndian::read_u32(prefix) as usize;
+        let scheme = prefix[4];
+        let payload = self
+            .data
+            .get(offset + 5..offset + 4 + length)
+            .ok_or(ParseError::CorruptChunk { offset })?;
+
+        let mut data = vec![];
+        let result = match scheme {
+            1 => GzDecoder::new(payload).read_to_end(&mut data),
+            2 => ZlibDecoder::new(payload).read_to_end(&mut data),
+            3 => {
+                data.extend_from_slice(payload);
+                Ok(data.len())
+            }
+            _ => return Err(ParseError::UnknownCompression { scheme, offset }),
+        };
+        result.map_err(|_| ParseError::CorruptChunk { offset })?;
+        Ok(Document::from(data))
+    }
+}
+
+impl fmt::Debug for RegionFile {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "RegionFile({} B buffer)", self.data.len())
+    }
+}
+
+/// A builder for an Anvil region file. Chunks are compressed with zlib
+/// as they're added, and laid out into sectors once the region is
+/// finished.
+///
+/// # Example
+///
+/// ```rust
+/// use nobility::bin_encode::NbtWriter;
+/// use nobility::region::RegionFileWriter;
+///
+/// let mut chunk = NbtWriter::new();
+/// let mut root = chunk.root("");
+/// root.field("xPos").int(0);
+/// root.field("zPos").int(0);
+/// root.finish();
+///
+/// let mut region = RegionFileWriter::new();
+/// region.chunk(0, 0, &chunk.finish());
+/// let data: Vec<u8> = region.finish();
+/// ```
+pub struct RegionFileWriter {
+    /// The timestamp and compressed data of each chunk, indexed the
+    /// same way as the header.
+    chunks: Vec<Option<(u32, Vec<u8>)>>,
+}
+
+impl RegionFileWriter {
+    /// Creates a region with no chunks.
+    pub fn new() -> RegionFileWriter {
+        RegionFileWriter {
+            chunks: vec![None; 1024],
+        }
+    }
+
+    /// Adds an encoded chunk at the given coordinates, timestamped with
+    /// the current time. Coordinates are taken modulo 32, like
+    /// [RegionFile::chunk]. Adding a chunk where there already is one
+    /// replaces it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the chunk is still larger than 1020KiB after
+    /// compression, since that won't fit in a region file.
+    pub fn chunk(&mut self, x: i32, z: i32, data: &[u8]) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_secs() as u32)
+            .unwrap_or(0);
+        self.chunk_with_timestamp(x, z, timestamp, data);
+    }
+
+    /// Same as [RegionFileWriter::chunk], but with an explicit
+    /// timestamp in seconds since the Unix epoch.
+    ///
+    /// # Panics
+    ///
+    /// Same as [RegionFileWriter::chunk].
+    pub fn chunk_with_timestamp(&mut self, x: i32, z: i32, timestamp: u32, data: &[u8]) {
+        let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+        // Writing to a Vec can't fail.
+        encoder.write_all(data).unwrap();
+        let compressed = encoder.finish().unwrap();
+        let sectors = sector_count(compressed.len());
+        if sectors > MAX_CHUNK_SECTORS {
+            panic!(
+                "Chunk is {} bytes after compression, which doesn't fit in a region file",
+                compressed.len()
+            );
+        }
+        self.chunks[chunk_index(x, z)] = Some((timestamp, compressed));
+    }
+
+    /// Lays out the chunks and returns the encoded region file.
+    pub fn finish(self) -> Vec<u8> {
+        let mut data = vec![0; HEADER_SIZE];
+        for (index, chunk) in self.chunks.iter().enumerate() {
+            let (timestamp, compressed) = match chunk {
+                Some(chunk) => chunk,
+                None => continue,
+            };
+            let sector = data.len() / SECTOR_SIZE;
+            let sectors = sector_count(compressed.len());
+            let location = (sector as u32) << 8 | sectors as u32;
+            BigEndian::write_u32(&mut data[index * 4..], location);
+            BigEndian::write_u32(&mut data[SECTOR_SIZE + index * 4..], *timestamp);
+
+            // The length includes the compression scheme byte.
+            data.write_u32::<BigEndian>(compressed.len() as u32 + 1)
+                .unwrap();
+            data.push(2);
+            data.extend_from_slice(compressed);
+            data.resize((sector + sectors) * SECTOR_SIZE, 0);
+        }
+        data
+    }
+}
+
+impl Default for RegionFileWriter {
+    fn default() -> RegionFileWriter {
+        RegionFileWriter::new()
+    }
+}
+
+impl fmt::Debug for RegionFileWriter {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let count = self.chunks.iter().filter(|chunk| chunk.is_some()).count();
+        write!(fmt, "RegionFileWriter({} chunks)", count)
+    }
+}
+
+fn chunk_index(x: i32, z: i32) -> usize {
+    ((x & 31) + (z & 31) * 32) as usize
+}
+
+/// How many sectors a compressed chunk takes up, including its 5 byte
+/// length and compression scheme prefix.
+fn sector_count(compressed_len: usize) -> usize {
+    (compressed_len + 5).div_ceil(SECTOR_SIZE)
+}
@@ -0,0 +1,466 @@
+use crate::bin_decode::{Limits, OwnedCompound, OwnedList, OwnedTag};
+use std::fmt;
+
+/// Failures which can occur while parsing SNBT. Every variant carries
+/// the byte offset into the input where the problem was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SnbtError {
+    /// The input ended in the middle of a value.
+    UnexpectedEnd { offset: usize },
+    /// A character was found where it isn't allowed.
+    UnexpectedChar { found: char, offset: usize },
+    /// A value was expected, but there was nothing there, such as in
+    /// `{a:}`.
+    ExpectedValue { offset: usize },
+    /// A compound key was expected, but there was nothing there.
+    ExpectedKey { offset: usize },
+    /// A quoted string contained a backslash followed by something other
+    /// than a backslash or quote.
+    InvalidEscape { offset: usize },
+    /// A list contained elements of different types.
+    MixedList { offset: usize },
+    /// A typed array like `[B;...]` used a type other than B, I, or L.
+    UnknownArrayType { found: char, offset: usize },
+    /// An element of a typed array didn't match the array's type, such
+    /// as `[I;1b]`.
+    InvalidArrayElement { offset: usize },
+    /// Lists and compounds were nested deeper than
+    /// [Limits::max_depth] allows by default.
+    DepthExceeded { offset: usize },
+    /// There was more input after the end of the value.
+    TrailingData { offset: usize },
+}
+
+impl SnbtError {
+    /// Returns the byte offset into the input where the error happened.
+    pub fn offset(&self) -> usize {
+        match *self {
+            SnbtError::UnexpectedEnd { offset }
+            | SnbtError::UnexpectedChar { offset, .. }
+            | SnbtError::ExpectedValue { offset }
+            | SnbtError::ExpectedKey { offset }
+            | SnbtError::InvalidEscape { offset }
+            | SnbtError::MixedList { offset }
+            | SnbtError::UnknownArrayType { offset, .. }
+            | SnbtError::InvalidArrayElement { offset }
+            | SnbtError::DepthExceeded { offset }
+            | SnbtError::TrailingData { offset } => offset,
+        }
+    }
+}
+
+impl fmt::Display for SnbtError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SnbtError::UnexpectedEnd { .. } => write!(fmt, "Unexpected end of input"),
+            SnbtError::UnexpectedChar { found, .. } => write!(fmt, "Unexpected {:?}", found),
+            SnbtError::ExpectedValue { .. } => write!(fmt, "Expected value"),
+            SnbtError::ExpectedKey { .. } => write!(fmt, "Expected key"),
+            SnbtError::InvalidEscape { .. } => write!(fmt, "Invalid escape sequence"),
+            SnbtError::MixedList { .. } => {
+                write!(fmt, "List elements must all have the same type")
+            }
+            SnbtError::UnknownArrayType { found, .. } => {
+                write!(fmt, "Unknown array type {:?}", found)
+            }
+            SnbtError::InvalidArrayElement { .. } => {
+                write!(fmt, "Array element does not match the array type")
+            }
+            SnbtError::DepthExceeded { .. } => write!(fmt, "Maximum nesting depth exceeded"),
+            SnbtError::TrailingData { .. } => write!(fmt, "Unexpected data after value"),
+        }?;
+        write!(fmt, " at offset {}", self.offset())
+    }
+}
+
+impl std::error::Error for SnbtError {}
+
+/// Parses SNBT, the text format used by Minecraft commands, such as
+/// `{name:"hi",n:3b,arr:[I;1,2,3]}`.
+///
+/// - Strings can be quoted with either `"` or `'`, or left unquoted if
+///   they only contain `0-9`, `A-Z`, `a-z`, `_`, `-`, `.`, and `+`.
+/// - Numbers can have a suffix of `b`, `s`, `l`, `f`, or `d` to pick
+///   their type, in either lowercase or uppercase. Without a suffix,
+///   numbers need a decimal point to be doubles, which can be followed
+///   by an exponent as in `1.5e3`. Other numbers are ints, except that
+///   `1e3` is a string, since it has an exponent but no decimal point.
+///   Numbers that are out of range for their type are treated as
+///   strings, as Minecraft does.
+/// - Unquoted `true` and `false` are bytes of 1 and 0, ignoring case.
+/// - Typed arrays are written as `[B;...]`, `[I;...]`, and `[L;...]`,
+///   and their elements must be of the matching type.
+/// - Empty lists become [OwnedList::End].
+///
+/// Whitespace is allowed between tokens. Any value can be at the top
+/// level, not just a compound.
+///
+/// # Example
+///
+/// ```rust
+/// use nobility::bin_decode::OwnedTag;
+/// use nobility::snbt;
+///
+/// let tag = snbt::parse("{name: \"Bananrama\", count: 3b}").unwrap();
+/// if let OwnedTag::Compound(root) = tag {
+///     assert_eq!(root.get("count"), Some(&OwnedTag::Byte(3)));
+/// }
+/// ```
+pub fn parse(input: &str) -> Result<OwnedTag, SnbtError> {
+    let mut parser = Parser {
+        input,
+        position: 0,
+        depth: 0,
+        max_depth: Limits::default().max_depth,
+    };
+    let value = parser.read_value()?;
+    parser.skip_whitespace();
+    if parser.position < input.len() {
+        return Err(SnbtError::TrailingData {
+            offset: parser.position,
+        });
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+    depth: usize,
+    max_depth: usize,
+}
+
+fn is_unquoted_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.' || c == '+'
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            self.position += c.len_utf8();
+        }
+    }
+
+    /// Skips whitespace and returns the next character without consuming
+    /// it.
+    fn peek_token(&mut self) -> Result<char, SnbtError> {
+        self.skip_whitespace();
+        self.peek().ok_or(SnbtError::UnexpectedEnd {
+            offset: self.position,
+        })
+    }
+
+    /// Skips whitespace and then consumes `expected`.
+    fn expect(&mut self, expected: char) -> Result<(), SnbtError> {
+        let found = self.peek_token()?;
+        if found != expected {
+            return Err(SnbtError::UnexpectedChar {
+                found,
+                offset: self.position,
+            });
+        }
+        self.position += expected.len_utf8();
+        Ok(())
+    }
+
+    fn enter(&mut self) -> Result<(), SnbtError> {
+        if self.depth >= self.max_depth {
+            return Err(SnbtError::DepthExceeded {
+                offset: self.position,
+            });
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn read_value(&mut self) -> Result<OwnedTag, SnbtError> {
+        match self.peek_token()? {
+            '{' => {
+                self.enter()?;
+                let result = self.read_compound().map(OwnedTag::Compound);
+                self.depth -= 1;
+                result
+            }
+            '[' => {
+                self.enter()?;
+                let result = self.read_list_or_array();
+                self.depth -= 1;
+                result
+            }
+            '"' | '\'' => Ok(OwnedTag::String(self.read_quoted()?)),
+            _ => {
+                let offset = self.position;
+                let token = self.read_unquoted();
+                if token.is_empty() {
+                    return Err(SnbtError::ExpectedValue { offset });
+                }
+                Ok(parse_unquoted(token))
+            }
+        }
+    }
+
+    fn read_unquoted(&mut self) -> &'a str {
+        let start = self.position;
+        while let Some(c) = self.peek() {
+            if !is_unquoted_char(c) {
+                break;
+            }
+            self.position += c.len_utf8();
+        }
+        &self.input[start..self.position]
+    }
+
+    /// Reads a string surrounded by either `"` or `'`. The opening quote
+    /// should be the next character.
+    fn read_quoted(&mut self) -> Result<String, SnbtError> {
+        let quote = self.peek_token()?;
+        self.position += 1;
+        let mut result = String::new();
+        let mut chars = self.input[self.position..].char_indices();
+        while let Some((index, c)) = chars.next() {
+            if c == quote {
+                self.position += index + 1;
+                return Ok(result);
+            }
+            if c == '\\' {
+                match chars.next() {
+                    Some((_, escaped)) if escaped == '\\' || escaped == quote => {
+                        result.push(escaped)
+                    }
+                    Some(_) => {
+                        return Err(SnbtError::InvalidEscape {
+                            offset: self.position + index,
+                        })
+                    }
+                    None => break,
+                }
+            } else {
+                result.push(c);
+            }
+        }
+        Err(SnbtError::UnexpectedEnd {
+            offset: self.input.len(),
+        })
+    }
+
+    fn read_key(&mut self) -> Result<String, SnbtError> {
+        match self.peek_token()? {
+            '"' | '\'' => self.read_quoted(),
+            _ => {
+                let offset = self.position;
+                let key = self.read_unquoted();
+                if key.is_empty() {
+                    return Err(SnbtError::ExpectedKey { offset });
+                }
+                Ok(key.to_owned())
+            }
+        }
+    }
+
+    /// Calls `func` for each element of a comma separated sequence,
+    /// until `end` is reached. The opening bracket should already have
+    /// been consumed.
+    fn read_sequence<F>(&mut self, end: char, mut func: F) -> Result<(), SnbtError>
+    where
+        F: FnMut(&mut Self) -> Result<(), SnbtError>,
+    {
+        if self.peek_token()? == end {
+            self.position += 1;
+            return Ok(());
+        }
+        loop {
+            func(self)?;
+            let found = self.peek_token()?;
+            self.position += found.len_utf8();
+            if found == end {
+                return Ok(());
+            } else if found != ',' {
+                return Err(SnbtError::UnexpectedChar {
+                    found,
+                    offset: self.position - found.len_utf8(),
+                });
+            }
+        }
+    }
+
+    fn read_compound(&mut self) -> Result<OwnedCompound, SnbtError> {
+        self.expect('{')?;
+        let mut compound = OwnedCompound::new();
+        self.read_sequence('}', |parser| {
+            let key = parser.read_key()?;
+            parser.expect(':')?;
+            let value = parser.read_value()?;
+            compound.push(key, value);
+            Ok(())
+        })?;
+        Ok(compound)
+    }
+
+    fn read_list_or_array(&mut self) -> Result<OwnedTag, SnbtError> {
+        self.expect('[')?;
+        // Typed arrays are a single letter followed by a semicolon.
+        let rest = &self.input[self.position..];
+        let mut chars = rest.chars();
+        if let (Some(kind), Some(';')) = (chars.next(), chars.next()) {
+            let offset = self.position;
+            self.position += kind.len_utf8() + 1;
+            return match kind {
+                'B' => self
+                    .read_array(|tag| match tag {
+                        OwnedTag::Byte(value) => Some(value as u8),
+                        _ => None,
+                    })
+                    .map(OwnedTag::ByteArray),
+                'I' => self
+                    .read_array(|tag| match tag {
+                        OwnedTag::Int(value) => Some(value),
+                        _ => None,
+                    })
+                    .map(OwnedTag::IntArray),
+                'L' => self
+                    .read_array(|tag| match tag {
+                        OwnedTag::Long(value) => Some(value),
+                        _ => None,
+                    })
+                    .map(OwnedTag::LongArray),
+                found => Err(SnbtError::UnknownArrayType { found, offset }),
+            };
+        }
+
+        let mut elements = vec![];
+        self.read_sequence(']', |parser| {
+            parser.skip_whitespace();
+            let offset = parser.position;
+            elements.push((offset, parser.read_value()?));
+            Ok(())
+        })?;
+        make_list(elements).map(OwnedTag::List)
+    }
+
+    fn read_array<T, F>(&mut self, convert: F) -> Result<Vec<T>, SnbtError>
+    where
+        F: Fn(OwnedTag) -> Option<T>,
+    {
+        let mut elements = vec![];
+        self.read_sequence(']', |parser| {
+            parser.skip_whitespace();
+            let offset = parser.position;
+            let value =
+                convert(parser.read_value()?).ok_or(SnbtError::InvalidArrayElement { offset })?;
+            elements.push(value);
+            Ok(())
+        })?;
+        Ok(elements)
+    }
+}
+
+/// Converts a list of parsed values into an [OwnedList], checking that
+/// they all have the same type.
+fn make_list(elements: Vec<(usize, OwnedTag)>) -> Result<OwnedList, SnbtError> {
+    macro_rules! collect {
+        ($variant:ident) => {
+            elements
+                .into_iter()
+                .map(|(offset, tag)| match tag {
+                    OwnedTag::$variant(value) => Ok(value),
+                    _ => Err(SnbtError::MixedList { offset }),
+                })
+                .collect::<Result<_, _>>()
+                .map(OwnedList::$variant)
+        };
+    }
+
+    match elements.first() {
+        None => Ok(OwnedList::End),
+        Some((_, OwnedTag::Byte(_))) => collect!(Byte),
+        Some((_, OwnedTag::Short(_))) => collect!(Short),
+        Some((_, OwnedTag::Int(_))) => collect!(Int),
+        Some((_, OwnedTag::Long(_))) => collect!(Long),
+        Some((_, OwnedTag::Float(_))) => collect!(Float),
+        Some((_, OwnedTag::Double(_))) => collect!(Double),
+        Some((_, OwnedTag::ByteArray(_))) => collect!(ByteArray),
+        Some((_, OwnedTag::String(_))) => collect!(String),
+        Some((_, OwnedTag::IntArray(_))) => collect!(IntArray),
+        Some((_, OwnedTag::LongArray(_))) => collect!(LongArray),
+        Some((_, OwnedTag::List(_))) => collect!(List),
+        Some((_, OwnedTag::Compound(_))) => collect!(Compound),
+    }
+}
+
+/// Checks for an optional sign followed by either `0` or a number with
+/// no leading zeroes.
+fn is_integer(text: &str) -> bool {
+    let digits = text.strip_prefix(&['-', '+'][..]).unwrap_or(text);
+    match digits.as_bytes() {
+        [b'0'] => true,
+        [b'1'..=b'9', rest @ ..] => rest.iter().all(u8::is_ascii_digit),
+        _ => false,
+    }
+}
+
+/// Checks for an optional sign, a decimal number, and an optional
+/// exponent. Unless `allow_integer` is set, the number must contain a
+/// decimal point.
+fn is_decimal(text: &str, allow_integer: bool) -> bool {
+    let text = text.strip_prefix(&['-', '+'][..]).unwrap_or(text);
+    let (mantissa, exponent) = match text.find(&['e', 'E'][..]) {
+        Some(index) => (&text[..index], Some(&text[index + 1..])),
+        None => (text, None),
+    };
+    let (whole, fraction) = match mantissa.find('.') {
+        Some(index) => (&mantissa[..index], Some(&mantissa[index + 1..])),
+        None => (mantissa, None),
+    };
+    let all_digits = |s: &str| s.bytes().all(|c| c.is_ascii_digit());
+    let valid_mantissa = match fraction {
+        Some(fraction) => {
+            all_digits(whole) && all_digits(fraction) && whole.len() + fraction.len() > 0
+        }
+        None => allow_integer && !whole.is_empty() && all_digits(whole),
+    };
+    let valid_exponent = match exponent {
+        Some(exponent) => {
+            let exponent = exponent.strip_prefix(&['-', '+'][..]).unwrap_or(exponent);
+            !exponent.is_empty() && all_digits(exponent)
+        }
+        None => true,
+    };
+    valid_mantissa && valid_exponent
+}
+
+/// Interprets an unquoted token as a boolean or number if it looks like
+/// one, and as a string otherwise.
+fn parse_unquoted(token: &str) -> OwnedTag {
+    if token.eq_ignore_ascii_case("true") {
+        OwnedTag::Byte(1)
+    } else if token.eq_ignore_ascii_case("false") {
+        OwnedTag::Byte(0)
+    } else {
+        parse_number(token).unwrap_or_else(|| OwnedTag::String(token.to_owned()))
+    }
+}
+
+fn parse_number(token: &str) -> Option<OwnedTag> {
+    let (body, suffix) = match token.char_indices().last() {
+        Some((index, c)) if c.is_ascii_alphabetic() => (&token[..index], Some(c)),
+        _ => (token, None),
+    };
+    match suffix.map(|c| c.to_ascii_lowercase()) {
+        Some('b') if is_integer(body) => body.parse().ok().map(OwnedTag::Byte),
+        Some('s') if is_integer(body) => body.parse().ok().map(OwnedTag::Short),
+        Some('l') if is_integer(body) => body.parse().ok().map(OwnedTag::Long),
+        Some('f') if is_decimal(body, true) => body.parse().ok().map(OwnedTag::Float),
+        Some('d') if is_decimal(body, true) => body.parse().ok().map(OwnedTag::Double),
+        _ if is_integer(token) => token.parse().ok().map(OwnedTag::Int),
+        _ if is_decimal(token, false) => token.parse().ok().map(OwnedTag::Double),
+        _ => None,
+    }
+}
@@ -1,4 +1,11 @@
-use nobility::bin_decode::Document;
+#![cfg(feature = "std")]
+
+use nobility::bin_decode::{
+    Document, Event, Limits, List, NbtError, NbtString, ParseError, ParseOptions, RequireError,
+    Tag, TrailingPolicy, Visit, Visitor,
+};
+use nobility::bin_encode::NbtWriter;
+use nobility::TagType;
 
 #[test]
 pub fn decode_hello_world() {
@@ -27,3 +34,1379 @@ pub fn decode_bigtest() {
     assert_eq!(name, "Level");
     assert_eq!(root.len(), 11);
 }
+
+#[test]
+pub fn decode_compound_get() {
+    let data = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(data)).unwrap();
+    let (_name, root) = document.parse().expect("Parsing to succeed");
+
+    assert_eq!(
+        root.get("shortTest").and_then(|tag| tag.to_i64()),
+        Some(32767)
+    );
+    assert_eq!(root.get("missing"), None);
+}
+
+#[test]
+pub fn decode_compound_typed_getters() {
+    let data = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(data)).unwrap();
+    let (_name, root) = document.parse().expect("Parsing to succeed");
+
+    assert_eq!(root.get_i64("longTest"), Some(9223372036854775807));
+    assert_eq!(root.get_i64("shortTest"), Some(32767));
+    assert_eq!(root.get_i64("doubleTest"), None);
+    assert_eq!(root.get_f64("floatTest"), Some(0.4982314705848694));
+    assert_eq!(
+        root.get_list("listTest (long)").map(|list| list.len()),
+        Some(5)
+    );
+
+    let nested = root.get_compound("nested compound test").unwrap();
+    let ham = nested.get_compound("ham").unwrap();
+    assert_eq!(ham.get_string("name").unwrap(), "Hampus");
+    assert_eq!(ham.get_f64("value"), Some(0.75));
+    assert_eq!(nested.get_string("ham"), None);
+}
+
+#[test]
+pub fn decode_into_iterator() {
+    let data = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(data)).unwrap();
+    let (_name, root) = document.parse().expect("Parsing to succeed");
+
+    let mut count = 0;
+    for entry in &root {
+        assert!(root.get(&entry.name().decode().unwrap()).is_some());
+        count += 1;
+    }
+    assert_eq!(count, 11);
+
+    let list = root.get_list("listTest (long)").unwrap();
+    let mut values = vec![];
+    for tag in list {
+        values.push(tag.to_i64().unwrap());
+    }
+    assert_eq!(values, vec![11, 12, 13, 14, 15]);
+
+    if let List::Long(array) = *list {
+        let values: Vec<i64> = array.into_iter().collect();
+        assert_eq!(values, vec![11, 12, 13, 14, 15]);
+    } else {
+        panic!("Expected a long list");
+    }
+}
+
+#[test]
+pub fn decode_list_element_type() {
+    let data = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(data)).unwrap();
+    let (_name, root) = document.parse().expect("Parsing to succeed");
+
+    let list = root.get_list("listTest (long)").unwrap();
+    assert_eq!(list.element_type(), TagType::Long);
+    let list = root.get_list("listTest (compound)").unwrap();
+    assert_eq!(list.element_type(), TagType::Compound);
+
+    // A TAG_List of 2 TAG_Int.
+    let data = vec![
+        10, 0, 0, 9, 0, 1, b'a', 3, 0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0, 2, 0,
+    ];
+    let document = Document::load(std::io::Cursor::new(data)).unwrap();
+    let (_name, root) = document.parse().expect("Parsing to succeed");
+    let list = root.get_list("a").unwrap();
+    assert_eq!(list.element_type(), TagType::Int);
+}
+
+#[test]
+pub fn decode_list_from_empty() {
+    let types = [
+        TagType::End,
+        TagType::Byte,
+        TagType::Short,
+        TagType::Int,
+        TagType::Long,
+        TagType::Float,
+        TagType::Double,
+        TagType::ByteArray,
+        TagType::String,
+        TagType::List,
+        TagType::Compound,
+        TagType::IntArray,
+        TagType::LongArray,
+    ];
+    for &tag in &types {
+        let list = List::from_empty(tag);
+        assert!(list.is_empty());
+        assert_eq!(list.element_type(), tag);
+    }
+}
+
+#[test]
+pub fn decode_list_typed_accessors() {
+    let data = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(data)).unwrap();
+    let (_name, root) = document.parse().expect("Parsing to succeed");
+
+    let list = root.get_list("listTest (long)").unwrap();
+    assert_eq!(
+        list.as_long_list().unwrap().to_vec(),
+        vec![11, 12, 13, 14, 15]
+    );
+    assert!(list.as_int_list().is_none());
+    assert!(list.as_compound_list().is_none());
+
+    let list = root.get_list("listTest (compound)").unwrap();
+    let compounds = list.as_compound_list().unwrap();
+    assert_eq!(compounds.len(), 2);
+    assert_eq!(compounds[1].get_string("name").unwrap(), "Compound tag #1");
+    assert!(list.as_string_list().is_none());
+}
+
+#[test]
+pub fn decode_compound_path() {
+    let data = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(data)).unwrap();
+    let (_name, root) = document.parse().expect("Parsing to succeed");
+
+    let name = root.path("nested compound test/ham/name").unwrap();
+    assert_eq!(name.as_string().unwrap(), "Hampus");
+    let name = root.path("listTest (compound)/1/name").unwrap();
+    assert_eq!(name.as_string().unwrap(), "Compound tag #1");
+    let element = root.path("listTest (long)/2").unwrap();
+    assert_eq!(*element, Tag::Long(13));
+    let nested = root.path("nested compound test/egg").unwrap();
+    assert_eq!(nested.as_compound().unwrap().len(), 2);
+
+    assert_eq!(root.path("nested compound test/bacon/name"), None);
+    assert_eq!(root.path("listTest (long)/5"), None);
+    assert_eq!(root.path("listTest (long)/foo"), None);
+    assert_eq!(root.path("intTest/0"), None);
+}
+
+#[test]
+pub fn decode_compound_require() {
+    let data = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(data)).unwrap();
+    let (_name, root) = document.parse().expect("Parsing to succeed");
+
+    let values = root
+        .require(&[("intTest", TagType::Int), ("byteTest", TagType::Byte)])
+        .unwrap();
+    assert_eq!(values, vec![&Tag::Int(2147483647), &Tag::Byte(127)]);
+
+    let errors = root
+        .require(&[
+            ("intTest", TagType::Int),
+            ("missing", TagType::Int),
+            ("byteTest", TagType::String),
+        ])
+        .unwrap_err();
+    assert_eq!(
+        errors,
+        vec![
+            RequireError::Missing {
+                key: "missing".to_owned()
+            },
+            RequireError::WrongType {
+                key: "byteTest".to_owned(),
+                expected: TagType::String,
+                found: TagType::Byte,
+            },
+        ]
+    );
+}
+
+#[test]
+pub fn decode_compound_to_hashmap() {
+    let data = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(data)).unwrap();
+    let (_name, root) = document.parse().expect("Parsing to succeed");
+
+    let map = root.to_hashmap().unwrap();
+    assert_eq!(map.len(), 11);
+    assert_eq!(map["intTest"], &Tag::Int(2147483647));
+    assert_eq!(map["byteTest"], &Tag::Byte(127));
+
+    // First entry wins on duplicate keys.
+    let data = vec![
+        10, 0, 0, 1, 0, 3, b'd', b'u', b'p', 1, 1, 0, 3, b'd', b'u', b'p', 2, 0,
+    ];
+    let document = Document::load(std::io::Cursor::new(data)).unwrap();
+    let (_name, root) = document.parse().expect("Parsing to succeed");
+    let map = root.to_hashmap().unwrap();
+    assert_eq!(map.len(), 1);
+    assert_eq!(map["dup"], &Tag::Byte(1));
+}
+
+#[test]
+pub fn decode_compound_to_pairs() {
+    let data = include_bytes!("../files/hello_world.nbt");
+    let document = Document::load(std::io::Cursor::new(data)).unwrap();
+    let (_name, root) = document.parse().expect("Parsing to succeed");
+
+    let pairs = root.to_pairs().unwrap();
+    assert_eq!(pairs.len(), 1);
+    assert_eq!(pairs[0].0, "name");
+    assert_eq!(pairs[0].1.as_string().unwrap(), "Bananrama");
+
+    // Invalid CESU-8 in a key.
+    let data = vec![10, 0, 0, 1, 0, 1, 0xff, 1, 0];
+    let document = Document::load(std::io::Cursor::new(data)).unwrap();
+    let (_name, root) = document.parse().expect("Parsing to succeed");
+    assert!(root.to_pairs().is_err());
+}
+
+#[test]
+pub fn decode_compound_find_all() {
+    let data = vec![
+        10, 0, 0, 1, 0, 3, b'd', b'u', b'p', 1, 1, 0, 1, b'x', 3, 1, 0, 3, b'd', b'u', b'p', 2, 0,
+    ];
+    let document = Document::load(std::io::Cursor::new(data)).unwrap();
+    let (_name, root) = document.parse().expect("Parsing to succeed");
+
+    let values: Vec<_> = root.find_all("dup").map(|entry| entry.value()).collect();
+    assert_eq!(values, vec![&Tag::Byte(1), &Tag::Byte(2)]);
+    assert_eq!(root.find_all("x").count(), 1);
+    assert_eq!(root.find_all("missing").count(), 0);
+}
+
+/// Creates a document containing a single field, which is a list nested
+/// `depth` times.
+fn nested_lists(depth: usize) -> Vec<u8> {
+    // Root compound and field header, both with empty names.
+    let mut data = vec![10, 0, 0, 9, 0, 0];
+    for _ in 1..depth {
+        // A TAG_List of TAG_List, with 1 element.
+        data.extend(&[9, 0, 0, 0, 1]);
+    }
+    // Innermost list is empty.
+    data.extend(&[0, 0, 0, 0, 0]);
+    // End of the root compound.
+    data.push(0);
+    data
+}
+
+#[test]
+pub fn decode_depth_exceeded() {
+    let document = Document::load(std::io::Cursor::new(nested_lists(100_000))).unwrap();
+    let result = document.parse();
+    assert!(matches!(result, Err(ParseError::DepthExceeded { .. })));
+}
+
+#[test]
+pub fn decode_depth_limits() {
+    let document = Document::load(std::io::Cursor::new(nested_lists(10))).unwrap();
+    // The root compound is also counted.
+    let limits = Limits {
+        max_depth: 11,
+        ..Limits::default()
+    };
+    assert!(document.parse_with_limits(limits).is_ok());
+    let limits = Limits {
+        max_depth: 10,
+        ..Limits::default()
+    };
+    let result = document.parse_with_limits(limits);
+    assert!(matches!(result, Err(ParseError::DepthExceeded { .. })));
+}
+
+#[test]
+pub fn decode_string_limit() {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.field("short").string("abcd");
+    root.field("long").string("abcdefghijklmnopqrstuvwxyz");
+    root.finish();
+    let document = Document::load(std::io::Cursor::new(writer.finish())).unwrap();
+
+    let limits = Limits {
+        max_string_len: 26,
+        ..Limits::default()
+    };
+    assert!(document.parse_with_limits(limits).is_ok());
+    let limits = Limits {
+        max_string_len: 8,
+        ..Limits::default()
+    };
+    let result = document.parse_with_limits(limits);
+    assert!(matches!(
+        result,
+        Err(ParseError::StringTooLong {
+            length: 26,
+            offset: 24
+        })
+    ));
+}
+
+#[test]
+pub fn decode_huge_length() {
+    // A TAG_List of TAG_Compound claiming to have 4 billion elements.
+    let data = vec![10, 0, 0, 9, 0, 0, 10, 0xFF, 0xFF, 0xFF, 0xFF, 0, 0];
+    let document = Document::load(std::io::Cursor::new(data)).unwrap();
+    assert!(matches!(document.parse(), Err(ParseError::EOF { .. })));
+
+    // A TAG_Long_Array claiming to have 4 billion elements.
+    let data = vec![10, 0, 0, 12, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0, 0];
+    let document = Document::load(std::io::Cursor::new(data)).unwrap();
+    assert!(matches!(document.parse(), Err(ParseError::EOF { .. })));
+}
+
+#[test]
+pub fn decode_trailing_policy() {
+    let hello_world = include_bytes!("../files/hello_world.nbt");
+
+    let mut padded = hello_world.to_vec();
+    padded.extend(&[0; 16]);
+    let document = Document::load(std::io::Cursor::new(padded)).unwrap();
+    assert!(document
+        .parse_strict(TrailingPolicy::AllowZeroPadding)
+        .is_ok());
+    assert!(matches!(
+        document.parse_strict(TrailingPolicy::Forbid),
+        Err(ParseError::TrailingData { offset }) if offset == hello_world.len()
+    ));
+
+    let mut garbage = hello_world.to_vec();
+    garbage.extend(&[0, 0, 1, 0]);
+    let document = Document::load(std::io::Cursor::new(garbage)).unwrap();
+    assert!(matches!(
+        document.parse_strict(TrailingPolicy::AllowZeroPadding),
+        Err(ParseError::TrailingData { .. })
+    ));
+}
+
+/// Decodes an unsigned LEB128 VarInt.
+fn read_varint_length(data: &[u8]) -> Option<(usize, usize)> {
+    let mut value = 0;
+    for (i, byte) in data.iter().enumerate().take(5) {
+        value |= ((byte & 0x7F) as usize) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+#[test]
+pub fn decode_custom_array_length() {
+    // Root compound containing a TAG_Byte_Array named "a" with 300
+    // elements, whose length is encoded as a VarInt.
+    let mut data = vec![10, 0, 0, 7, 0, 1, b'a', 0xAC, 0x02];
+    data.extend((0..300).map(|i| i as u8));
+    data.push(0);
+
+    let document = Document::load(std::io::Cursor::new(data)).unwrap();
+    let options = ParseOptions {
+        array_length: read_varint_length,
+        ..ParseOptions::default()
+    };
+    let (_name, root) = document.parse_with_options(options).unwrap();
+    let array = root.get("a").and_then(Tag::as_byte_array).unwrap();
+    assert_eq!(array.len(), 300);
+    assert_eq!(array[299], 43);
+
+    // The default u32 length reader can't parse it.
+    assert!(document.parse().is_err());
+}
+
+#[test]
+pub fn decode_preview_root() {
+    let data = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(&data[..])).unwrap();
+    let (_name, root) = document.parse().unwrap();
+
+    let preview = document.preview_root(3).unwrap();
+    assert_eq!(preview.len(), 3);
+    assert!(preview[..] == root.clone().into_vec()[..3]);
+
+    let preview = document.preview_root(100).unwrap();
+    assert_eq!(preview.len(), root.len());
+}
+
+#[test]
+pub fn decode_byte_array_bit() {
+    let tag = Tag::ByteArray(&[0b0000_0101, 0b1000_0000]);
+    assert_eq!(tag.byte_array_bit(0), Some(true));
+    assert_eq!(tag.byte_array_bit(1), Some(false));
+    assert_eq!(tag.byte_array_bit(2), Some(true));
+    assert_eq!(tag.byte_array_bit(8), Some(false));
+    assert_eq!(tag.byte_array_bit(15), Some(true));
+    assert_eq!(tag.byte_array_bit(16), None);
+    assert_eq!(Tag::Int(5).byte_array_bit(0), None);
+}
+
+#[test]
+pub fn decode_split_all() {
+    let mut data = vec![];
+    for name in &["first", "second", "third"] {
+        let mut writer = NbtWriter::new();
+        let mut root = writer.root(name);
+        root.field("name").string(name);
+        root.finish();
+        data.extend(writer.finish());
+    }
+
+    let documents = Document::split_all(&data).unwrap();
+    assert_eq!(documents.len(), 3);
+    assert_eq!(documents[1].0, "second");
+    assert_eq!(documents[2].1.get_string("name").unwrap(), "third");
+
+    assert!(Document::split_all(&[]).unwrap().is_empty());
+    assert!(matches!(
+        Document::split_all(&data[..data.len() - 1]),
+        Err(ParseError::EOF { .. })
+    ));
+}
+
+#[test]
+pub fn decode_events() {
+    let data = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(&data[..])).unwrap();
+
+    let mut compounds = 0;
+    let mut depth = 0;
+    for event in document.events() {
+        match event.unwrap() {
+            Event::StartCompound { .. } => {
+                compounds += 1;
+                depth += 1;
+            }
+            Event::StartList { .. } => depth += 1,
+            Event::End => depth -= 1,
+            Event::Primitive { .. } => (),
+            _ => unreachable!(),
+        }
+    }
+    // The root, "nested compound test", "ham", "egg", and the two
+    // elements of "listTest (compound)".
+    assert_eq!(compounds, 6);
+    assert_eq!(depth, 0);
+}
+
+#[test]
+pub fn decode_events_skip() {
+    let data = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(&data[..])).unwrap();
+
+    let mut events = document.events();
+    let mut compounds = 0;
+    let mut names = vec![];
+    while let Some(event) = events.next() {
+        match event.unwrap() {
+            Event::StartCompound { name } => {
+                compounds += 1;
+                if events.depth() > 1 {
+                    names.push(name.unwrap().decode().unwrap().into_owned());
+                    events.skip_subtree().unwrap();
+                }
+            }
+            Event::StartList { name, .. } => {
+                names.push(name.unwrap().decode().unwrap().into_owned());
+                events.skip_subtree().unwrap();
+            }
+            _ => (),
+        }
+    }
+    assert_eq!(compounds, 2);
+    assert_eq!(names.len(), 3);
+    assert!(names.contains(&"nested compound test".to_owned()));
+}
+
+#[derive(Default)]
+struct CountingVisitor {
+    compounds: usize,
+    ends: usize,
+    longs: Vec<i64>,
+}
+
+impl<'a> Visitor<'a> for CountingVisitor {
+    fn visit_compound_start(&mut self, name: Option<NbtString<'a>>) -> Visit {
+        self.compounds += 1;
+        if name.is_some_and(|name| name == "nested compound test") {
+            Visit::Skip
+        } else {
+            Visit::Enter
+        }
+    }
+
+    fn visit_compound_end(&mut self) {
+        self.ends += 1;
+    }
+
+    fn visit_long(&mut self, _name: Option<NbtString<'a>>, value: i64) {
+        self.longs.push(value);
+    }
+}
+
+#[test]
+pub fn decode_visitor() {
+    let data = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(&data[..])).unwrap();
+
+    let mut visitor = CountingVisitor::default();
+    document.visit(&mut visitor).unwrap();
+    // "ham" and "egg" are skipped as part of "nested compound test".
+    assert_eq!(visitor.compounds, 4);
+    assert_eq!(visitor.ends, 3);
+    assert_eq!(
+        visitor.longs,
+        vec![
+            9223372036854775807,
+            11,
+            12,
+            13,
+            14,
+            15,
+            1264099775885,
+            1264099775885
+        ]
+    );
+}
+
+#[test]
+pub fn decode_max_declared_length() {
+    let data = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(&data[..])).unwrap();
+    // The byte array test has 1000 elements.
+    assert_eq!(document.max_declared_length().unwrap(), 1000);
+
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.field("small").int_list(&[1, 2, 3]);
+    let mut field = root.field("nested");
+    let mut nested = field.compound();
+    nested.field("large").byte_list(&[0; 100_000]);
+    nested.finish();
+    root.finish();
+    let document = Document::load(std::io::Cursor::new(writer.finish())).unwrap();
+    assert_eq!(document.max_declared_length().unwrap(), 100_000);
+
+    let document = Document::load(std::io::Cursor::new(vec![10, 0, 0, 0])).unwrap();
+    assert_eq!(document.max_declared_length().unwrap(), 0);
+}
+
+#[test]
+pub fn decode_estimate_compressibility() {
+    let repetitive = Document::load(std::io::Cursor::new(vec![b'a'; 4096])).unwrap();
+    assert_eq!(repetitive.estimate_compressibility(), 0.0);
+
+    // Simple LCG so the test doesn't need a random number generator.
+    let mut state: u32 = 12345;
+    let random = (0..4096)
+        .map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (state >> 16) as u8
+        })
+        .collect::<Vec<u8>>();
+    let random = Document::load(std::io::Cursor::new(random)).unwrap();
+    let score = random.estimate_compressibility();
+    assert!(score > 0.9 && score <= 1.0, "{}", score);
+
+    let data = include_bytes!("../files/bigtest.nbt");
+    let bigtest = Document::load(std::io::Cursor::new(&data[..])).unwrap();
+    let score = bigtest.estimate_compressibility();
+    assert!(score > 0.0 && score < random.estimate_compressibility());
+}
+
+#[test]
+pub fn decode_array_copy_into() {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.field("ints").int_array(&[1, 2, 3, 4, 5]);
+    root.finish();
+    let document = Document::load(std::io::Cursor::new(writer.finish())).unwrap();
+    let (_name, root) = document.parse().unwrap();
+    let array = match root.get("ints") {
+        Some(Tag::IntArray(array)) => *array,
+        other => panic!("expected int array, got {:?}", other),
+    };
+
+    let mut large = [0; 8];
+    assert_eq!(array.copy_into(&mut large), 5);
+    assert_eq!(large, [1, 2, 3, 4, 5, 0, 0, 0]);
+
+    let mut small = [0; 3];
+    assert_eq!(array.copy_into(&mut small), 3);
+    assert_eq!(small, [1, 2, 3]);
+}
+
+#[test]
+pub fn decode_array_to_vec_fast() {
+    let data = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(&data[..])).unwrap();
+    let (_name, root) = document.parse().unwrap();
+    let list = root.get_list("listTest (long)").unwrap();
+    let array = list.as_long_list().unwrap();
+    assert_eq!(array.to_vec_fast(), array.to_vec());
+
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.field("ints")
+        .int_array(&(-500..500).collect::<Vec<i32>>());
+    root.finish();
+    let document = Document::load(std::io::Cursor::new(writer.finish())).unwrap();
+    let (_name, root) = document.parse().unwrap();
+    match root.get("ints") {
+        Some(Tag::IntArray(array)) => {
+            assert_eq!(array.to_vec_fast(), (-500..500).collect::<Vec<i32>>())
+        }
+        other => panic!("expected int array, got {:?}", other),
+    }
+}
+
+#[test]
+pub fn decode_parse_expecting_root() {
+    let data = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(&data[..])).unwrap();
+
+    let root = document.parse_expecting_root("Level").unwrap();
+    assert_eq!(root.get("intTest"), Some(&Tag::Int(2147483647)));
+
+    match document.parse_expecting_root("Wrong") {
+        Err(ParseError::UnexpectedRootName { expected, found }) => {
+            assert_eq!(&*expected, "Wrong");
+            assert_eq!(&*found, "Level");
+        }
+        other => panic!("expected UnexpectedRootName, got {:?}", other),
+    }
+}
+
+#[test]
+pub fn decode_root_entries() {
+    let data = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(&data[..])).unwrap();
+    let (_name, root) = document.parse().unwrap();
+
+    let mut count = 0;
+    for (lazy, eager) in document.root_entries().zip(root.iter()) {
+        assert!(lazy.unwrap() == *eager);
+        count += 1;
+    }
+    assert_eq!(count, 11);
+    assert_eq!(document.root_entries().count(), 11);
+
+    // Truncated documents produce an error as the last item.
+    let data = include_bytes!("../files/hello_world.nbt");
+    let document = Document::load(std::io::Cursor::new(&data[..20])).unwrap();
+    let results: Vec<_> = document.root_entries().collect();
+    assert!(matches!(results.last(), Some(Err(ParseError::EOF { .. }))));
+}
+
+#[test]
+pub fn decode_gzip_header_fields() {
+    use flate2::write::GzEncoder;
+    use flate2::{Compression, GzBuilder};
+    use std::io::Write;
+
+    let data = include_bytes!("../files/hello_world.nbt");
+
+    let mut plain = GzEncoder::new(vec![], Compression::default());
+    plain.write_all(data).unwrap();
+    let mut with_fields = GzBuilder::new()
+        .filename("hello_world.nbt")
+        .comment("a comment")
+        .extra(vec![1, 2, 3, 4])
+        .write(vec![], Compression::default());
+    with_fields.write_all(data).unwrap();
+
+    for compressed in [plain.finish().unwrap(), with_fields.finish().unwrap()] {
+        let document = Document::load(std::io::Cursor::new(compressed)).unwrap();
+        let (name, root) = document.parse().unwrap();
+        assert_eq!(name, "hello world");
+        assert_eq!(root.get_string("name").unwrap(), "Bananrama");
+    }
+}
+
+#[test]
+pub fn decode_byte_list_signed() {
+    // A list of TAG_Byte containing 1, -1, and -128.
+    let data = vec![10, 0, 0, 9, 0, 1, b'l', 1, 0, 0, 0, 3, 1, 0xff, 0x80, 0];
+    let document = Document::load(std::io::Cursor::new(data)).unwrap();
+    let (_name, root) = document.parse().unwrap();
+    let list = root.get_list("l").unwrap();
+    let bytes = list.as_byte_list().unwrap();
+    assert_eq!(bytes.len(), 3);
+    assert_eq!(bytes.get(1), Some(-1i8));
+    assert_eq!(bytes.to_vec(), vec![1, -1, -128]);
+    assert_eq!(list.get(2), Some(Tag::Byte(-128)));
+}
+
+#[test]
+pub fn decode_entry_at_offset() {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    // Type at 3, name at 4..7, value at 7..11.
+    root.field("a").int(1);
+    // Type at 11, name at 12..20, contents at 20..33.
+    let mut nested = root.compound_field("nested");
+    // Type at 20, name at 21..24, value at 24..32, then TAG_End at 32.
+    nested.field("b").long(2);
+    nested.finish();
+    // Type at 33, name at 34..37, header at 37..42, elements at 42..50.
+    root.field("l").int_list(&[5, 6]);
+    // TAG_End at 50.
+    root.finish();
+    let document = Document::load(std::io::Cursor::new(writer.finish())).unwrap();
+
+    let path = |offset| document.entry_at_offset(offset).unwrap();
+    let owned = |names: &[&str]| Some(names.iter().map(|&s| s.to_owned()).collect());
+    assert_eq!(path(0), owned(&[]));
+    assert_eq!(path(3), owned(&["a"]));
+    assert_eq!(path(10), owned(&["a"]));
+    assert_eq!(path(11), owned(&["nested"]));
+    assert_eq!(path(26), owned(&["nested", "b"]));
+    assert_eq!(path(32), owned(&["nested"]));
+    assert_eq!(path(38), owned(&["l"]));
+    assert_eq!(path(47), owned(&["l", "1"]));
+    assert_eq!(path(50), owned(&[]));
+    assert_eq!(path(51), None);
+
+    let data = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(&data[..])).unwrap();
+    // The root is named "Level", so the first entry starts at offset 8.
+    let (_name, root) = document.parse().unwrap();
+    let first = root.entries()[0].name().to_string_lossy().into_owned();
+    assert_eq!(document.entry_at_offset(8).unwrap(), Some(vec![first]));
+}
+
+#[test]
+pub fn decode_array_to_vec_bulk() {
+    let longs = (0..100_000i64)
+        .map(|i| i.wrapping_mul(0x0123_4567_89ab_cdef))
+        .collect::<Vec<_>>();
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.field("longs").long_array(&longs);
+    root.field("shorts")
+        .short_list(&[i16::MIN, -1, 0, 1, i16::MAX]);
+    root.finish();
+    let document = Document::load(std::io::Cursor::new(writer.finish())).unwrap();
+    let (_name, root) = document.parse().unwrap();
+
+    match root.get("longs") {
+        Some(Tag::LongArray(array)) => {
+            assert_eq!(array.to_vec(), array.iter().collect::<Vec<_>>());
+            assert_eq!(array.to_vec(), longs);
+        }
+        other => panic!("expected long array, got {:?}", other),
+    }
+    let shorts = root
+        .get_list("shorts")
+        .and_then(List::as_short_list)
+        .unwrap();
+    assert_eq!(shorts.to_vec(), shorts.iter().collect::<Vec<_>>());
+}
+
+#[test]
+pub fn decode_array_to_vec_unaligned() {
+    // Varying the length of the field name moves the array data around,
+    // so both the aligned bulk copy and the element-wise fallback get
+    // used.
+    let ints = (-500..500).map(|i| i * 65_537).collect::<Vec<i32>>();
+    let doubles = (-500..500).map(|i| i as f64 / 3.0).collect::<Vec<f64>>();
+    for padding in 0..8 {
+        let mut writer = NbtWriter::new();
+        let mut root = writer.root("");
+        root.field(&"x".repeat(padding + 1)).int_array(&ints);
+        root.field(&"y".repeat(padding + 1)).double_list(&doubles);
+        root.finish();
+        let document = Document::load(std::io::Cursor::new(writer.finish())).unwrap();
+        let (_name, root) = document.parse().unwrap();
+
+        match root.get(&"x".repeat(padding + 1)) {
+            Some(Tag::IntArray(array)) => assert_eq!(array.to_vec(), ints),
+            other => panic!("expected int array, got {:?}", other),
+        }
+        let list = root
+            .get_list(&"y".repeat(padding + 1))
+            .and_then(List::as_double_list)
+            .unwrap();
+        assert_eq!(list.to_vec(), list.iter().collect::<Vec<_>>());
+        assert_eq!(list.to_vec(), doubles);
+    }
+}
+
+#[test]
+pub fn decode_open() {
+    let document = Document::open("files/hello_world.nbt").unwrap();
+    let (name, root) = document.parse().unwrap();
+    assert_eq!(name, "hello world");
+    assert_eq!(root.get_string("name").unwrap(), "Bananrama");
+
+    // Compressed files are also supported.
+    let document = Document::open("files/bigtest.nbt").unwrap();
+    let (name, _root) = document.parse().unwrap();
+    assert_eq!(name, "Level");
+
+    assert!(Document::open("files/does_not_exist.nbt").is_err());
+}
+
+#[test]
+pub fn decode_list_to_f64_array() {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.field("Rotation").double_list(&[90.0, -45.0]);
+    root.field("Motion").double_list(&[0.5, -0.08, 0.0]);
+    root.field("Ints").int_list(&[1, 2]);
+    root.finish();
+    let document = Document::load(std::io::Cursor::new(writer.finish())).unwrap();
+    let (_name, root) = document.parse().unwrap();
+
+    let rotation = root.get_list("Rotation").unwrap();
+    assert_eq!(rotation.to_f64_array::<2>(), Some([90.0, -45.0]));
+    assert_eq!(rotation.to_f64_array::<3>(), None);
+    let motion = root.get_list("Motion").unwrap();
+    assert_eq!(motion.to_f64_array::<3>(), Some([0.5, -0.08, 0.0]));
+    assert_eq!(motion.to_f64_array::<2>(), None);
+    let ints = root.get_list("Ints").unwrap();
+    assert_eq!(ints.to_f64_array::<2>(), None);
+}
+
+#[test]
+pub fn decode_parse_recovering() {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.field("first").int(1);
+    root.field("second").string("two");
+    root.field("third").long_array(&[3, 3, 3]);
+    root.finish();
+    let data = writer.finish();
+
+    let document = Document::load(std::io::Cursor::new(&data[..])).unwrap();
+    let (recovered, error) = document.parse_recovering();
+    assert!(error.is_none());
+    assert!(recovered == document.parse().unwrap().1);
+
+    // Cut off in the middle of the long array.
+    let document = Document::load(std::io::Cursor::new(&data[..data.len() - 10])).unwrap();
+    let (recovered, error) = document.parse_recovering();
+    assert_eq!(recovered.len(), 2);
+    assert!(matches!(recovered.get("first"), Some(Tag::Int(1))));
+    assert_eq!(recovered.get_string("second").unwrap(), "two");
+    let (error, offset) = error.unwrap();
+    assert!(matches!(error, ParseError::EOF { .. }));
+    // Root tag, empty name, then 1 + 2 + 5 + 4 and 1 + 2 + 6 + 2 + 3.
+    assert_eq!(offset, 3 + 12 + 14);
+}
+
+#[test]
+pub fn decode_bedrock_leveldat() {
+    #[rustfmt::skip]
+    let body: &[u8] = &[
+        10, 0, 0,
+        // LevelName: "My Wörld"
+        8, 9, 0, b'L', b'e', b'v', b'e', b'l', b'N', b'a', b'm', b'e',
+        9, 0, b'M', b'y', b' ', b'W', 0xc3, 0xb6, b'r', b'l', b'd',
+        // StorageVersion: 9
+        3, 14, 0, b'S', b't', b'o', b'r', b'a', b'g', b'e',
+        b'V', b'e', b'r', b's', b'i', b'o', b'n',
+        9, 0, 0, 0,
+        // RandomSeed: 0x0102030405060708
+        4, 10, 0, b'R', b'a', b'n', b'd', b'o', b'm', b'S', b'e', b'e', b'd',
+        8, 7, 6, 5, 4, 3, 2, 1,
+        // lastOpenedWithVersion: [1, 16]
+        9, 21, 0, b'l', b'a', b's', b't', b'O', b'p', b'e', b'n', b'e', b'd',
+        b'W', b'i', b't', b'h', b'V', b'e', b'r', b's', b'i', b'o', b'n',
+        3, 2, 0, 0, 0, 1, 0, 0, 0, 16, 0, 0, 0,
+        0,
+    ];
+    let mut data = vec![9, 0, 0, 0];
+    data.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    data.extend_from_slice(body);
+
+    let (version, document) = Document::load_bedrock_leveldat(&data[..]).unwrap();
+    assert_eq!(version, 9);
+    let (name, root) = document.parse_strict(TrailingPolicy::Forbid).unwrap();
+    assert_eq!(name, "");
+    assert_eq!(root.get_string("LevelName").unwrap(), "My Wörld");
+    assert!(matches!(root.get("StorageVersion"), Some(Tag::Int(9))));
+    assert!(matches!(
+        root.get("RandomSeed"),
+        Some(Tag::Long(0x0102030405060708))
+    ));
+    let versions = root.get_list("lastOpenedWithVersion").unwrap();
+    assert_eq!(versions.as_int_list().unwrap().to_vec(), vec![1, 16]);
+
+    // The header's length has to match the rest of the file.
+    let mut short = data.clone();
+    short.pop();
+    assert!(matches!(
+        Document::load_bedrock_leveldat(&short[..]),
+        Err(NbtError::Parse(ParseError::EOF { .. }))
+    ));
+    let mut long = data.clone();
+    long.push(0);
+    assert!(matches!(
+        Document::load_bedrock_leveldat(&long[..]),
+        Err(NbtError::Parse(ParseError::TrailingData { offset })) if offset == data.len()
+    ));
+}
+
+#[test]
+pub fn decode_error_eq() {
+    let data = include_bytes!("../files/hello_world.nbt");
+    let document = Document::load(std::io::Cursor::new(&data[..20])).unwrap();
+    assert_eq!(
+        document.parse().unwrap_err(),
+        ParseError::EOF { offset: 17 }
+    );
+
+    let document = Document::load(std::io::Cursor::new(&[0u8][..])).unwrap();
+    assert_eq!(
+        document.parse().unwrap_err(),
+        ParseError::IncorrectStartTag {
+            tag: TagType::End,
+            offset: 0
+        }
+    );
+
+    let document = Document::load(std::io::Cursor::new(&[10u8, 0, 0, 42][..])).unwrap();
+    assert_eq!(
+        document.parse().unwrap_err(),
+        ParseError::UnknownTag { tag: 42, offset: 3 }
+    );
+    assert_ne!(
+        document.parse().unwrap_err(),
+        ParseError::UnknownTag { tag: 42, offset: 4 }
+    );
+}
+
+#[test]
+pub fn decode_get_compound_or_empty() {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    let mut tag = root.compound_field("tag");
+    tag.field("Damage").int(3);
+    tag.finish();
+    root.field("Count").byte(1);
+    root.finish();
+    let document = Document::load(std::io::Cursor::new(writer.finish())).unwrap();
+    let (_name, root) = document.parse().unwrap();
+
+    let tag = root.get_compound_or_empty("tag");
+    assert!(matches!(tag.get("Damage"), Some(Tag::Int(3))));
+    let missing = root.get_compound_or_empty("display");
+    assert!(missing.is_empty());
+    // Keys with another type are treated as missing.
+    assert!(root.get_compound_or_empty("Count").is_empty());
+}
+
+#[test]
+pub fn decode_error_offsets() {
+    // Truncated in the middle of the string's contents, which start
+    // after the tag (1), name (2 + 4) and length (2).
+    let data = include_bytes!("../files/hello_world.nbt");
+    let document = Document::load(std::io::Cursor::new(&data[..30])).unwrap();
+    assert_eq!(
+        document.parse().unwrap_err(),
+        ParseError::EOF { offset: 0x17 }
+    );
+
+    // The second document's root is a TAG_Int.
+    let mut data = data.to_vec();
+    let second = data.len();
+    data.extend_from_slice(&[3, 0, 0, 0, 0, 0, 1]);
+    assert_eq!(
+        Document::split_all(&data).unwrap_err(),
+        ParseError::IncorrectStartTag {
+            tag: TagType::Int,
+            offset: second
+        }
+    );
+
+    // A list of TAG_End with 2 elements.
+    let data = vec![10, 0, 0, 9, 0, 1, b'l', 0, 0, 0, 0, 2, 0];
+    let document = Document::load(std::io::Cursor::new(data)).unwrap();
+    assert_eq!(
+        document.parse().unwrap_err(),
+        ParseError::UnexpectedEndTag { offset: 12 }
+    );
+    assert_eq!(
+        document.parse().unwrap_err().to_string(),
+        "Unexpected end tag at offset 0xc"
+    );
+}
+
+#[test]
+pub fn decode_element_type_histogram() {
+    use nobility::bin_encode::WriteValue;
+
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.field("nested").write(&WriteValue::List(vec![
+        WriteValue::List(vec![WriteValue::Int(1), WriteValue::Int(2)]),
+        WriteValue::List(vec![WriteValue::String("a")]),
+        WriteValue::List(vec![WriteValue::Int(3)]),
+        WriteValue::List(vec![]),
+    ]));
+    root.field("doubles").double_list(&[1.0, 2.0, 3.0]);
+    root.finish();
+    let document = Document::load(std::io::Cursor::new(writer.finish())).unwrap();
+    let (_name, root) = document.parse().unwrap();
+
+    let histogram = root.get_list("nested").unwrap().element_type_histogram();
+    let mut expected = [0; 13];
+    expected[TagType::Int as usize] = 2;
+    expected[TagType::String as usize] = 1;
+    expected[TagType::End as usize] = 1;
+    assert_eq!(histogram, expected);
+
+    let histogram = root.get_list("doubles").unwrap().element_type_histogram();
+    let mut expected = [0; 13];
+    expected[TagType::Double as usize] = 3;
+    assert_eq!(histogram, expected);
+}
+
+#[test]
+pub fn decode_trailing_concatenated() {
+    let hello_world = include_bytes!("../files/hello_world.nbt");
+    let mut data = hello_world.to_vec();
+    data.extend_from_slice(hello_world);
+    let document = Document::load(std::io::Cursor::new(data)).unwrap();
+
+    // The lenient parse stops after the first document.
+    assert!(document.parse().is_ok());
+    assert_eq!(
+        document.parse_strict(TrailingPolicy::Forbid).unwrap_err(),
+        ParseError::TrailingData {
+            offset: hello_world.len()
+        }
+    );
+}
+
+#[test]
+pub fn decode_outline() {
+    let data = include_bytes!("../files/hello_world.nbt");
+    let document = Document::load(std::io::Cursor::new(&data[..])).unwrap();
+    let (_name, root) = document.parse().unwrap();
+    let outline = root.outline();
+    assert!(outline.contains("name: String"));
+    assert_eq!(outline, "name: String \"Bananrama\"\n");
+
+    let data = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(&data[..])).unwrap();
+    let (_name, root) = document.parse().unwrap();
+    let outline = root.outline();
+    assert!(outline.contains("\nnested compound test: Compound (2 entries)\n  ham: Compound (2 entries)\n    name: String \"Hampus\"\n"));
+    assert!(outline.contains("listTest (long): List<Long> [11, 12, 13, 14, 15] (5 elements)\n"));
+    assert!(outline.contains(
+        "listTest (compound): List<Compound> (2 elements)\n  [0]: Compound (2 entries)\n"
+    ));
+    assert!(outline.contains(": ByteArray [0, 62, 34, 16, 8, 10, 22, 44, ...] (1000 elements)\n"));
+}
+
+#[test]
+pub fn decode_parse_any() {
+    // A root TAG_List named "list" containing two TAG_Short.
+    let data = vec![9, 0, 4, b'l', b'i', b's', b't', 2, 0, 0, 0, 2, 0, 1, 0, 2];
+    let document = Document::load(std::io::Cursor::new(data)).unwrap();
+    let (name, root) = document.parse_any().unwrap();
+    assert_eq!(name, "list");
+    let list = root.as_list().unwrap();
+    assert_eq!(list.as_short_list().unwrap().to_vec(), vec![1, 2]);
+    assert_eq!(
+        document.parse().unwrap_err(),
+        ParseError::IncorrectStartTag {
+            tag: TagType::List,
+            offset: 0
+        }
+    );
+
+    // Compound roots parse the same as with parse().
+    let data = include_bytes!("../files/hello_world.nbt");
+    let document = Document::load(std::io::Cursor::new(&data[..])).unwrap();
+    let (name, root) = document.parse_any().unwrap();
+    assert_eq!(name, "hello world");
+    assert!(root.as_compound().unwrap() == &document.parse().unwrap().1);
+
+    let document = Document::load(std::io::Cursor::new(&[0u8][..])).unwrap();
+    assert_eq!(
+        document.parse_any().unwrap_err(),
+        ParseError::UnexpectedEndTag { offset: 1 }
+    );
+}
+
+#[test]
+pub fn decode_lazy_compound() {
+    let data = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(&data[..])).unwrap();
+    let (eager_name, eager) = document.parse().unwrap();
+    let (lazy_name, lazy) = document.parse_lazy().unwrap();
+
+    assert_eq!(eager_name, lazy_name);
+    assert_eq!(lazy.len(), eager.len());
+    for (key, entry) in lazy.keys().zip(eager.iter()) {
+        assert_eq!(key, *entry.name());
+        let key = key.decode().unwrap();
+        assert!(lazy.get(&key).unwrap().unwrap() == *entry.value());
+        assert_eq!(lazy.tag_type(&key), Some(entry.value().tag_type()));
+    }
+    let nested = lazy.get("nested compound test").unwrap().unwrap();
+    assert!(Some(&nested) == eager.get("nested compound test"));
+    assert!(lazy.get("missing").is_none());
+
+    // The whole document is still validated.
+    let data = include_bytes!("../files/hello_world.nbt");
+    let document = Document::load(std::io::Cursor::new(&data[..30])).unwrap();
+    assert_eq!(
+        document.parse_lazy().unwrap_err(),
+        ParseError::EOF { offset: 0x17 }
+    );
+}
+
+#[test]
+pub fn decode_load_uncompressed() {
+    let data = include_bytes!("../files/hello_world.nbt");
+    let document = Document::load_uncompressed(&data[..]).unwrap();
+    let (name, _root) = document.parse().unwrap();
+    assert_eq!(name, "hello world");
+}
+
+#[test]
+pub fn decode_get_skipping() {
+    let data = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(&data[..])).unwrap();
+    let (_name, root) = document.parse().unwrap();
+    for key in &["longTest", "nested compound test", "doubleTest"] {
+        let skipped = document.get_skipping(key).unwrap().unwrap();
+        assert!(Some(&skipped) == root.get(key));
+    }
+    assert!(document.get_skipping("missing").unwrap().is_none());
+
+    // Corruption after the key isn't noticed.
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.field("first").int(1);
+    root.field("second").string("truncated");
+    root.finish();
+    let data = writer.finish();
+    let document = Document::load(std::io::Cursor::new(&data[..data.len() - 4])).unwrap();
+    assert!(matches!(
+        document.get_skipping("first"),
+        Ok(Some(Tag::Int(1)))
+    ));
+    assert!(matches!(
+        document.get_skipping("second"),
+        Err(ParseError::EOF { .. })
+    ));
+}
+
+#[test]
+pub fn decode_tag_eq_primitive() {
+    let data = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(&data[..])).unwrap();
+    let (_name, root) = document.parse().unwrap();
+
+    assert!(*root.get("intTest").unwrap() == 2147483647i32);
+    assert!(*root.get("byteTest").unwrap() == 127i8);
+    assert!(*root.get("shortTest").unwrap() == 32767i16);
+    assert!(*root.get("longTest").unwrap() == i64::MAX);
+    assert!(*root.get("doubleTest").unwrap() == 0.4931287132182315f64);
+    assert!(*root.get("stringTest").unwrap() == "HELLO WORLD THIS IS A TEST STRING ÅÄÖ!");
+
+    // The variant has to match, numbers aren't coerced.
+    assert!(*root.get("byteTest").unwrap() != 127i32);
+    assert!(*root.get("intTest").unwrap() != 2147483647i64);
+    assert!(*root.get("floatTest").unwrap() != 0.49823147f64);
+    assert!(*root.get("intTest").unwrap() != "2147483647");
+}
+
+#[test]
+pub fn decode_list_length_mismatch() {
+    // A TAG_List of TAG_Int claiming 3 elements, with room for 2. The
+    // trailing bytes would be read as the third element otherwise.
+    let data = vec![
+        10, 0, 0, 9, 0, 1, b'l', 3, 0, 0, 0, 3, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0,
+    ];
+    let document = Document::load(std::io::Cursor::new(data)).unwrap();
+    let expected = ParseError::ListLengthMismatch {
+        length: 3,
+        offset: 8,
+    };
+    assert_eq!(document.parse().unwrap_err(), expected);
+    assert_eq!(document.max_declared_length().unwrap_err(), expected);
+    assert!(matches!(
+        document.events().collect::<Result<Vec<_>, _>>(),
+        Err(ParseError::ListLengthMismatch {
+            length: 3,
+            offset: 8
+        })
+    ));
+}
+
+#[test]
+pub fn decode_tag_try_from() {
+    use nobility::bin_decode::TagConversionError;
+    use std::convert::TryFrom;
+
+    let data = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(&data[..])).unwrap();
+    let (_name, root) = document.parse().unwrap();
+
+    assert_eq!(
+        i32::try_from(root.get("intTest").unwrap()).unwrap(),
+        2147483647
+    );
+    assert_eq!(
+        i64::try_from(root.get("longTest").unwrap()).unwrap(),
+        i64::MAX
+    );
+    assert_eq!(
+        f32::try_from(root.get("floatTest").unwrap()).unwrap(),
+        0.49823147
+    );
+    assert_eq!(
+        String::try_from(root.get("stringTest").unwrap()).unwrap(),
+        "HELLO WORLD THIS IS A TEST STRING ÅÄÖ!"
+    );
+    assert_eq!(
+        NbtString::try_from(root.get("stringTest").unwrap()).unwrap(),
+        "HELLO WORLD THIS IS A TEST STRING ÅÄÖ!"
+    );
+
+    assert!(matches!(
+        i64::try_from(root.get("intTest").unwrap()),
+        Err(TagConversionError::Mismatch {
+            expected: TagType::Long,
+            found: TagType::Int
+        })
+    ));
+    assert!(matches!(
+        String::try_from(root.get("byteTest").unwrap()),
+        Err(TagConversionError::Mismatch {
+            expected: TagType::String,
+            found: TagType::Byte
+        })
+    ));
+
+    let invalid = Tag::String(NbtString::new(&[0xff]));
+    assert!(matches!(
+        String::try_from(&invalid),
+        Err(TagConversionError::Decode(_))
+    ));
+}
+
+#[test]
+pub fn decode_is_gzip_is_zlib() {
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use nobility::bin_decode::{is_gzip, is_zlib};
+    use std::io::Write;
+
+    let gzip = include_bytes!("../files/bigtest.nbt");
+    let raw = include_bytes!("../files/hello_world.nbt");
+    let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+    encoder.write_all(raw).unwrap();
+    let zlib = encoder.finish().unwrap();
+
+    assert!(is_gzip(gzip));
+    assert!(!is_zlib(gzip));
+    assert!(is_zlib(&zlib));
+    assert!(!is_gzip(&zlib));
+    assert!(!is_gzip(raw));
+    assert!(!is_zlib(raw));
+
+    assert!(!is_gzip(&[]));
+    assert!(!is_zlib(&[0x78]));
+}
+
+#[test]
+pub fn decode_find_sorted() {
+    let keys = [
+        "Air",
+        "Count",
+        "Health",
+        "Motion",
+        "Pos",
+        "id",
+        "slot",
+        "é",
+        "\u{1F600}",
+    ];
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    for (index, key) in keys.iter().enumerate() {
+        root.field(key).int(index as i32);
+    }
+    root.finish();
+    let data = writer.finish();
+
+    let document = Document::load(&data[..]).unwrap();
+    let (_name, root) = document.parse().unwrap();
+    for (index, key) in keys.iter().enumerate() {
+        let entry = root.find_sorted(key).unwrap();
+        assert_eq!(entry.name(), key);
+        assert_eq!(*entry.value(), index as i32);
+    }
+    assert!(root.find_sorted("Armor").is_none());
+    assert!(root.find_sorted("").is_none());
+    assert!(root.find_sorted("zzz").is_none());
+}
+
+#[test]
+pub fn decode_tag_summary() {
+    let data = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(&data[..])).unwrap();
+    let (_name, root) = document.parse().unwrap();
+
+    assert_eq!(Tag::Compound(root.clone()).summary(), "Compound(11 fields)");
+    assert_eq!(
+        root.get("longTest").unwrap().summary(),
+        "Long(9223372036854775807)"
+    );
+    assert_eq!(root.get("byteTest").unwrap().summary(), "Byte(127)");
+    assert_eq!(
+        root.get("listTest (long)").unwrap().summary(),
+        "List<Long>(5)"
+    );
+    assert_eq!(
+        root.get("listTest (compound)").unwrap().summary(),
+        "List<Compound>(2)"
+    );
+    assert_eq!(
+        root.get("stringTest").unwrap().summary(),
+        r#"String("HELLO WORLD THIS"...)"#
+    );
+    assert_eq!(
+        root.get("byteArrayTest (the first 1000 values of (n*n*255+n*7)%100, starting with n=0 (0, 62, 34, 16, 8, ...))")
+            .unwrap()
+            .summary(),
+        "ByteArray(1000)"
+    );
+}
+
+#[test]
+pub fn decode_parse_index() {
+    let data = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(&data[..])).unwrap();
+    let (name, index) = document.parse_index().unwrap();
+    assert_eq!(name, "Level");
+    assert_eq!(index.len(), 11);
+
+    let (offset, tag) = index.get("intTest").unwrap();
+    assert_eq!(tag, TagType::Int);
+    assert_eq!(
+        document.as_bytes()[offset..offset + 4],
+        2147483647i32.to_be_bytes()
+    );
+    assert_eq!(
+        index.get("nested compound test").unwrap().1,
+        TagType::Compound
+    );
+    assert_eq!(index.get("listTest (long)").unwrap().1, TagType::List);
+    assert_eq!(index.get("missing"), None);
+
+    // Keys come back in document order, matching a full parse.
+    let (_name, root) = document.parse().unwrap();
+    let keys = index.iter().map(|(key, _, _)| key).collect::<Vec<_>>();
+    let expected = root
+        .iter()
+        .map(|entry| entry.name().decode().unwrap().into_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(keys, expected);
+    for ((_, _, tag), entry) in index.iter().zip(root.iter()) {
+        assert_eq!(tag, entry.value().tag_type());
+    }
+}
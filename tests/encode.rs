@@ -1,6 +1,14 @@
+#![cfg(feature = "std")]
+// test_bigtest spells out bigtest.nbt exactly as the spec example does.
+#![allow(clippy::excessive_precision, clippy::reserve_after_initialization)]
+
 use flate2::read::GzDecoder;
-use nobility::bin_encode::NbtWriter;
-use std::io::Read;
+use nobility::bin_decode::{Document, List, Tag};
+use nobility::bin_encode::{
+    canonical_eq, save_to_path, EncodeError, HashWriter, NbtWriter, WriteValue,
+};
+use nobility::TagType;
+use std::io::{Read, Write};
 
 #[test]
 fn test_hello() {
@@ -112,3 +120,956 @@ fn test_bigtest() {
         panic!("Failed");
     }
 }
+
+#[test]
+fn test_write_tag_value_roundtrip() {
+    let input = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(input)).unwrap();
+    let (name, source) = document.parse().unwrap();
+
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root(&name.decode().unwrap());
+    for entry in source.iter() {
+        root.raw_field(entry.name().as_bytes())
+            .write_tag_value(entry.value());
+    }
+    root.finish();
+    let result = writer.finish();
+
+    let document = Document::load(std::io::Cursor::new(result)).unwrap();
+    let (result_name, result_root) = document.parse().unwrap();
+    assert_eq!(result_name, name);
+    assert!(result_root == source);
+
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("wrapper");
+    root.write_compound("Level", &source);
+    root.finish();
+    let result = writer.finish();
+
+    let document = Document::load(std::io::Cursor::new(result)).unwrap();
+    let (_name, result_root) = document.parse().unwrap();
+    assert!(result_root.get_compound("Level") == Some(&source));
+}
+
+#[test]
+fn test_write_from_with_rename() {
+    let input = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(input)).unwrap();
+    let (_name, source) = document.parse().unwrap();
+
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("Level");
+    root.write_from_with_rename(&source, |name| match name {
+        "intTest" => Some("renamedIntTest".to_owned()),
+        "byteTest" => None,
+        other => Some(other.to_owned()),
+    })
+    .unwrap();
+    root.finish();
+    let result = writer.finish();
+
+    let document = Document::load(std::io::Cursor::new(result)).unwrap();
+    let (name, root) = document.parse().unwrap();
+    assert_eq!(name, "Level");
+    assert_eq!(root.len(), source.len() - 1);
+    assert_eq!(root.get("intTest"), None);
+    assert_eq!(root.get("renamedIntTest"), Some(&Tag::Int(2147483647)));
+    assert_eq!(root.get("byteTest"), None);
+    assert_eq!(
+        root.get("nested compound test"),
+        source.get("nested compound test")
+    );
+    assert_eq!(
+        root.get("listTest (compound)"),
+        source.get("listTest (compound)")
+    );
+}
+
+#[test]
+fn test_raw_field() {
+    // 0xFF never appears in CESU-8, so this name can't be decoded.
+    let bad_name: &[u8] = b"\xFFkey";
+
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("root");
+    root.raw_field(bad_name).int(42);
+    root.field("good").string("value");
+    root.finish();
+    let original = writer.finish();
+
+    let document = Document::load(std::io::Cursor::new(&original[..])).unwrap();
+    let (name, source) = document.parse().unwrap();
+    let entries = source.entries();
+    assert_eq!(entries.len(), 2);
+    assert!(entries[0].name().decode().is_err());
+    assert_eq!(entries[0].name().as_bytes(), bad_name);
+
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root(&name.decode().unwrap());
+    for entry in entries {
+        let mut field = root.raw_field(entry.name().as_bytes());
+        match entry.value() {
+            Tag::Int(value) => field.int(*value),
+            Tag::String(value) => field.raw_string(value.as_bytes()),
+            other => panic!("unexpected tag {:?}", other),
+        }
+    }
+    root.finish();
+    let result = writer.finish();
+
+    assert_eq!(result, original);
+}
+
+#[test]
+fn test_with_capacity() {
+    let mut writer = NbtWriter::with_capacity(1024);
+    let mut root = writer.root("hello world");
+    root.field("name").string("Bananrama");
+    root.finish();
+    let result = writer.finish();
+
+    let mut expected = NbtWriter::new();
+    let mut root = expected.root("hello world");
+    root.field("name").string("Bananrama");
+    root.finish();
+    assert_eq!(result, expected.finish());
+    assert!(result.capacity() >= 1024);
+}
+
+#[test]
+fn test_list_iter() {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.field("ints").int_list_iter(0..1000);
+    root.field("bytes").byte_list_iter((-2..2).rev());
+    root.field("doubles")
+        .double_list_iter((0..3).map(f64::from));
+    root.field("empty").long_list_iter(std::iter::empty());
+    root.finish();
+    let result = writer.finish();
+
+    let document = Document::load(std::io::Cursor::new(result)).unwrap();
+    let (_name, root) = document.parse().unwrap();
+    let ints = root.get_list("ints").and_then(List::as_int_list).unwrap();
+    assert_eq!(ints.to_vec(), (0..1000).collect::<Vec<i32>>());
+    let bytes = root.get_list("bytes").and_then(List::as_byte_list).unwrap();
+    assert_eq!(bytes.to_vec(), vec![1, 0, -1, -2]);
+    let doubles = root
+        .get_list("doubles")
+        .and_then(List::as_double_list)
+        .unwrap();
+    assert_eq!(doubles.to_vec(), vec![0.0, 1.0, 2.0]);
+    let empty = root.get_list("empty").unwrap();
+    assert_eq!(empty.element_type(), TagType::Long);
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn test_array_raw_roundtrip() {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.field("ints").int_array(&[1, -2, 3]);
+    root.field("longs").long_array(&[4, -5]);
+    root.finish();
+    let original = writer.finish();
+
+    let document = Document::load(std::io::Cursor::new(&original[..])).unwrap();
+    let (_name, root) = document.parse().unwrap();
+    let ints = match root.get("ints") {
+        Some(Tag::IntArray(array)) => array.as_raw_bytes(),
+        other => panic!("expected int array, got {:?}", other),
+    };
+    assert_eq!(ints, &[0, 0, 0, 1, 0xff, 0xff, 0xff, 0xfe, 0, 0, 0, 3]);
+    let longs = match root.get("longs") {
+        Some(Tag::LongArray(array)) => array.as_raw_bytes(),
+        other => panic!("expected long array, got {:?}", other),
+    };
+
+    let mut writer = NbtWriter::new();
+    let mut copy = writer.root("");
+    copy.field("ints").int_array_raw(ints);
+    copy.field("longs").long_array_raw(longs);
+    copy.finish();
+    assert_eq!(writer.finish(), original);
+}
+
+#[test]
+#[should_panic(expected = "can't be split into 4 byte elements")]
+fn test_int_array_raw_bad_length() {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.field("ints").int_array_raw(&[0, 0, 0, 1, 0]);
+    root.finish();
+}
+
+#[test]
+fn test_long_array_tag() {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.field("longs").long_array(&[4, -5]);
+    root.finish();
+    let data = writer.finish();
+
+    let document = Document::load(std::io::Cursor::new(data)).unwrap();
+    let (_name, root) = document.parse().unwrap();
+    match root.get("longs") {
+        Some(Tag::LongArray(array)) => assert_eq!(array.to_vec(), vec![4, -5]),
+        other => panic!("expected long array, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_bool_roundtrip() {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.field("yes").bool(true);
+    root.field("no").bool(false);
+    root.field("other").byte(2);
+    root.field("int").int(1);
+    root.finish();
+    let result = writer.finish();
+
+    let document = Document::load(std::io::Cursor::new(result)).unwrap();
+    let (_name, root) = document.parse().unwrap();
+    assert_eq!(root.get("yes"), Some(&Tag::Byte(1)));
+    assert_eq!(root.get("no"), Some(&Tag::Byte(0)));
+    assert_eq!(root.get("yes").and_then(Tag::as_bool), Some(true));
+    assert_eq!(root.get("no").and_then(Tag::as_bool), Some(false));
+    assert_eq!(root.get("other").and_then(Tag::as_bool), None);
+    assert_eq!(root.get("int").and_then(Tag::as_bool), None);
+}
+
+#[test]
+fn test_finish_gzip() {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("hello world");
+    root.field("name").string("Bananrama");
+    root.finish();
+    let result = writer.finish_gzip(6);
+
+    assert_eq!(&result[0..2], &[0x1f, 0x8b]);
+    let document = Document::load(std::io::Cursor::new(result)).unwrap();
+    let (name, root) = document.parse().unwrap();
+    assert_eq!(name, "hello world");
+    assert_eq!(root.get_string("name").unwrap(), "Bananrama");
+}
+
+#[test]
+fn test_save_to_path() {
+    let input = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load(std::io::Cursor::new(input)).unwrap();
+    let (name, source) = document.parse().unwrap();
+
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root(&name.decode().unwrap());
+    for entry in source.iter() {
+        root.raw_field(entry.name().as_bytes())
+            .write_tag_value(entry.value());
+    }
+    root.finish();
+    let bytes = writer.finish();
+
+    for &compress in &[false, true] {
+        let path = std::env::temp_dir().join(format!(
+            "nobility-save-{}-{}.nbt",
+            std::process::id(),
+            compress
+        ));
+        save_to_path(&bytes, &path, compress).unwrap();
+        let raw = std::fs::read(&path).unwrap();
+        assert_eq!(raw.starts_with(&[0x1f, 0x8b]), compress);
+
+        let saved = Document::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let (saved_name, saved_root) = saved.parse().unwrap();
+        assert_eq!(saved_name, name);
+        assert!(saved_root == source);
+    }
+}
+
+#[test]
+fn test_write_value() {
+    let value = WriteValue::Compound(vec![
+        ("name", WriteValue::String("Bananrama")),
+        ("bytes", WriteValue::ByteArray(&[1, 2, 3])),
+        ("longs", WriteValue::LongArray(&[4, 5])),
+        (
+            "nested",
+            WriteValue::List(vec![
+                WriteValue::Compound(vec![("a", WriteValue::Short(1))]),
+                WriteValue::Compound(vec![("b", WriteValue::Double(2.5))]),
+            ]),
+        ),
+        ("empty", WriteValue::List(vec![])),
+    ]);
+
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("root");
+    root.field("value").write(&value);
+    root.finish();
+    let result = writer.finish();
+
+    let document = Document::load(std::io::Cursor::new(result)).unwrap();
+    let (_name, root) = document.parse().unwrap();
+    let value = root.get_compound("value").unwrap();
+    assert_eq!(value.get_string("name").unwrap(), "Bananrama");
+    assert_eq!(
+        value.get("bytes").and_then(Tag::as_byte_array),
+        Some(&[1, 2, 3][..])
+    );
+    match value.get("longs") {
+        Some(Tag::LongArray(array)) => assert_eq!(array.to_vec(), vec![4, 5]),
+        other => panic!("Expected a long array, got {:?}", other),
+    }
+    let nested = value.get_list("nested").unwrap();
+    assert_eq!(nested.len(), 2);
+    let first = nested.get(0).unwrap();
+    assert_eq!(first.as_compound().unwrap().get_i64("a"), Some(1));
+    let second = nested.get(1).unwrap();
+    assert_eq!(second.as_compound().unwrap().get_f64("b"), Some(2.5));
+    assert!(value.get_list("empty").unwrap().is_empty());
+}
+
+#[test]
+fn test_canonical_eq() {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("root");
+    root.field("a").int(1);
+    root.field("empty").int_list(&[]);
+    let mut nested = root.compound_field("nested");
+    nested.field("x").string("foo");
+    nested.field("y").byte(2);
+    nested.finish();
+    root.finish();
+    let first = writer.finish();
+
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("root");
+    let mut nested = root.compound_field("nested");
+    nested.field("y").byte(2);
+    nested.field("x").string("foo");
+    nested.finish();
+    root.field("empty").byte_list(&[]);
+    root.field("a").int(1);
+    root.finish();
+    let second = writer.finish();
+
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("root");
+    root.field("a").int(2);
+    root.field("empty").int_list(&[]);
+    let mut nested = root.compound_field("nested");
+    nested.field("x").string("foo");
+    nested.field("y").byte(2);
+    nested.finish();
+    root.finish();
+    let third = writer.finish();
+
+    assert!(canonical_eq(&first, &second).unwrap());
+    assert!(!canonical_eq(&first, &third).unwrap());
+    assert!(canonical_eq(&first, &[1, 2, 3]).is_err());
+}
+
+#[test]
+fn test_compound_list_with_count() {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("root");
+    let mut list = root.compound_list_field_with_count("list", 2);
+    for i in 0..2 {
+        let mut element = list.element();
+        element.field("i").int(i);
+        element.finish();
+    }
+    list.finish();
+    root.finish();
+    let result = writer.finish();
+
+    let document = Document::load(std::io::Cursor::new(result)).unwrap();
+    let (_name, root) = document.parse().unwrap();
+    let list = root.get_list("list").unwrap().as_compound_list().unwrap();
+    assert_eq!(list.len(), 2);
+    assert_eq!(list[1].get_i64("i"), Some(1));
+}
+
+#[test]
+fn test_compound_list_with_count_too_few() {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("root");
+    let mut list = root.compound_list_field_with_count("list", 3);
+    list.element().finish();
+    list.element().finish();
+    assert_eq!(
+        list.try_finish(),
+        Err(EncodeError::CountMismatch {
+            expected: 3,
+            actual: 2
+        })
+    );
+    root.finish();
+}
+
+#[test]
+fn test_panic_while_building() {
+    fn build(fail: bool) -> Vec<u8> {
+        let mut writer = NbtWriter::new();
+        let mut root = writer.root("root");
+        let mut list = root.compound_list_field("list");
+        let mut element = list.element();
+        element.field("value").int(1);
+        if fail {
+            // Unwinds through three unfinished builders, none of which
+            // should panic again while being dropped.
+            panic!("failed halfway through");
+        }
+        element.finish();
+        list.finish();
+        root.finish();
+        writer.finish()
+    }
+
+    assert!(std::panic::catch_unwind(|| build(false)).is_ok());
+    let err = std::panic::catch_unwind(|| build(true)).unwrap_err();
+    assert_eq!(err.downcast_ref::<&str>(), Some(&"failed halfway through"));
+}
+
+#[test]
+#[should_panic(expected = "String of 65536 bytes is too long for NBT")]
+fn test_string_too_long() {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    // The largest string that fits is fine.
+    root.field("ok").string(&"a".repeat(65535));
+    root.field("too long").string(&"a".repeat(65536));
+    root.finish();
+}
+
+#[test]
+#[should_panic(expected = "The previous element of CompoundListWriter must be finished")]
+fn test_compound_list_unfinished_element() {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    let mut list = root.compound_list_field("list");
+    let mut first = list.element();
+    first.field("value").int(1);
+    // The borrow checker prevents using the list while an element is
+    // alive, but it can still be leaked without being finished.
+    std::mem::forget(first);
+    list.element();
+}
+
+#[test]
+fn test_empty_list_roundtrip() {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.field("end").empty_list(TagType::End);
+    root.field("byte").empty_list(TagType::Byte);
+    root.field("int").empty_list(TagType::Int);
+    root.finish();
+    let original = writer.finish();
+
+    let doc = Document::load(std::io::Cursor::new(original.clone())).unwrap();
+    let (_name, source) = doc.parse().unwrap();
+    let types: Vec<_> = source
+        .iter()
+        .map(|entry| entry.value().as_list().unwrap().element_type())
+        .collect();
+    assert_eq!(types, vec![TagType::End, TagType::Byte, TagType::Int]);
+
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.write_from_with_rename(&source, |name| Some(name.to_owned()))
+        .unwrap();
+    root.finish();
+    assert_eq!(writer.finish(), original);
+}
+
+#[test]
+fn test_byte_array_from_bits() {
+    let bits: Vec<bool> = (0..11).map(|i| i % 3 == 0).collect();
+
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.field("flags")
+        .byte_array_from_bits(bits.iter().copied());
+    root.finish();
+    let data = writer.finish();
+
+    let doc = Document::load(std::io::Cursor::new(data)).unwrap();
+    let (_name, root) = doc.parse().unwrap();
+    let flags = root.get("flags").unwrap();
+    assert_eq!(flags.as_byte_array(), Some(&[0b0100_1001, 0b0000_0010][..]));
+    for (index, &bit) in bits.iter().enumerate() {
+        assert_eq!(flags.byte_array_bit(index), Some(bit));
+    }
+    // Padding bits are zero.
+    assert_eq!(flags.byte_array_bit(11), Some(false));
+}
+
+#[test]
+fn test_stream_hello() {
+    let mut cursor = std::io::Cursor::new(vec![]);
+    let mut writer = NbtWriter::new_writer(&mut cursor);
+
+    let mut root = writer.root("hello world");
+    root.field("name").string("Bananrama");
+    root.finish();
+    writer.finish_writer().unwrap();
+
+    let expected = include_bytes!("../files/hello_world.nbt");
+    assert_eq!(cursor.into_inner(), expected);
+}
+
+/// Records the size of every write, to check that output is streamed.
+#[derive(Default)]
+struct ChunkRecorder {
+    data: Vec<u8>,
+    writes: usize,
+}
+
+impl Write for ChunkRecorder {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writes += 1;
+        self.data.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn write_large(writer: &mut NbtWriter<impl Write>) {
+    let mut root = writer.root("large");
+    for i in 0..2000 {
+        root.field(&format!("field{}", i)).long(i);
+    }
+    let mut list = root.compound_list_field("list");
+    for i in 0..1000 {
+        let mut element = list.element();
+        element.field("value").int(i);
+        element.finish();
+    }
+    list.finish();
+    root.finish();
+}
+
+#[test]
+fn test_stream_large() {
+    let mut writer = NbtWriter::new();
+    write_large(&mut writer);
+    let expected = writer.finish();
+
+    let mut writer = NbtWriter::new_writer(ChunkRecorder::default());
+    write_large(&mut writer);
+    let recorder = writer.finish_writer().unwrap();
+    assert_eq!(recorder.data, expected);
+    assert!(recorder.writes > 1);
+}
+
+#[derive(Debug)]
+struct FailingWriter;
+
+impl Write for FailingWriter {
+    fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+        Err(std::io::Error::other("disk full"))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_stream_error() {
+    let mut writer = NbtWriter::new_writer(FailingWriter);
+    let mut root = writer.root("hello world");
+    root.field("name").string("Bananrama");
+    root.finish();
+    let err = writer.finish_writer().unwrap_err();
+    assert_eq!(err.to_string(), "disk full");
+}
+
+/// Unlike DefaultHasher, this gives a different result depending on
+/// how its input is split up between write() calls.
+#[derive(Default)]
+struct SplitSensitiveHasher(u64);
+
+impl std::hash::Hasher for SplitSensitiveHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0 = self.0.wrapping_mul(31).wrapping_add(bytes.len() as u64);
+        for byte in bytes {
+            self.0 = self.0.wrapping_mul(131).wrapping_add(*byte as u64);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+fn hash_buffer<H: std::hash::Hasher>(hasher: H, data: &[u8]) -> u64 {
+    let mut hash_writer = HashWriter::new(hasher);
+    hash_writer.write_all(data).unwrap();
+    hash_writer.finish()
+}
+
+#[test]
+fn test_with_hasher() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::Hasher;
+
+    fn build<W: Write>(writer: &mut NbtWriter<W>) {
+        let mut root = writer.root("hashed");
+        for i in 0..2000 {
+            root.field(&format!("field{}", i)).int(i);
+        }
+        root.finish();
+    }
+
+    let mut writer = NbtWriter::new();
+    build(&mut writer);
+    let data = writer.finish();
+    // Big enough to be streamed out in several pieces.
+    assert!(data.len() > 8192 * 2);
+
+    let mut writer = NbtWriter::with_hasher(DefaultHasher::new());
+    build(&mut writer);
+    let mut expected = DefaultHasher::new();
+    expected.write(&data);
+    assert_eq!(writer.finish_hash(), expected.finish());
+
+    let mut writer = NbtWriter::with_hasher(SplitSensitiveHasher::default());
+    build(&mut writer);
+    assert_eq!(
+        writer.finish_hash(),
+        hash_buffer(SplitSensitiveHasher::default(), &data)
+    );
+}
+
+#[test]
+fn test_with_hasher_pinned_list() {
+    // compound_list_field holds back flushing until the list is done so
+    // it can fill in the length, while compound_list_field_with_count
+    // doesn't, but both produce the same document and so the same hash.
+    fn build<W: Write>(writer: &mut NbtWriter<W>, with_count: bool) {
+        let mut root = writer.root("");
+        let mut list = if with_count {
+            root.compound_list_field_with_count("entries", 2000)
+        } else {
+            root.compound_list_field("entries")
+        };
+        for i in 0..2000 {
+            let mut element = list.element();
+            element.field("id").int(i);
+            element.field("name").string(&format!("entry {}", i));
+            element.finish();
+        }
+        list.finish();
+        root.field("after").long(42);
+        root.finish();
+    }
+
+    let mut pinned = NbtWriter::new();
+    build(&mut pinned, false);
+    let mut counted = NbtWriter::new();
+    build(&mut counted, true);
+    let data = pinned.finish();
+    assert_eq!(data, counted.finish());
+    assert!(data.len() > 8192 * 2);
+
+    let mut pinned = NbtWriter::with_hasher(SplitSensitiveHasher::default());
+    build(&mut pinned, false);
+    let mut counted = NbtWriter::with_hasher(SplitSensitiveHasher::default());
+    build(&mut counted, true);
+    let pinned = pinned.finish_hash();
+    assert_eq!(pinned, counted.finish_hash());
+    assert_eq!(pinned, hash_buffer(SplitSensitiveHasher::default(), &data));
+}
+
+#[test]
+fn test_int_array_from_roundtrip() {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.field("bounds").int_array_from([-16, 0, 32, 255]);
+    root.finish();
+    let data = writer.finish();
+
+    let document = Document::load(std::io::Cursor::new(&data[..])).unwrap();
+    let (_name, root) = document.parse().unwrap();
+    let bounds = match root.get("bounds") {
+        Some(Tag::IntArray(array)) => *array,
+        other => panic!("expected int array, got {:?}", other),
+    };
+    assert_eq!(bounds.as_array::<4>(), Some([-16, 0, 32, 255]));
+    assert_eq!(bounds.as_array::<3>(), None);
+}
+
+#[test]
+fn test_field_raw_compound() {
+    let mut piece = NbtWriter::new();
+    let mut root = piece.root("");
+    root.field("enabled").byte(1);
+    root.field("name").string("spawner");
+    root.finish();
+    let piece = piece.finish();
+
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("assembled");
+    root.field("version").int(3);
+    root.field_raw_compound("plugin", &piece[3..]);
+    root.field("after").string("still here");
+    root.finish();
+    let data = writer.finish();
+
+    let document = Document::load(std::io::Cursor::new(&data[..])).unwrap();
+    let (name, root) = document.parse().unwrap();
+    assert_eq!(name.decode().unwrap(), "assembled");
+    assert_eq!(*root.get("version").unwrap(), 3);
+    let plugin = root
+        .get("plugin")
+        .and_then(|tag| tag.as_compound())
+        .unwrap();
+    assert_eq!(*plugin.get("enabled").unwrap(), 1i8);
+    assert_eq!(*plugin.get("name").unwrap(), "spawner");
+    assert_eq!(*root.get("after").unwrap(), "still here");
+}
+
+#[test]
+#[should_panic(expected = "must end with TAG_End")]
+fn test_field_raw_compound_unterminated() {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.field_raw_compound("plugin", &[1, 0, 1, b'a', 5]);
+    root.finish();
+}
+
+#[test]
+fn test_little_endian_roundtrip() {
+    let mut writer = NbtWriter::new_little_endian();
+    let mut root = writer.root("bedrock");
+    root.field("short").short(-2);
+    root.field("int").int(0x01020304);
+    root.field("long").long(-5_000_000_000);
+    root.field("float").float(0.5);
+    root.field("double").double(-1.25);
+    root.field("name").string("日本 \u{1F600}");
+    root.field("ints").int_array(&[1, -1, 65536]);
+    root.field("longs").long_array(&[i64::MIN, 7]);
+    root.field("shorts").short_list(&[3, -3]);
+    let mut list = root.compound_list_field("entries");
+    let mut element = list.element();
+    element.field("id").int(9);
+    element.finish();
+    list.finish();
+    root.finish();
+    let data = writer.finish();
+
+    // Lengths and values are little-endian.
+    assert_eq!(&data[..5], &[10, 7, 0, b'b', b'e']);
+    assert_eq!(
+        &data[10..20],
+        &[2, 5, 0, b's', b'h', b'o', b'r', b't', 0xfe, 0xff]
+    );
+
+    let document = Document::load_little_endian(&data[..]).unwrap();
+    let (name, root) = document.parse().unwrap();
+    assert_eq!(name, "bedrock");
+    assert_eq!(*root.get("short").unwrap(), -2i16);
+    assert_eq!(*root.get("int").unwrap(), 0x01020304);
+    assert_eq!(*root.get("long").unwrap(), -5_000_000_000i64);
+    assert_eq!(*root.get("float").unwrap(), 0.5f32);
+    assert_eq!(*root.get("double").unwrap(), -1.25);
+    assert_eq!(*root.get("name").unwrap(), "日本 \u{1F600}");
+    match root.get("ints") {
+        Some(Tag::IntArray(array)) => assert_eq!(array.to_vec(), vec![1, -1, 65536]),
+        other => panic!("expected int array, got {:?}", other),
+    }
+    match root.get("longs") {
+        Some(Tag::LongArray(array)) => assert_eq!(array.to_vec(), vec![i64::MIN, 7]),
+        other => panic!("expected long array, got {:?}", other),
+    }
+    match root.get("shorts") {
+        Some(Tag::List(List::Short(list))) => assert_eq!(list.to_vec(), vec![3, -3]),
+        other => panic!("expected short list, got {:?}", other),
+    }
+    let entries = match root.get("entries") {
+        Some(Tag::List(List::Compound(list))) => list,
+        other => panic!("expected compound list, got {:?}", other),
+    };
+    assert_eq!(entries.len(), 1);
+    assert_eq!(*entries.get(0).unwrap().get("id").unwrap(), 9);
+}
+
+#[test]
+fn test_little_endian_writer() {
+    fn build<W: Write>(writer: &mut NbtWriter<W>) {
+        let mut root = writer.root("bedrock");
+        // Large enough that it's passed to the sink in several pieces.
+        for i in 0..2000 {
+            root.field(&format!("field{}", i)).int(i);
+        }
+        // The length of this list is patched in after it's finished.
+        let mut list = root.compound_list_field("entries");
+        let mut element = list.element();
+        element.field("id").long(9);
+        element.finish();
+        list.finish();
+        root.finish();
+    }
+
+    let mut writer = NbtWriter::new_little_endian();
+    build(&mut writer);
+    let expected = writer.finish();
+
+    let mut writer = NbtWriter::new_writer_little_endian(vec![]);
+    build(&mut writer);
+    let streamed = writer.finish_writer().unwrap();
+    assert_eq!(streamed, expected);
+}
+
+#[test]
+fn test_string_cesu8_roundtrip() {
+    let value = "nul\0 emoji \u{1F600} bmp \u{FFFD}";
+    assert_eq!(NbtWriter::check_string(value), Ok(()));
+
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.field("value").string(value);
+    root.finish();
+    let data = writer.finish();
+
+    let document = Document::load(&data[..]).unwrap();
+    let (_name, root) = document.parse().unwrap();
+    let string = root.get("value").unwrap().as_string().unwrap();
+    // The NUL takes 2 bytes and the emoji a 6 byte surrogate pair.
+    assert_eq!(string.len(), value.len() + 1 + 2);
+    assert!(string
+        .as_bytes()
+        .windows(2)
+        .any(|pair| pair == [0xC0, 0x80]));
+    assert!(!string.as_bytes().contains(&0));
+    assert_eq!(string.decode().unwrap(), value);
+}
+
+#[test]
+fn test_check_string() {
+    assert_eq!(NbtWriter::check_string(""), Ok(()));
+    assert_eq!(NbtWriter::check_string(&"a".repeat(65535)), Ok(()));
+    assert_eq!(
+        NbtWriter::check_string(&"a".repeat(65536)),
+        Err(EncodeError::StringTooLong { length: 65536 })
+    );
+    // Fits as UTF-8, but not once each emoji grows from 4 to 6 bytes.
+    let emoji = "\u{1F600}".repeat(11000);
+    assert_eq!(emoji.len(), 44000);
+    assert_eq!(
+        NbtWriter::check_string(&emoji),
+        Err(EncodeError::StringTooLong { length: 66000 })
+    );
+}
+
+#[test]
+fn test_reset_reuse() {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("first");
+    root.field("value").int(1);
+    root.field("padding").byte_array(&[0; 256]);
+    root.finish();
+    let first = writer.as_bytes().to_vec();
+
+    writer.reset();
+    assert!(writer.as_bytes().is_empty());
+    let mut root = writer.root("second");
+    root.field("value").int(2);
+    root.finish();
+    let second = writer.as_bytes().to_vec();
+    assert_eq!(writer.finish(), second);
+
+    let document = Document::load(&first[..]).unwrap();
+    let (name, root) = document.parse().unwrap();
+    assert_eq!(name, "first");
+    assert_eq!(*root.get("value").unwrap(), 1);
+
+    let document = Document::load(&second[..]).unwrap();
+    let (name, root) = document.parse().unwrap();
+    assert_eq!(name, "second");
+    assert_eq!(*root.get("value").unwrap(), 2);
+    assert_eq!(root.len(), 1);
+}
+
+#[test]
+fn test_reset_streaming() {
+    // A streaming writer has already passed most of the first document
+    // on to its sink by the time it's reset.
+    let mut writer = NbtWriter::new_writer(Vec::new());
+    let mut root = writer.root("first");
+    root.field("padding").byte_array(&[0; 20000]);
+    root.finish();
+
+    writer.reset();
+    let mut root = writer.root("second");
+    root.field("value").int(2);
+    root.finish();
+    let second = writer.finish();
+
+    let mut expected = NbtWriter::new();
+    let mut root = expected.root("second");
+    root.field("value").int(2);
+    root.finish();
+    assert_eq!(second, expected.finish());
+}
+
+#[test]
+fn test_primitive_list_builder() {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    let mut field = root.field("ints");
+    let mut list = field.int_list_builder();
+    assert!(list.is_empty());
+    for value in (0..20).filter(|value| value % 3 == 0) {
+        list.push(value * -1000);
+    }
+    assert_eq!(list.len(), 7);
+    list.finish();
+    let mut field = root.field("empty");
+    field.double_list_builder().finish();
+    let mut field = root.field("bytes");
+    let mut list = field.byte_list_builder();
+    list.push(-1);
+    list.push(2);
+    list.finish();
+    root.field("after").string("end");
+    root.finish();
+    let data = writer.finish();
+
+    let document = Document::load(&data[..]).unwrap();
+    let (_name, root) = document.parse().unwrap();
+    match root.get("ints") {
+        Some(Tag::List(List::Int(list))) => assert_eq!(
+            list.to_vec(),
+            vec![0, -3000, -6000, -9000, -12000, -15000, -18000]
+        ),
+        other => panic!("expected int list, got {:?}", other),
+    }
+    match root.get("empty") {
+        Some(Tag::List(List::Double(list))) => assert!(list.is_empty()),
+        other => panic!("expected double list, got {:?}", other),
+    }
+    match root.get("bytes") {
+        Some(Tag::List(List::Byte(list))) => assert_eq!(list.to_vec(), vec![-1, 2]),
+        other => panic!("expected byte list, got {:?}", other),
+    }
+    assert_eq!(*root.get("after").unwrap(), "end");
+}
+
+#[test]
+#[should_panic(expected = "finish() must be called on PrimitiveListWriter")]
+fn test_primitive_list_builder_unfinished() {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    let mut field = root.field("ints");
+    let mut list = field.int_list_builder();
+    list.push(1);
+    drop(list);
+}
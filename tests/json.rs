@@ -0,0 +1,96 @@
+#![cfg(feature = "json")]
+
+use nobility::bin_decode::{Document, Tag};
+use serde_json::json;
+
+#[test]
+fn bigtest_to_json() {
+    let data = include_bytes!("../files/bigtest.nbt");
+    let doc = Document::load(std::io::Cursor::new(&data[..])).unwrap();
+    let (_name, root) = doc.parse().unwrap();
+    let value = root.to_json();
+
+    assert_eq!(value["intTest"], json!(2147483647));
+    assert_eq!(value["byteTest"], json!(127));
+    assert_eq!(
+        value["stringTest"],
+        json!("HELLO WORLD THIS IS A TEST STRING \u{c5}\u{c4}\u{d6}!")
+    );
+    assert_eq!(value["listTest (long)"], json!([11, 12, 13, 14, 15]));
+    assert_eq!(
+        value["nested compound test"]["egg"]["name"],
+        json!("Eggbert")
+    );
+    assert_eq!(
+        value["listTest (compound)"][1]["created-on"],
+        json!(1264099775885i64)
+    );
+    // Larger than JSON can safely represent, so it's a string.
+    assert_eq!(value["longTest"], json!("9223372036854775807"));
+}
+
+#[test]
+fn long_to_json() {
+    assert_eq!(Tag::Long(1 << 53).to_json(), json!("9007199254740992"));
+    assert_eq!(
+        Tag::Long((1 << 53) - 1).to_json(),
+        json!(9007199254740991i64)
+    );
+    assert_eq!(Tag::Long(-(1 << 53)).to_json(), json!("-9007199254740992"));
+    assert_eq!(Tag::Double(f64::NAN).to_json(), json!(null));
+}
+
+#[test]
+fn json_roundtrip() {
+    use nobility::bin_encode::from_json;
+
+    let value = json!({
+        "name": "Bananrama",
+        "level": 20,
+        "created": 1264099775885i64,
+        "ratio": 0.5,
+        "scores": [1, 2, 3],
+        "positions": [1, 2.5],
+        "empty": [],
+        "nested": { "items": [{ "id": "stone" }, { "id": "dirt" }] },
+    });
+    let data = from_json("hello world", &value).unwrap();
+    let doc = Document::load(std::io::Cursor::new(&data[..])).unwrap();
+    let (name, root) = doc.parse().unwrap();
+    assert_eq!(name.decode().unwrap(), "hello world");
+
+    assert!(matches!(root.get("level"), Some(Tag::Int(20))));
+    assert!(matches!(
+        root.get("created"),
+        Some(Tag::Long(1264099775885))
+    ));
+    let mut expected = value.clone();
+    expected["positions"] = json!([1.0, 2.5]);
+    assert_eq!(root.to_json(), expected);
+}
+
+#[test]
+fn json_to_nbt_errors() {
+    use nobility::bin_encode::{from_json, JsonToNbtError};
+
+    assert_eq!(
+        from_json("", &json!([])),
+        Err(JsonToNbtError::RootNotObject)
+    );
+    assert_eq!(
+        from_json("", &json!({ "a": null })),
+        Err(JsonToNbtError::Null)
+    );
+    assert_eq!(
+        from_json("", &json!({ "a": [1, "two"] })),
+        Err(JsonToNbtError::MixedList)
+    );
+
+    let long = "\0".repeat(40000);
+    let too_long = Err(JsonToNbtError::StringTooLong { length: 80000 });
+    assert_eq!(from_json(&long, &json!({})), too_long);
+    assert_eq!(from_json("", &json!({ &long: 1 })), too_long);
+    assert_eq!(from_json("", &json!({ "a": long })), too_long);
+    assert_eq!(from_json("", &json!({ "a": { &long: 1 } })), too_long);
+    assert_eq!(from_json("", &json!({ "a": [long] })), too_long);
+}
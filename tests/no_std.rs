@@ -0,0 +1,58 @@
+//! Only uses what's available without the `std` feature. Run with
+//! `cargo test --no-default-features --test no_std` to check that the
+//! crate still builds that way.
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use nobility::bin_decode::{parse_borrowed, Cesu8DecodingError, Document, List, NbtString, Tag};
+use nobility::TagType;
+
+const HELLO_WORLD: &[u8] = include_bytes!("../files/hello_world.nbt");
+
+#[test]
+fn no_std_parse_borrowed() {
+    let (name, root) = parse_borrowed(HELLO_WORLD).unwrap();
+    assert_eq!(name, "hello world");
+    assert_eq!(root.len(), 1);
+    assert_eq!(*root.get("name").unwrap(), "Bananrama");
+    assert!(root.get("missing").is_none());
+    assert_eq!(root.get("name").unwrap().tag_type(), TagType::String);
+}
+
+#[test]
+fn no_std_document() {
+    let document = Document::from(HELLO_WORLD.to_vec());
+    let (_name, root) = document.parse_lazy().unwrap();
+    assert_eq!(root.tag_type("name"), Some(TagType::String));
+    let (_name, index) = document.parse_index().unwrap();
+    assert_eq!(index.get("name").map(|(_, tag)| tag), Some(TagType::String));
+}
+
+#[test]
+fn no_std_lists() {
+    // A compound named "" containing a list of three ints.
+    let mut data = Vec::new();
+    data.extend_from_slice(&[10, 0, 0, 9, 0, 4, b'l', b'i', b's', b't', 3]);
+    data.extend_from_slice(&[0, 0, 0, 3, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 3, 0]);
+    let (_name, root) = parse_borrowed(&data).unwrap();
+    match root.get("list") {
+        Some(Tag::List(List::Int(list))) => assert_eq!(list.to_vec(), [1, 2, 3]),
+        other => panic!("expected int list, got {:?}", other),
+    }
+}
+
+#[test]
+fn no_std_decode() {
+    let (name, _root) = parse_borrowed(HELLO_WORLD).unwrap();
+    assert_eq!(name.decode().unwrap(), "hello world");
+    assert_eq!(name.char_count(), Ok(11));
+    // A NUL and a surrogate pair for U+1F600.
+    let data = [0xC0, 0x80, 0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80];
+    let string = NbtString::new(&data);
+    assert_eq!(string.decode().unwrap(), "\0\u{1F600}");
+    let chars = string.chars().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(chars, ['\0', '\u{1F600}']);
+    assert_eq!(NbtString::new(&[0xFF]).decode(), Err(Cesu8DecodingError));
+}
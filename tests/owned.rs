@@ -0,0 +1,118 @@
+#![cfg(feature = "std")]
+
+use nobility::bin_decode::{
+    parse_owned, Document, OwnedList, OwnedTag, SharedList, SharedTag, Tag,
+};
+use nobility::bin_encode::NbtWriter;
+use std::sync::Arc;
+
+#[test]
+fn parse_owned_hello_world() {
+    let data = include_bytes!("../files/hello_world.nbt");
+    let (name, root) = parse_owned(data).expect("Parsing to succeed");
+
+    assert_eq!(name, "hello world");
+    assert_eq!(root.len(), 1);
+    assert_eq!(
+        root.get("name"),
+        Some(&OwnedTag::String("Bananrama".to_owned()))
+    );
+}
+
+#[test]
+fn parse_owned_bigtest() {
+    // bigtest.nbt is gzip compressed.
+    let data = include_bytes!("../files/bigtest.nbt");
+    let (name, root) = parse_owned(data).expect("Parsing to succeed");
+
+    assert_eq!(name, "Level");
+    assert_eq!(root.len(), 11);
+    assert_eq!(root.get("intTest"), Some(&OwnedTag::Int(2147483647)));
+}
+
+fn load_owned(data: &[u8]) -> OwnedTag {
+    let doc = Document::load(std::io::Cursor::new(data)).unwrap();
+    let (_name, root) = doc.parse().unwrap();
+    let tag = Tag::Compound(root);
+    // The document and everything borrowing from it is dropped here.
+    tag.to_owned_tag().expect("Strings to be valid")
+}
+
+#[test]
+fn owned_tag_outlives_document() {
+    let data = include_bytes!("../files/bigtest.nbt").to_vec();
+    let tag = load_owned(&data);
+    drop(data);
+
+    let root = match tag {
+        OwnedTag::Compound(root) => root,
+        other => panic!("Expected compound, got {:?}", other),
+    };
+    assert_eq!(root.get("shortTest"), Some(&OwnedTag::Short(32767)));
+    match root.get("nested compound test") {
+        Some(OwnedTag::Compound(nested)) => match nested.get("ham") {
+            Some(OwnedTag::Compound(ham)) => assert_eq!(
+                ham.get("name"),
+                Some(&OwnedTag::String("Hampus".to_owned()))
+            ),
+            other => panic!("Expected compound, got {:?}", other),
+        },
+        other => panic!("Expected compound, got {:?}", other),
+    }
+    match root.get("listTest (long)") {
+        Some(OwnedTag::List(OwnedList::Long(list))) => {
+            assert_eq!(list, &vec![11, 12, 13, 14, 15])
+        }
+        other => panic!("Expected list, got {:?}", other),
+    }
+}
+
+#[test]
+fn owned_deduplicated() {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    let mut list = root.compound_list_field("BlockEntities");
+    for _ in 0..3 {
+        let mut element = list.element();
+        element.field("id").string("minecraft:chest");
+        element.compound_list_field("Items").finish();
+        element.finish();
+    }
+    let mut element = list.element();
+    element.field("id").string("minecraft:furnace");
+    element.finish();
+    list.finish();
+    let mut copy = root.compound_field("copy");
+    copy.field("id").string("minecraft:chest");
+    copy.compound_list_field("Items").finish();
+    copy.finish();
+    root.finish();
+    let data = writer.finish();
+
+    let document = Document::load(std::io::Cursor::new(&data[..])).unwrap();
+    let (_name, root) = document.parse().unwrap();
+    let shared = root.to_owned_compound_deduplicated().unwrap();
+    assert_eq!(
+        shared.to_owned_compound(),
+        root.to_owned_compound().unwrap()
+    );
+
+    let entities = match shared.get("BlockEntities") {
+        Some(SharedTag::List(SharedList::Compound(list))) => list,
+        other => panic!("Expected compound list, got {:?}", other),
+    };
+    assert!(Arc::ptr_eq(&entities[0], &entities[1]));
+    assert!(Arc::ptr_eq(&entities[0], &entities[2]));
+    assert!(!Arc::ptr_eq(&entities[0], &entities[3]));
+    match shared.get("copy") {
+        Some(SharedTag::Compound(copy)) => assert!(Arc::ptr_eq(copy, &entities[0])),
+        other => panic!("Expected compound, got {:?}", other),
+    }
+}
+
+#[test]
+fn owned_send_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<OwnedTag>();
+    assert_send_sync::<SharedTag>();
+}
@@ -0,0 +1,107 @@
+#![cfg(feature = "std")]
+
+use nobility::bin_decode::{ParseError, Tag};
+use nobility::bin_encode::NbtWriter;
+use nobility::region::{RegionFile, RegionFileWriter};
+
+fn chunk_position(region: &RegionFile, x: i32, z: i32) -> Option<(i32, i32)> {
+    let document = region.chunk(x, z)?.unwrap();
+    let (_name, root) = document.parse().unwrap();
+    let x = match root.get("xPos") {
+        Some(Tag::Int(x)) => *x,
+        other => panic!("expected xPos, got {:?}", other),
+    };
+    let z = match root.get("zPos") {
+        Some(Tag::Int(z)) => *z,
+        other => panic!("expected zPos, got {:?}", other),
+    };
+    Some((x, z))
+}
+
+#[test]
+fn region_read_chunks() {
+    let region = RegionFile::open("files/sample.mca").unwrap();
+
+    // Zlib compressed.
+    assert_eq!(chunk_position(&region, 0, 0), Some((0, 0)));
+    // Gzip compressed.
+    assert_eq!(chunk_position(&region, 1, 0), Some((1, 0)));
+    assert_eq!(chunk_position(&region, 31, 31), Some((31, 31)));
+    // Absolute coordinates wrap around.
+    assert_eq!(chunk_position(&region, -1, 63), Some((31, 31)));
+    assert_eq!(chunk_position(&region, 5, 7), None);
+}
+
+#[test]
+fn region_corrupt_chunks() {
+    let mut data = std::fs::read("files/sample.mca").unwrap();
+    // Point chunk (2, 0) past the end of the file.
+    data[8..12].copy_from_slice(&[0, 0, 100, 1]);
+    // Change the compression scheme of chunk (0, 0).
+    data[8192 + 4] = 4;
+    let region = RegionFile::load(&data[..]).unwrap();
+
+    assert!(matches!(
+        region.chunk(2, 0),
+        Some(Err(ParseError::CorruptChunk { offset: 409600 }))
+    ));
+    assert!(matches!(
+        region.chunk(0, 0),
+        Some(Err(ParseError::UnknownCompression {
+            scheme: 4,
+            offset: 8192
+        }))
+    ));
+}
+
+#[test]
+fn region_load_short() {
+    assert!(RegionFile::load(&[][..]).unwrap().chunk(0, 0).is_none());
+    assert!(RegionFile::load(&[0; 100][..]).is_err());
+}
+
+fn encode_chunk(x: i32, z: i32, filler: usize) -> Vec<u8> {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.field("xPos").int(x);
+    root.field("zPos").int(z);
+    // Pseudo-random so that it doesn't compress well.
+    let mut state = 12345u32;
+    let filler = (0..filler)
+        .map(|_| {
+            state = state.wrapping_mul(1103515245).wrapping_add(12345);
+            (state >> 16) as u8
+        })
+        .collect::<Vec<u8>>();
+    root.field("Filler").byte_list(&filler);
+    root.finish();
+    writer.finish()
+}
+
+#[test]
+fn region_write_roundtrip() {
+    let mut writer = RegionFileWriter::new();
+    writer.chunk_with_timestamp(0, 0, 1000, &encode_chunk(0, 0, 0));
+    // Spans several sectors.
+    writer.chunk_with_timestamp(3, 4, 2000, &encode_chunk(3, 4, 10000));
+    writer.chunk_with_timestamp(31, 0, 3000, &encode_chunk(31, 0, 100));
+    writer.chunk(-1, -1, &encode_chunk(31, 31, 0));
+    let data = writer.finish();
+    assert_eq!(data.len() % 4096, 0);
+    // Header, then one sector each for three chunks and three for the
+    // large one.
+    assert_eq!(data.len(), 4096 * (2 + 3 + 3));
+
+    let region = RegionFile::load(&data[..]).unwrap();
+    assert_eq!(chunk_position(&region, 0, 0), Some((0, 0)));
+    assert_eq!(chunk_position(&region, 3, 4), Some((3, 4)));
+    assert_eq!(chunk_position(&region, 31, 0), Some((31, 0)));
+    assert_eq!(chunk_position(&region, 31, 31), Some((31, 31)));
+    assert_eq!(chunk_position(&region, 1, 1), None);
+
+    assert_eq!(region.timestamp(0, 0), Some(1000));
+    assert_eq!(region.timestamp(3, 4), Some(2000));
+    assert_eq!(region.timestamp(31, 0), Some(3000));
+    assert!(region.timestamp(31, 31).unwrap() > 3000);
+    assert_eq!(region.timestamp(1, 1), None);
+}
@@ -0,0 +1,142 @@
+#![cfg(feature = "serde")]
+
+use nobility::bin_decode::{from_document, Document};
+use nobility::bin_encode::NbtWriter;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::Cursor;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Food {
+    name: String,
+    value: f32,
+}
+
+#[derive(Debug, Deserialize)]
+struct Named<'a> {
+    name: &'a str,
+    #[serde(rename = "created-on")]
+    created_on: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct BigTest<'a> {
+    #[serde(rename = "nested compound test")]
+    nested: HashMap<String, Food>,
+    #[serde(rename = "intTest")]
+    int_test: i32,
+    #[serde(rename = "byteTest")]
+    byte_test: i8,
+    #[serde(rename = "stringTest")]
+    string_test: String,
+    #[serde(rename = "listTest (long)")]
+    list_test_long: Vec<i64>,
+    #[serde(rename = "doubleTest")]
+    double_test: f64,
+    #[serde(rename = "floatTest")]
+    float_test: f32,
+    #[serde(rename = "longTest")]
+    long_test: i64,
+    #[serde(rename = "listTest (compound)", borrow)]
+    list_test_compound: Vec<Named<'a>>,
+    #[serde(
+        rename = "byteArrayTest (the first 1000 values of (n*n*255+n*7)%100, starting with n=0 (0, 62, 34, 16, 8, ...))"
+    )]
+    byte_array_test: Vec<u8>,
+    #[serde(rename = "shortTest")]
+    short_test: i16,
+    missing: Option<i32>,
+}
+
+#[test]
+fn deserialize_bigtest() {
+    let data = include_bytes!("../files/bigtest.nbt");
+    let doc = Document::load(Cursor::new(&data[..])).unwrap();
+    let result: BigTest = from_document(&doc).expect("Deserialize to succeed");
+
+    assert_eq!(result.nested.len(), 2);
+    assert_eq!(
+        result.nested["egg"],
+        Food {
+            name: "Eggbert".to_owned(),
+            value: 0.5
+        }
+    );
+    assert_eq!(result.int_test, 2147483647);
+    assert_eq!(result.byte_test, 127);
+    assert_eq!(
+        result.string_test,
+        "HELLO WORLD THIS IS A TEST STRING \u{c5}\u{c4}\u{d6}!"
+    );
+    assert_eq!(result.list_test_long, vec![11, 12, 13, 14, 15]);
+    assert_eq!(result.double_test, 0.4931287132182315);
+    assert_eq!(result.float_test, 0.49823147);
+    assert_eq!(result.long_test, 9223372036854775807);
+    assert_eq!(result.list_test_compound.len(), 2);
+    assert_eq!(result.list_test_compound[0].name, "Compound tag #0");
+    assert_eq!(result.list_test_compound[0].created_on, 1264099775885);
+    assert_eq!(result.byte_array_test.len(), 1000);
+    assert_eq!(&result.byte_array_test[..5], &[0, 62, 34, 16, 8]);
+    assert_eq!(result.short_test, 32767);
+    assert_eq!(result.missing, None);
+}
+
+#[test]
+fn deserialize_wrong_type() {
+    #[derive(Debug, Deserialize)]
+    struct HelloWorld {
+        #[allow(dead_code)]
+        name: i32,
+    }
+
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("hello world");
+    root.field("name").string("Bananrama");
+    root.finish();
+    let doc = Document::load(Cursor::new(writer.finish())).unwrap();
+
+    assert!(from_document::<HelloWorld>(&doc).is_err());
+}
+
+#[test]
+fn deserialize_bool_and_enum() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum Mode {
+        Survival,
+        Creative,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Settings {
+        hardcore: bool,
+        mode: Mode,
+    }
+
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.field("hardcore").byte(1);
+    root.field("mode").string("Creative");
+    root.finish();
+    let doc = Document::load(Cursor::new(writer.finish())).unwrap();
+
+    let settings: Settings = from_document(&doc).unwrap();
+    assert!(settings.hardcore);
+    assert_eq!(settings.mode, Mode::Creative);
+}
+
+#[test]
+fn deserialize_byte_array_signed() {
+    #[derive(Debug, Deserialize)]
+    struct Data {
+        bytes: Vec<i8>,
+    }
+
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.field("bytes").byte_array(&[0, 1, 127, 128, 255]);
+    root.finish();
+    let doc = Document::load(Cursor::new(writer.finish())).unwrap();
+
+    let data: Data = from_document(&doc).unwrap();
+    assert_eq!(data.bytes, vec![0, 1, 127, -128, -1]);
+}
@@ -0,0 +1,144 @@
+#![cfg(feature = "std")]
+
+use nobility::bin_decode::{OwnedCompound, OwnedList, OwnedTag};
+use nobility::snbt::{self, SnbtError};
+
+fn parse_compound(input: &str) -> OwnedCompound {
+    match snbt::parse(input) {
+        Ok(OwnedTag::Compound(compound)) => compound,
+        other => panic!("Expected compound, got {:?}", other),
+    }
+}
+
+#[test]
+fn snbt_example() {
+    let root = parse_compound("{name:\"hi\",n:3b,arr:[I;1,2,3]}");
+    assert_eq!(root.len(), 3);
+    assert_eq!(root.get("name"), Some(&OwnedTag::String("hi".to_owned())));
+    assert_eq!(root.get("n"), Some(&OwnedTag::Byte(3)));
+    assert_eq!(root.get("arr"), Some(&OwnedTag::IntArray(vec![1, 2, 3])));
+}
+
+#[test]
+fn snbt_numbers() {
+    let root = parse_compound(
+        "{ a: 1, b: 1.5, c: 2s, d: -3l, e: 1.5f, f: 2d, g: .5, h: 1e3, i: 1.0e3, j: 300b, k: 07 }",
+    );
+    assert_eq!(root.get("a"), Some(&OwnedTag::Int(1)));
+    assert_eq!(root.get("b"), Some(&OwnedTag::Double(1.5)));
+    assert_eq!(root.get("c"), Some(&OwnedTag::Short(2)));
+    assert_eq!(root.get("d"), Some(&OwnedTag::Long(-3)));
+    assert_eq!(root.get("e"), Some(&OwnedTag::Float(1.5)));
+    assert_eq!(root.get("f"), Some(&OwnedTag::Double(2.0)));
+    assert_eq!(root.get("g"), Some(&OwnedTag::Double(0.5)));
+    // Without a decimal point or suffix, this isn't a number.
+    assert_eq!(root.get("h"), Some(&OwnedTag::String("1e3".to_owned())));
+    assert_eq!(root.get("i"), Some(&OwnedTag::Double(1000.0)));
+    // Out of range for a byte.
+    assert_eq!(root.get("j"), Some(&OwnedTag::String("300b".to_owned())));
+    // Leading zeroes aren't allowed.
+    assert_eq!(root.get("k"), Some(&OwnedTag::String("07".to_owned())));
+}
+
+#[test]
+fn snbt_booleans_and_suffixes() {
+    assert_eq!(snbt::parse("true"), Ok(OwnedTag::Byte(1)));
+    assert_eq!(snbt::parse("FALSE"), Ok(OwnedTag::Byte(0)));
+    assert_eq!(
+        snbt::parse("\"true\""),
+        Ok(OwnedTag::String("true".to_owned()))
+    );
+    assert_eq!(snbt::parse("1b"), Ok(OwnedTag::Byte(1)));
+    assert_eq!(snbt::parse("1B"), Ok(OwnedTag::Byte(1)));
+    assert_eq!(snbt::parse("10S"), Ok(OwnedTag::Short(10)));
+    assert_eq!(snbt::parse("-4L"), Ok(OwnedTag::Long(-4)));
+    assert_eq!(snbt::parse("3.5F"), Ok(OwnedTag::Float(3.5)));
+    assert_eq!(snbt::parse("2D"), Ok(OwnedTag::Double(2.0)));
+    assert_eq!(
+        snbt::parse("[B; true, 0b]"),
+        Ok(OwnedTag::ByteArray(vec![1, 0]))
+    );
+}
+
+#[test]
+fn snbt_strings_and_lists() {
+    let root = parse_compound(
+        r#"{"quoted key": 'it\'s', bare: minecraft.stone, list: [a, "b"], empty: [], nested: [[1], [2, 3]], arrays: [B; 1b, -1b], longs: [L;]}"#,
+    );
+    assert_eq!(
+        root.get("quoted key"),
+        Some(&OwnedTag::String("it's".to_owned()))
+    );
+    assert_eq!(
+        root.get("bare"),
+        Some(&OwnedTag::String("minecraft.stone".to_owned()))
+    );
+    assert_eq!(
+        root.get("list"),
+        Some(&OwnedTag::List(OwnedList::String(vec![
+            "a".to_owned(),
+            "b".to_owned()
+        ])))
+    );
+    assert_eq!(root.get("empty"), Some(&OwnedTag::List(OwnedList::End)));
+    assert_eq!(
+        root.get("nested"),
+        Some(&OwnedTag::List(OwnedList::List(vec![
+            OwnedList::Int(vec![1]),
+            OwnedList::Int(vec![2, 3]),
+        ])))
+    );
+    assert_eq!(root.get("arrays"), Some(&OwnedTag::ByteArray(vec![1, 255])));
+    assert_eq!(root.get("longs"), Some(&OwnedTag::LongArray(vec![])));
+}
+
+#[test]
+fn snbt_errors() {
+    assert_eq!(
+        snbt::parse("[1, 2b]"),
+        Err(SnbtError::MixedList { offset: 4 })
+    );
+    assert_eq!(
+        snbt::parse("[I;1,2b]"),
+        Err(SnbtError::InvalidArrayElement { offset: 5 })
+    );
+    assert_eq!(
+        snbt::parse("[X;1]"),
+        Err(SnbtError::UnknownArrayType {
+            found: 'X',
+            offset: 1
+        })
+    );
+    assert_eq!(
+        snbt::parse("{a:1"),
+        Err(SnbtError::UnexpectedEnd { offset: 4 })
+    );
+    assert_eq!(
+        snbt::parse("{a 1}"),
+        Err(SnbtError::UnexpectedChar {
+            found: '1',
+            offset: 3
+        })
+    );
+    assert_eq!(
+        snbt::parse("{a:}"),
+        Err(SnbtError::ExpectedValue { offset: 3 })
+    );
+    assert_eq!(
+        snbt::parse("{:1}"),
+        Err(SnbtError::ExpectedKey { offset: 1 })
+    );
+    assert_eq!(
+        snbt::parse("'a\\nb'"),
+        Err(SnbtError::InvalidEscape { offset: 2 })
+    );
+    assert_eq!(
+        snbt::parse("{} {}"),
+        Err(SnbtError::TrailingData { offset: 3 })
+    );
+    let deep = "[".repeat(1000);
+    assert!(matches!(
+        snbt::parse(&deep),
+        Err(SnbtError::DepthExceeded { .. })
+    ));
+}
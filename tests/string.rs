@@ -1,4 +1,7 @@
-use nobility::bin_decode::NbtString;
+#![cfg(feature = "std")]
+
+use nobility::bin_decode::{Document, NbtString};
+use nobility::bin_encode::NbtWriter;
 
 #[test]
 fn test_string_debug_malformed() {
@@ -7,3 +10,140 @@ fn test_string_debug_malformed() {
     let formatted = format!("{:?}", string);
     assert_eq!(formatted, r#""foo bar\" \0 \xC0""#);
 }
+
+#[test]
+fn test_empty_string_roundtrip() {
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.field("").string("");
+    root.finish();
+    let result = writer.finish();
+
+    // Every empty string is just a zero u16 length.
+    assert_eq!(result, [10, 0, 0, 8, 0, 0, 0, 0, 0]);
+
+    let document = Document::load(std::io::Cursor::new(result)).unwrap();
+    let (name, root) = document.parse().unwrap();
+    assert_eq!(name, "");
+    assert!(name.is_empty());
+    assert_eq!(*root[0].name(), "");
+    let value = root[0].value().as_string().unwrap();
+    assert_eq!(value.decode().unwrap(), "");
+}
+
+#[test]
+fn test_string_is_ascii() {
+    assert!(NbtString::new(b"DataVersion").is_ascii());
+    assert!(NbtString::new(b"").is_ascii());
+    // U+1F600 encoded as a CESU-8 surrogate pair.
+    assert!(!NbtString::new(b"\xED\xA0\xBD\xED\xB8\x80").is_ascii());
+    // Java encodes NUL as two bytes.
+    assert!(!NbtString::new(b"a\xC0\x80").is_ascii());
+}
+
+#[test]
+fn test_string_as_str() {
+    assert_eq!(NbtString::new(b"DataVersion").as_str(), Some("DataVersion"));
+    assert_eq!(
+        NbtString::new(b"\xC3\x85\xC3\x84\xC3\x96!").as_str(),
+        Some("ÅÄÖ!")
+    );
+    // U+1F600 encoded as a CESU-8 surrogate pair.
+    let string = NbtString::new(b"\xED\xA0\xBD\xED\xB8\x80");
+    assert_eq!(string.as_str(), None);
+    assert_eq!(string.decode().unwrap(), "\u{1F600}");
+    assert_eq!(NbtString::new(b"a\xC0\x80").as_str(), None);
+}
+
+#[test]
+fn test_string_lossy() {
+    let string = NbtString::new(b"foo bar\" \0 \xC0");
+    assert_eq!(string.to_string_lossy(), "foo bar\" \0 \u{FFFD}");
+
+    // Valid CESU-8 which isn't UTF-8 is still decoded.
+    let string = NbtString::new(b"\xC0\x80 \xED\xA0\xBD\xED\xB8\x80");
+    assert_eq!(string.to_string_lossy(), "\0 \u{1F600}");
+
+    // An unpaired surrogate.
+    let string = NbtString::new(b"a\xED\xA0\xBDb");
+    assert_eq!(string.to_string_lossy(), "a\u{FFFD}\u{FFFD}\u{FFFD}b");
+}
+
+#[test]
+fn test_string_display() {
+    let string = NbtString::new(b"Bananrama");
+    assert_eq!(format!("{}", string), "Bananrama");
+    assert_eq!(string.to_string(), "Bananrama");
+
+    let string = NbtString::new(b"foo \xC0 bar");
+    assert_eq!(format!("{}", string), "foo \u{FFFD} bar");
+}
+
+#[test]
+fn test_string_char_count() {
+    assert_eq!(NbtString::new(b"").char_count().unwrap(), 0);
+    assert_eq!(NbtString::new(b"hello").char_count().unwrap(), 5);
+    // "é" is 2 bytes and "日本" is 3 bytes per char.
+    assert_eq!(NbtString::new("é 日本".as_bytes()).char_count().unwrap(), 4);
+    // U+1F600 as a CESU-8 surrogate pair is 6 bytes, but one char.
+    assert_eq!(
+        NbtString::new(b"\xED\xA0\xBD\xED\xB8\x80!")
+            .char_count()
+            .unwrap(),
+        2
+    );
+    assert!(NbtString::new(b"\xC0").char_count().is_err());
+}
+
+#[test]
+fn test_string_len_and_chars() {
+    // "é" is 2 bytes, the NUL is Java's 2 byte encoding, and U+1F600
+    // is a 6 byte surrogate pair.
+    let string = NbtString::new(b"\xC3\xA9\xC0\x80\xED\xA0\xBD\xED\xB8\x80!");
+    assert_eq!(string.len(), 11);
+    assert!(!string.is_empty());
+    let chars = string.chars().collect::<Result<Vec<char>, _>>().unwrap();
+    assert_eq!(chars, vec!['é', '\0', '\u{1F600}', '!']);
+
+    assert_eq!(NbtString::new(b"").len(), 0);
+    assert!(NbtString::new(b"").is_empty());
+    assert_eq!(NbtString::new(b"").chars().count(), 0);
+
+    // Invalid bytes produce an error, then decoding continues.
+    let results = NbtString::new(b"a\xC0b").chars().collect::<Vec<_>>();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].as_ref().ok(), Some(&'a'));
+    assert!(results[1].is_err());
+    assert_eq!(results[2].as_ref().ok(), Some(&'b'));
+}
+
+#[test]
+fn test_owned_string_roundtrip() {
+    use nobility::bin_decode::OwnedNbtString;
+
+    let owned: OwnedNbtString = {
+        let data = b"foo \xC0 bar".to_vec();
+        let string = NbtString::new(&data);
+        string.to_owned()
+    };
+    assert!(owned.decode().is_err());
+    assert_eq!(owned.as_bytes(), b"foo \xC0 bar");
+    assert_eq!(owned.to_string_lossy(), "foo \u{FFFD} bar");
+    assert_eq!(format!("{:?}", owned), r#""foo \xC0 bar""#);
+
+    // Writing it back out preserves the invalid bytes.
+    let mut writer = NbtWriter::new();
+    let mut root = writer.root("");
+    root.field("name").raw_string(owned.as_bytes());
+    root.finish();
+    let data = writer.finish();
+    let document = Document::load(&data[..]).unwrap();
+    let (_name, root) = document.parse().unwrap();
+    let string = root.get("name").unwrap().as_string().unwrap();
+    assert_eq!(string, owned);
+    assert_eq!(owned.as_nbt_string(), string);
+
+    let valid = NbtString::new("日本".as_bytes()).to_owned();
+    assert_eq!(valid.decode().unwrap(), "日本");
+    assert_eq!(valid.into_bytes(), "日本".as_bytes());
+}
@@ -0,0 +1,66 @@
+use nobility::TagType;
+
+const ALL_TAGS: [TagType; 13] = [
+    TagType::End,
+    TagType::Byte,
+    TagType::Short,
+    TagType::Int,
+    TagType::Long,
+    TagType::Float,
+    TagType::Double,
+    TagType::ByteArray,
+    TagType::String,
+    TagType::List,
+    TagType::Compound,
+    TagType::IntArray,
+    TagType::LongArray,
+];
+
+#[test]
+fn tag_type_from_u8() {
+    for tag in ALL_TAGS.iter() {
+        assert_eq!(TagType::from_u8(*tag as u8), Some(*tag));
+    }
+    assert_eq!(TagType::from_u8(13), None);
+    assert_eq!(TagType::from_u8(255), None);
+}
+
+#[test]
+fn tag_type_name() {
+    assert_eq!(TagType::End.name(), "TAG_End");
+    assert_eq!(TagType::Byte.name(), "TAG_Byte");
+    assert_eq!(TagType::ByteArray.name(), "TAG_Byte_Array");
+    assert_eq!(TagType::LongArray.name(), "TAG_Long_Array");
+    for tag in ALL_TAGS.iter() {
+        assert!(tag.name().starts_with("TAG_"));
+    }
+}
+
+#[test]
+fn tag_type_classification() {
+    for tag in ALL_TAGS.iter() {
+        let expected = match tag {
+            TagType::End => (false, false, false, None),
+            TagType::Byte => (true, false, false, Some(1)),
+            TagType::Short => (true, false, false, Some(2)),
+            TagType::Int => (true, false, false, Some(4)),
+            TagType::Long => (true, false, false, Some(8)),
+            TagType::Float => (true, false, false, Some(4)),
+            TagType::Double => (true, false, false, Some(8)),
+            TagType::ByteArray => (false, true, false, None),
+            TagType::String => (false, false, false, None),
+            TagType::List => (false, false, true, None),
+            TagType::Compound => (false, false, true, None),
+            TagType::IntArray => (false, true, false, None),
+            TagType::LongArray => (false, true, false, None),
+            _ => unreachable!(),
+        };
+        let actual = (
+            tag.is_primitive(),
+            tag.is_array(),
+            tag.is_container(),
+            tag.element_size(),
+        );
+        assert_eq!(actual, expected, "{:?}", tag);
+    }
+}
@@ -0,0 +1,24 @@
+#![cfg(feature = "tokio")]
+
+use nobility::bin_decode::Document;
+
+#[tokio::test]
+async fn load_async_plain() {
+    let data = include_bytes!("../files/hello_world.nbt");
+    let document = Document::load_async(std::io::Cursor::new(&data[..]))
+        .await
+        .unwrap();
+    let (name, root) = document.parse().unwrap();
+    assert_eq!(name, "hello world");
+    assert_eq!(*root.get("name").unwrap(), "Bananrama");
+}
+
+#[tokio::test]
+async fn load_async_gzip() {
+    let data = include_bytes!("../files/bigtest.nbt");
+    let document = Document::load_async(&data[..]).await.unwrap();
+    let expected = Document::load(&data[..]).unwrap();
+    assert_eq!(document.as_bytes(), expected.as_bytes());
+    let (name, _root) = document.parse().unwrap();
+    assert_eq!(name, "Level");
+}
@@ -0,0 +1,21 @@
+use nobility::{uuid_ints_to_longs, uuid_longs_to_ints};
+
+#[test]
+fn uuid_int_long_roundtrip() {
+    // f81d4fae-7dec-11d0-a765-00a0c91e6bf6
+    let ints = [
+        0xf81d4faeu32 as i32,
+        0x7dec11d0,
+        0xa76500a0u32 as i32,
+        0xc91e6bf6u32 as i32,
+    ];
+    let longs = [0xf81d4fae7dec11d0u64 as i64, 0xa76500a0c91e6bf6u64 as i64];
+
+    assert_eq!(uuid_ints_to_longs(ints), longs);
+    assert_eq!(uuid_longs_to_ints(longs), ints);
+    assert_eq!(uuid_longs_to_ints(uuid_ints_to_longs(ints)), ints);
+
+    assert_eq!(uuid_ints_to_longs([0; 4]), [0; 2]);
+    assert_eq!(uuid_ints_to_longs([-1; 4]), [-1; 2]);
+    assert_eq!(uuid_longs_to_ints([-1; 2]), [-1; 4]);
+}